@@ -0,0 +1,70 @@
+//! 构建脚本
+//!
+//! 注入构建信息环境变量，供`info`子命令排查问题时使用
+
+use std::process::Command;
+
+fn main() {
+    // git提交号，非git环境下为unknown
+    let git_commit = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=TRANSLATION_CLI_GIT_COMMIT={}", git_commit);
+
+    // 启用的feature
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase()))
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=TRANSLATION_CLI_FEATURES={}", features.join(","));
+
+    // 依赖的monolith版本，从Cargo.lock读取实际解析的版本
+    let monolith_version = std::fs::read_to_string("Cargo.lock")
+        .ok()
+        .and_then(|lock| {
+            let mut lines = lock.lines();
+            while let Some(line) = lines.next() {
+                if line.trim() == "name = \"monolith\"" {
+                    return lines.next().and_then(|version_line| {
+                        version_line
+                            .trim()
+                            .strip_prefix("version = \"")
+                            .and_then(|v| v.strip_suffix('"'))
+                            .map(str::to_string)
+                    });
+                }
+            }
+            None
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=TRANSLATION_CLI_MONOLITH_VERSION={}", monolith_version);
+
+    // 目标平台、构建配置和编译器版本
+    println!(
+        "cargo:rustc-env=TRANSLATION_CLI_TARGET={}",
+        std::env::var("TARGET").unwrap_or_default()
+    );
+    println!(
+        "cargo:rustc-env=TRANSLATION_CLI_PROFILE={}",
+        std::env::var("PROFILE").unwrap_or_default()
+    );
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=TRANSLATION_CLI_RUSTC_VERSION={}", rustc_version);
+
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+}
@@ -0,0 +1,51 @@
+//! 大文档译文回写阶段的耗时基准
+//!
+//! 生成含大量段落、链接和属性的HTML，提取文本后构造译文，统计
+//! `apply_translations_to_dom_with_options`的耗时。用release模式运行：
+//! `cargo run --release --example bench_apply_translations -- 20000 > /dev/null`
+
+use std::time::Instant;
+
+use html5ever::parse_document;
+use html5ever::tendril::TendrilSink;
+use markup5ever_rcdom::RcDom;
+use translation_cli::html_processor::{
+    apply_translations_to_dom_with_options, extract_translatable_texts_with_options, HtmlProcessingOptions,
+};
+
+fn main() {
+    let sections: usize = std::env::args().nth(1).and_then(|arg| arg.parse().ok()).unwrap_or(20_000);
+
+    let mut html = String::from("<html><head><title>Benchmark</title></head><body>");
+    for index in 0..sections {
+        html.push_str(&format!(
+            "<section><h2 title=\"Section tooltip {index}\">Heading {index}</h2>\
+             <p>Paragraph {index} with <a href=\"/page/{index}\">link text {index}</a> and more words.</p>\
+             <img src=\"/img/{index}.png\" alt=\"Image {index}\"><div><span>Shared footer text</span></div></section>"
+        ));
+    }
+    html.push_str("</body></html>");
+
+    let options = HtmlProcessingOptions::default();
+    let parse = || parse_document(RcDom::default(), Default::default()).from_utf8().read_from(&mut html.as_bytes()).unwrap();
+
+    let texts = extract_translatable_texts_with_options(&parse(), &options);
+    let translations: Vec<String> = texts.iter().map(|text| format!("译文：{}", text)).collect();
+
+    let mut durations = Vec::new();
+    for _ in 0..3 {
+        let dom = parse();
+        let start = Instant::now();
+        let dom = apply_translations_to_dom_with_options(dom, &texts, &translations, &options).unwrap();
+        durations.push(start.elapsed());
+        drop(dom);
+    }
+    durations.sort();
+
+    eprintln!(
+        "文档 {} KB，{} 条文本，回写耗时（3次取中位数）: {:.1} ms",
+        html.len() / 1024,
+        texts.len(),
+        durations[1].as_secs_f64() * 1000.0
+    );
+}
@@ -46,8 +46,12 @@ async fn test_web_crawler() -> Result<()> {
         include_css: true,
         include_js: false,
         include_images: false,
+        embed_fonts: true,
         user_agent: "translation-cli-test/0.1.0".to_string(),
         timeout: 10,
+        max_resource_concurrency: 4,
+        page_cache: None,
+        host_throttle: None,
     };
 
     // 创建爬虫实例
@@ -1,6 +1,6 @@
-/// 翻译API配置常量
-/// 
-/// 该文件定义了所有翻译服务相关的常量配置，方便统一管理和维护
+//! 翻译API配置常量
+//!
+//! 该文件定义了所有翻译服务相关的常量配置，方便统一管理和维护
 
 /// 默认翻译API配置
 pub mod api_config {
@@ -10,6 +10,12 @@ pub mod api_config {
     /// 本地开发API地址
     pub const LOCAL_API_URL: &str = "http://localhost:1188/translate";
     
+    /// Azure Translator全局端点
+    pub const AZURE_API_URL: &str = "https://api.cognitive.microsofttranslator.com/translate";
+    
+    /// Azure Translator API版本
+    pub const AZURE_API_VERSION: &str = "3.0";
+    
     /// 备用API地址列表
     pub const BACKUP_API_URLS: &[&str] = &[
         "https://api.deepl.com/v2/translate",
@@ -45,6 +51,15 @@ pub mod service_config {
     
     /// 请求超时时间（秒）
     pub const REQUEST_TIMEOUT_SECONDS: u64 = 30;
+    
+    /// TCP keep-alive探测间隔（秒）
+    pub const TCP_KEEPALIVE_SECONDS: u64 = 60;
+    
+    /// 连接池中空闲连接的保留时间（秒）
+    pub const POOL_IDLE_TIMEOUT_SECONDS: u64 = 90;
+    
+    /// 翻译请求重试退避基数（毫秒）
+    pub const RETRY_DELAY_BASE_MS: u64 = 500;
 }
 
 /// 网页爬取配置
@@ -63,6 +78,9 @@ pub mod crawler_config {
     
     /// 最大页面大小（字节）
     pub const MAX_PAGE_SIZE_BYTES: usize = 50 * 1024 * 1024; // 50MB
+    
+    /// 默认最大并发资源下载数
+    pub const DEFAULT_MAX_RESOURCE_CONCURRENCY: usize = 4;
 }
 
 /// 错误消息常量
@@ -96,6 +114,12 @@ pub mod performance_config {
     
     /// 最大并发连接数
     pub const MAX_CONCURRENT_CONNECTIONS: usize = 10;
+
+    /// 启用`--stream-large`时超过该大小的文档分块翻译（字节）
+    pub const STREAM_LARGE_THRESHOLD_BYTES: usize = 8 * 1024 * 1024; // 8MB
+
+    /// 分块翻译时每块的目标大小（字节）
+    pub const STREAM_CHUNK_SIZE_BYTES: usize = 1024 * 1024; // 1MB
 }
 
 /// 实用工具函数
@@ -0,0 +1,148 @@
+//! 品牌词大小写保护模块
+//!
+//! 翻译API常把iPhone、eBay、GitHub等有特定大小写的品牌词改写成`Iphone`、`Ebay`。
+//! 内置一份常见品牌词列表作为轻量的默认术语表：提取时识别原文中出现的品牌词，
+//! 翻译后将译文中大小写不同的同一单词强制还原为正确形式
+
+// 标准库导入
+use std::ops::Range;
+use std::sync::OnceLock;
+
+// 第三方crate导入
+use regex::Regex;
+
+/// 内置的常见品牌词（正确大小写形式）
+pub const BUILTIN_BRANDS: &[&str] = &[
+    "iPhone", "iPad", "iPod", "iMac", "iOS", "iPadOS", "macOS", "watchOS", "tvOS", "iCloud",
+    "iTunes", "iMessage", "FaceTime", "AirPods", "MacBook", "AirDrop", "eBay", "PayPal",
+    "GitHub", "GitLab", "LinkedIn", "YouTube", "WhatsApp", "TikTok", "WeChat", "QQ", "JavaScript",
+    "TypeScript", "PostgreSQL", "MySQL", "MongoDB", "SQLite", "Node.js", "npm", "jQuery",
+    "WordPress", "PlayStation", "Xbox", "OpenAI", "ChatGPT", "DeepL", "DeepSeek", "AliExpress",
+    "McDonald's", "IKEA", "BMW", "IBM", "AMD", "NVIDIA", "HarmonyOS", "OnePlus", "DuckDuckGo",
+    "SoundCloud", "Dropbox", "OneDrive", "PowerPoint", "Wi-Fi", "USB-C", "HTTPS",
+];
+
+/// 判断字节是否为单词字符，用于确定品牌词边界
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// 在已转小写的文本中查找品牌词（小写形式）的所有整词出现位置
+fn find_word_occurrences(lowered: &str, needle: &str) -> Vec<usize> {
+    let bytes = lowered.as_bytes();
+    lowered
+        .match_indices(needle)
+        .map(|(offset, _)| offset)
+        .filter(|&offset| {
+            let end = offset + needle.len();
+            let before_ok = offset == 0 || !is_word_byte(bytes[offset - 1]);
+            let after_ok = end == bytes.len() || !is_word_byte(bytes[end]);
+            before_ok && after_ok
+        })
+        .collect()
+}
+
+/// 识别原文中出现的内置品牌词（不区分大小写，按整词匹配）
+pub fn detect_brands(text: &str) -> Vec<&'static str> {
+    let lowered = text.to_ascii_lowercase();
+    BUILTIN_BRANDS
+        .iter()
+        .copied()
+        .filter(|brand| !find_word_occurrences(&lowered, &brand.to_ascii_lowercase()).is_empty())
+        .collect()
+}
+
+/// 找出URL、域名、文件路径以及`{{S0}}`/`{{P0}}`占位符所在的字节区间
+///
+/// 这些位置区分大小写（如`github.com`、`/opt/iphone`），其中的品牌词不能改写。
+fn protected_spans(text: &str) -> Vec<Range<usize>> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?i)[a-z][a-z0-9+.-]*://[\x21-\x7e]+",
+            r"|\bwww\.[\x21-\x7e]+",
+            r"|\b(?:[a-z0-9-]+\.)+(?:com|net|org|io|dev|app|ai|co|cn|jp|uk|de)\b[\x21-\x7e]*",
+            r"|\{\{\s*[SP]\s*\d+\s*\}\}",
+            r"|(?:\.{1,2}|~)?/[\x21-\x7e]+",
+            r"|\b[a-z]:\\[\x21-\x7e]*",
+        ))
+        .expect("受保护区间正则无效")
+    });
+    pattern
+        .find_iter(text)
+        .filter(|found| {
+            // 以`/`开头的路径必须从词首开始，避免把`iOS/iPadOS`这样的并列写法当成路径
+            !found.as_str().starts_with(['/', '.', '~'])
+                || !text[..found.start()].chars().next_back().is_some_and(char::is_alphanumeric)
+        })
+        .map(|found| found.range())
+        .collect()
+}
+
+/// 将译文中原文出现过的品牌词还原为正确大小写
+///
+/// 品牌词均为ASCII，转小写不改变字节长度，可直接按偏移原位替换；
+/// URL、域名、文件路径和占位符中的出现保持原样。
+pub fn restore_brand_casing(original: &str, translation: &str) -> String {
+    let brands = detect_brands(original);
+    if brands.is_empty() || translation.is_empty() {
+        return translation.to_string();
+    }
+
+    let protected = protected_spans(translation);
+    let lowered = translation.to_ascii_lowercase();
+    let mut restored = translation.to_string();
+    for brand in brands {
+        for offset in find_word_occurrences(&lowered, &brand.to_ascii_lowercase()) {
+            if protected.iter().any(|span| span.contains(&offset)) {
+                continue;
+            }
+            restored.replace_range(offset..offset + brand.len(), brand);
+        }
+    }
+    restored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_brand_casing() {
+        assert_eq!(
+            restore_brand_casing("Buy an iPhone on eBay", "在Ebay上购买Iphone"),
+            "在eBay上购买iPhone"
+        );
+        assert_eq!(
+            restore_brand_casing("Star us on GitHub", "在 Github 上给我们点星"),
+            "在 GitHub 上给我们点星"
+        );
+    }
+
+    #[test]
+    fn test_only_whole_words_from_original_are_restored() {
+        // 原文没有的品牌词不改动
+        assert_eq!(restore_brand_casing("Hello", "Ebay 你好"), "Ebay 你好");
+        // 非整词出现不识别
+        assert!(detect_brands("iphones and ebayer").is_empty());
+        assert_eq!(detect_brands("Use NPM or node.js"), vec!["Node.js", "npm"]);
+    }
+
+    #[test]
+    fn test_urls_paths_and_placeholders_keep_their_casing() {
+        assert_eq!(
+            restore_brand_casing(
+                "Star GitHub at https://github.com/org/repo or github.com",
+                "在Github上点星：https://github.com/org/repo 或 github.com"
+            ),
+            "在GitHub上点星：https://github.com/org/repo 或 github.com"
+        );
+        assert_eq!(
+            restore_brand_casing("Copy the iPhone backup to ~/backups/iphone", "将Iphone备份复制到 ~/backups/iphone"),
+            "将iPhone备份复制到 ~/backups/iphone"
+        );
+        assert_eq!(restore_brand_casing("QQ {{S0}}", "Qq {{ s0 }} qq"), "QQ {{ s0 }} QQ");
+        // 并列写法中的斜杠不视为路径
+        assert_eq!(restore_brand_casing("iOS/iPadOS", "Ios/Ipados"), "iOS/iPadOS");
+    }
+}
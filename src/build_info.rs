@@ -0,0 +1,53 @@
+//! 构建信息模块
+//!
+//! 提供由`build.rs`在编译时注入的版本、git提交、feature等信息
+
+/// crate版本号
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 编译时的git提交号
+pub const GIT_COMMIT: &str = env!("TRANSLATION_CLI_GIT_COMMIT");
+
+/// 启用的feature，逗号分隔
+pub const FEATURES: &str = env!("TRANSLATION_CLI_FEATURES");
+
+/// 依赖的monolith版本
+pub const MONOLITH_VERSION: &str = env!("TRANSLATION_CLI_MONOLITH_VERSION");
+
+/// 目标平台
+pub const TARGET: &str = env!("TRANSLATION_CLI_TARGET");
+
+/// 构建配置（debug/release）
+pub const PROFILE: &str = env!("TRANSLATION_CLI_PROFILE");
+
+/// 编译器版本
+pub const RUSTC_VERSION: &str = env!("TRANSLATION_CLI_RUSTC_VERSION");
+
+/// 生成详细构建信息文本
+pub fn build_info_report() -> String {
+    let features = if FEATURES.is_empty() { "无" } else { FEATURES };
+
+    [
+        format!("translation-cli {}", VERSION),
+        format!("git提交:     {}", GIT_COMMIT),
+        format!("启用feature: {}", features),
+        format!("monolith:    {}", MONOLITH_VERSION),
+        format!("目标平台:    {}", TARGET),
+        format!("构建配置:    {}", PROFILE),
+        format!("编译器:      {}", RUSTC_VERSION),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info_report() {
+        let report = build_info_report();
+        assert!(report.starts_with(&format!("translation-cli {}", VERSION)));
+        assert!(report.contains(MONOLITH_VERSION));
+        assert!(!GIT_COMMIT.is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+//! 翻译API熔断器模块
+//!
+//! API持续失败时继续发请求只会浪费时间并加重对端故障。连续失败达到阈值后熔断器「打开」，
+//! 冷却期内的请求立即失败；冷却结束后进入「半开」状态，只放行一个探测请求，
+//! 探测成功则恢复正常，失败则重新打开
+
+// 标准库导入
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// 第三方crate导入
+use tracing::{info, warn};
+
+// 本地模块导入
+use crate::error::TranslationError;
+
+/// 熔断器
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// 打开熔断所需的连续失败次数
+    threshold: usize,
+    /// 打开后暂停请求的时长
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+/// 熔断器的可变状态
+#[derive(Debug, Default)]
+struct BreakerState {
+    /// 当前连续失败次数
+    consecutive_failures: usize,
+    /// 熔断打开的时刻，`None`表示关闭
+    opened_at: Option<Instant>,
+    /// 半开状态下探测请求的发出时刻；探测请求被取消时，超过冷却时长后允许再次探测
+    probe_started: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// 创建熔断器，`threshold`为打开熔断所需的连续失败次数（至少为1）
+    pub fn new(threshold: usize, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// 打开熔断所需的连续失败次数
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// 熔断打开后的冷却时长
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// 请求发出前检查：关闭时放行；打开且仍在冷却期内时返回错误；
+    /// 冷却结束后只放行一个探测请求，其余请求在探测结果出来前继续失败
+    pub fn check(&self) -> Result<(), TranslationError> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(opened_at) = state.opened_at else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        let cooling_down = now.duration_since(opened_at) < self.cooldown;
+        let probing = state
+            .probe_started
+            .is_some_and(|started| now.duration_since(started) < self.cooldown);
+        if cooling_down || probing {
+            let retry_after = self.cooldown.saturating_sub(now.duration_since(opened_at));
+            return Err(TranslationError::CircuitOpen {
+                consecutive_failures: state.consecutive_failures,
+                retry_after,
+            });
+        }
+
+        info!("🔌 熔断冷却结束，发送探测请求");
+        state.probe_started = Some(now);
+        Ok(())
+    }
+
+    /// 记录一次成功请求，关闭熔断并清零失败计数
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.opened_at.is_some() {
+            info!("✅ 探测请求成功，熔断已恢复");
+        }
+        *state = BreakerState::default();
+    }
+
+    /// 记录一次失败请求；达到阈值或半开探测失败时打开熔断
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.consecutive_failures += 1;
+        let probe_failed = state.probe_started.take().is_some();
+        if probe_failed || (state.opened_at.is_none() && state.consecutive_failures >= self.threshold) {
+            warn!(
+                "🔌 翻译API连续失败 {} 次，熔断 {:?}，期间请求将立即失败",
+                state.consecutive_failures, self.cooldown
+            );
+            state.opened_at = Some(Instant::now());
+        }
+    }
+
+    /// 熔断当前是否处于打开状态（含半开）
+    pub fn is_open(&self) -> bool {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).opened_at.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+        breaker.record_failure();
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        breaker.record_failure();
+        assert!(breaker.check().is_ok());
+
+        breaker.record_failure();
+        assert!(breaker.is_open());
+        match breaker.check() {
+            Err(TranslationError::CircuitOpen { consecutive_failures, .. }) => assert_eq!(consecutive_failures, 3),
+            other => panic!("熔断应处于打开状态: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_half_open_probe() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+        assert!(breaker.check().is_err());
+
+        // 冷却结束后只放行一个探测请求，探测失败重新打开
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_err());
+        breaker.record_failure();
+        assert!(breaker.check().is_err());
+
+        // 再次冷却后探测成功，熔断关闭
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.check().is_ok());
+        breaker.record_success();
+        assert!(!breaker.is_open());
+        assert!(breaker.check().is_ok());
+    }
+}
@@ -0,0 +1,255 @@
+//! 翻译覆盖率报告模块
+//!
+//! 复制原页面结构，按提取和应用阶段的结果给文本着色：已翻译为绿色、
+//! 提取到但API返回空译文为红色、被过滤跳过为灰色，直观呈现翻译是否完整
+
+// 标准库导入
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+// 第三方crate导入
+use anyhow::{Context, Result};
+use html5ever::parse_document;
+use html5ever::tendril::TendrilSink;
+use html5ever::{ns, Attribute, LocalName, QualName};
+use markup5ever_rcdom::{Handle, Node, NodeData, RcDom};
+
+// 本地模块导入
+use crate::html_processor::serialize_dom_to_html;
+
+/// 内容不作为页面文本渲染的元素，其中的文本不着色
+const NON_RENDERED_ELEMENTS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "title", "textarea", "xmp", "iframe",
+    "noembed", "noframes",
+];
+
+/// 报告中注入的样式
+const COVERAGE_STYLE: &str = "\
+.tc-coverage-translated{background:#c8f7c5;}\
+.tc-coverage-untranslated{background:#f9c0c0;}\
+.tc-coverage-skipped{background:#e0e0e0;color:#666;}\
+.tc-coverage-legend{position:sticky;top:0;z-index:2147483647;padding:6px 12px;\
+font:14px/1.5 sans-serif;background:#fff;border-bottom:1px solid #ccc;}";
+
+/// 文本覆盖状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageStatus {
+    /// 已翻译
+    Translated,
+    /// 提取到但译文为空
+    Untranslated,
+    /// 未提取（被过滤跳过）
+    Skipped,
+}
+
+impl CoverageStatus {
+    fn class_name(self) -> &'static str {
+        match self {
+            Self::Translated => "tc-coverage-translated",
+            Self::Untranslated => "tc-coverage-untranslated",
+            Self::Skipped => "tc-coverage-skipped",
+        }
+    }
+}
+
+/// 覆盖率统计（按页面文本节点计数）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoverageSummary {
+    /// 已翻译的文本节点数
+    pub translated: usize,
+    /// 提取到但未翻译的文本节点数
+    pub untranslated: usize,
+    /// 被过滤跳过的文本节点数
+    pub skipped: usize,
+}
+
+impl CoverageSummary {
+    fn record(&mut self, status: CoverageStatus) {
+        match status {
+            CoverageStatus::Translated => self.translated += 1,
+            CoverageStatus::Untranslated => self.untranslated += 1,
+            CoverageStatus::Skipped => self.skipped += 1,
+        }
+    }
+}
+
+/// 生成覆盖率报告HTML
+///
+/// `originals`与`translations`为提取到的文本及其译文（翻译失败时为空字符串）；
+/// 页面中未出现在`originals`里的非空白文本视为被过滤跳过。
+pub fn render_coverage_report(
+    html: &str,
+    originals: &[String],
+    translations: &[String],
+) -> Result<(String, CoverageSummary)> {
+    let dom = parse_document(RcDom::default(), Default::default())
+        .from_utf8()
+        .read_from(&mut html.as_bytes())
+        .context("覆盖率报告解析原页面失败")?;
+
+    let translated: HashMap<&str, &str> = originals
+        .iter()
+        .zip(translations)
+        .map(|(original, translation)| (original.as_str(), translation.as_str()))
+        .collect();
+
+    let mut summary = CoverageSummary::default();
+    mark_coverage(&dom.document, &translated, &mut summary);
+    insert_legend(&dom.document, &summary);
+
+    Ok((serialize_dom_to_html(dom)?, summary))
+}
+
+/// 写出覆盖率报告，返回统计
+pub fn write_coverage_report(
+    path: &Path,
+    html: &str,
+    originals: &[String],
+    translations: &[String],
+) -> Result<CoverageSummary> {
+    let (report, summary) = render_coverage_report(html, originals, translations)?;
+    std::fs::write(path, report)
+        .with_context(|| format!("写入覆盖率报告失败: {}", path.display()))?;
+    Ok(summary)
+}
+
+/// 递归给文本节点包上着色的`<span>`
+fn mark_coverage(node: &Handle, translated: &HashMap<&str, &str>, summary: &mut CoverageSummary) {
+    if let NodeData::Element { ref name, .. } = node.data {
+        if NON_RENDERED_ELEMENTS.contains(&name.local.as_ref()) {
+            return;
+        }
+    }
+
+    let children: Vec<Handle> = node.children.borrow().clone();
+    for (position, child) in children.iter().enumerate() {
+        let status = match child.data {
+            NodeData::Text { ref contents } => {
+                let text = contents.borrow();
+                let trimmed = text.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                match translated.get(trimmed) {
+                    Some(translation) if !translation.trim().is_empty() => CoverageStatus::Translated,
+                    Some(_) => CoverageStatus::Untranslated,
+                    None => CoverageStatus::Skipped,
+                }
+            }
+            NodeData::Element { .. } => {
+                mark_coverage(child, translated, summary);
+                continue;
+            }
+            _ => continue,
+        };
+        summary.record(status);
+
+        let mut attrs = vec![("class", status.class_name().to_string())];
+        if let NodeData::Text { ref contents } = child.data {
+            if let Some(translation) = translated.get(contents.borrow().trim()).filter(|t| !t.is_empty()) {
+                attrs.push(("title", translation.to_string()));
+            }
+        }
+        let span = new_element("span", attrs);
+        span.parent.set(Some(Rc::downgrade(node)));
+        child.parent.set(Some(Rc::downgrade(&span)));
+        span.children.borrow_mut().push(child.clone());
+        node.children.borrow_mut()[position] = span;
+    }
+}
+
+/// 在`<head>`中注入样式，在`<body>`开头插入图例和统计
+fn insert_legend(document: &Handle, summary: &CoverageSummary) {
+    let head = find_element(document, "head");
+    let body = find_element(document, "body");
+
+    if let Some(head) = head {
+        let style = new_element("style", vec![]);
+        append_child(&style, new_text(COVERAGE_STYLE));
+        append_child(&head, style);
+    }
+
+    if let Some(body) = body {
+        let legend = new_element("div", vec![("class", "tc-coverage-legend".to_string())]);
+        append_child(&legend, new_text("翻译覆盖率："));
+        for (status, label, count) in [
+            (CoverageStatus::Translated, "已翻译", summary.translated),
+            (CoverageStatus::Untranslated, "未翻译", summary.untranslated),
+            (CoverageStatus::Skipped, "已跳过", summary.skipped),
+        ] {
+            let item = new_element("span", vec![("class", status.class_name().to_string())]);
+            append_child(&item, new_text(&format!("{} {}", label, count)));
+            append_child(&legend, item);
+            append_child(&legend, new_text(" "));
+        }
+        legend.parent.set(Some(Rc::downgrade(&body)));
+        body.children.borrow_mut().insert(0, legend);
+    }
+}
+
+/// 深度优先查找第一个指定标签的元素
+fn find_element(node: &Handle, tag: &str) -> Option<Handle> {
+    if let NodeData::Element { ref name, .. } = node.data {
+        if name.local.as_ref() == tag {
+            return Some(node.clone());
+        }
+    }
+    node.children
+        .borrow()
+        .iter()
+        .find_map(|child| find_element(child, tag))
+}
+
+fn new_element(tag: &str, attrs: Vec<(&str, String)>) -> Handle {
+    Node::new(NodeData::Element {
+        name: QualName::new(None, ns!(html), LocalName::from(tag)),
+        attrs: RefCell::new(
+            attrs
+                .into_iter()
+                .map(|(name, value)| Attribute {
+                    name: QualName::new(None, ns!(), LocalName::from(name)),
+                    value: value.into(),
+                })
+                .collect(),
+        ),
+        template_contents: RefCell::new(None),
+        mathml_annotation_xml_integration_point: false,
+    })
+}
+
+fn new_text(text: &str) -> Handle {
+    Node::new(NodeData::Text {
+        contents: RefCell::new(text.into()),
+    })
+}
+
+fn append_child(parent: &Handle, child: Handle) {
+    child.parent.set(Some(Rc::downgrade(parent)));
+    parent.children.borrow_mut().push(child);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_coverage_report() {
+        let html = r#"<html><head><title>Page</title></head><body>
+<p>Hello</p><p>Broken</p><td>42</td><script>var x = "code";</script></body></html>"#;
+        let originals = vec!["Page".to_string(), "Hello".to_string(), "Broken".to_string()];
+        let translations = vec!["页面".to_string(), "你好".to_string(), String::new()];
+
+        let (report, summary) = render_coverage_report(html, &originals, &translations).unwrap();
+        assert_eq!(summary, CoverageSummary { translated: 1, untranslated: 1, skipped: 1 });
+        assert!(report.contains(r#"<span class="tc-coverage-translated" title="你好">Hello</span>"#));
+        assert!(report.contains(r#"<span class="tc-coverage-untranslated">Broken</span>"#));
+        assert!(report.contains(r#"<span class="tc-coverage-skipped">42</span>"#));
+        // 标题和脚本不着色，图例插入到body开头
+        assert!(report.contains("<title>Page</title>"));
+        assert!(report.contains(r#"var x = "code";"#));
+        assert!(report.contains(r#"<body><div class="tc-coverage-legend">翻译覆盖率："#));
+        assert!(report.contains(".tc-coverage-translated{"));
+    }
+}
@@ -0,0 +1,133 @@
+//! 日期本地化模块
+//!
+//! 按目标语言格式化`<time datetime>`中的机器可读日期，替代对显示文本的字面翻译
+
+// 第三方crate导入
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike};
+
+/// 解析后的`datetime`属性值
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MachineDate {
+    /// 年月，如`2024-01`
+    Month { year: i32, month: u32 },
+    /// 日期，如`2024-01-01`
+    Date(NaiveDate),
+    /// 日期和时间，如`2024-01-01T14:30:00Z`（保留原文中的本地时间）
+    DateTime(NaiveDateTime),
+}
+
+/// 英文月份名
+const EN_MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September",
+    "October", "November", "December",
+];
+
+/// 德文月份名
+const DE_MONTHS: [&str; 12] = [
+    "Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober",
+    "November", "Dezember",
+];
+
+/// 法文月份名
+const FR_MONTHS: [&str; 12] = [
+    "janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre",
+    "octobre", "novembre", "décembre",
+];
+
+/// 西班牙文月份名
+const ES_MONTHS: [&str; 12] = [
+    "enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre",
+    "octubre", "noviembre", "diciembre",
+];
+
+/// 按目标语言格式化`datetime`属性值
+///
+/// 支持`YYYY-MM`、`YYYY-MM-DD`以及带时间（可含时区）的格式，时间按原文中的本地时间显示。
+/// 无法解析或目标语言不受支持时返回`None`，调用方可退回字面翻译。
+pub fn localize_datetime(datetime: &str, lang: &str) -> Option<String> {
+    let date = parse_machine_date(datetime.trim())?;
+    let language = lang
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    let (year, month, day, time) = match date {
+        MachineDate::Month { year, month } => (year, month, None, None),
+        MachineDate::Date(date) => (date.year(), date.month(), Some(date.day()), None),
+        MachineDate::DateTime(datetime) => (
+            datetime.year(),
+            datetime.month(),
+            Some(datetime.day()),
+            Some(format!("{:02}:{:02}", datetime.hour(), datetime.minute())),
+        ),
+    };
+    let month_index = (month - 1) as usize;
+
+    let formatted = match (language.as_str(), day) {
+        ("zh" | "ja", Some(day)) => format!("{}年{}月{}日", year, month, day),
+        ("zh" | "ja", None) => format!("{}年{}月", year, month),
+        ("ko", Some(day)) => format!("{}년 {}월 {}일", year, month, day),
+        ("ko", None) => format!("{}년 {}월", year, month),
+        ("en", Some(day)) => format!("{} {}, {}", EN_MONTHS[month_index], day, year),
+        ("en", None) => format!("{} {}", EN_MONTHS[month_index], year),
+        ("de", Some(day)) => format!("{}. {} {}", day, DE_MONTHS[month_index], year),
+        ("de", None) => format!("{} {}", DE_MONTHS[month_index], year),
+        ("fr", Some(day)) => format!("{} {} {}", day, FR_MONTHS[month_index], year),
+        ("fr", None) => format!("{} {}", FR_MONTHS[month_index], year),
+        ("es", Some(day)) => format!("{} de {} de {}", day, ES_MONTHS[month_index], year),
+        ("es", None) => format!("{} de {}", ES_MONTHS[month_index], year),
+        _ => return None,
+    };
+
+    Some(match time {
+        Some(time) => format!("{} {}", formatted, time),
+        None => formatted,
+    })
+}
+
+/// 解析HTML`datetime`属性中常见的机器可读日期格式
+fn parse_machine_date(value: &str) -> Option<MachineDate> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(MachineDate::DateTime(datetime.naive_local()));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(MachineDate::DateTime(datetime));
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(MachineDate::Date(date));
+    }
+
+    let (year, month) = value.split_once('-')?;
+    let (year, month): (i32, u32) = (year.parse().ok()?, month.parse().ok()?);
+    (year.to_string().len() == 4 && (1..=12).contains(&month))
+        .then_some(MachineDate::Month { year, month })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localize_datetime() {
+        assert_eq!(localize_datetime("2024-01-01", "zh").as_deref(), Some("2024年1月1日"));
+        assert_eq!(localize_datetime("2024-01-01", "zh-CN").as_deref(), Some("2024年1月1日"));
+        assert_eq!(localize_datetime("2024-03-05", "en").as_deref(), Some("March 5, 2024"));
+        assert_eq!(localize_datetime("2024-03-05", "de").as_deref(), Some("5. März 2024"));
+        assert_eq!(localize_datetime("2024-03", "ja").as_deref(), Some("2024年3月"));
+        assert_eq!(
+            localize_datetime("2024-01-01T14:30:00+08:00", "ko").as_deref(),
+            Some("2024년 1월 1일 14:30")
+        );
+        assert_eq!(localize_datetime("2024-01-01T09:05", "fr").as_deref(), Some("1 janvier 2024 09:05"));
+    }
+
+    #[test]
+    fn test_localize_datetime_unsupported() {
+        assert_eq!(localize_datetime("PT2H30M", "zh"), None);
+        assert_eq!(localize_datetime("2024-13", "zh"), None);
+        assert_eq!(localize_datetime("2024-01-01", "th"), None);
+    }
+}
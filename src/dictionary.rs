@@ -0,0 +1,123 @@
+//! 翻译词典模块
+//!
+//! 将翻译得到的「原文→译文」映射去重后导出为JSON词典文件，
+//! 多次导出到同一文件时合并累积，便于后续作为术语表复用
+
+// 标准库导入
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// 第三方crate导入
+use anyhow::{Context, Result};
+
+/// 原文到译文的翻译词典，按原文排序
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TranslationDictionary {
+    entries: BTreeMap<String, String>,
+}
+
+impl TranslationDictionary {
+    /// 创建空词典
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从JSON词典文件加载，文件不存在时返回空词典
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("读取词典文件失败: {}", path.display()))?;
+        let entries: BTreeMap<String, String> = serde_json::from_str(&content)
+            .with_context(|| format!("词典文件格式无效（应为原文到译文的JSON对象）: {}", path.display()))?;
+        Ok(Self { entries })
+    }
+
+    /// 写出为格式化的JSON词典文件
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(&self.entries)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("写入词典文件失败: {}", path.display()))
+    }
+
+    /// 加入翻译映射，空译文和与原文相同的译文不收录，同一原文以后加入的为准
+    pub fn extend_from_pairs(&mut self, originals: &[String], translations: &[String]) {
+        for (original, translation) in originals.iter().zip(translations) {
+            let original = original.trim();
+            let translation = translation.trim();
+            if original.is_empty() || translation.is_empty() || original == translation {
+                continue;
+            }
+            self.entries
+                .insert(original.to_string(), translation.to_string());
+        }
+    }
+
+    /// 查询原文对应的译文
+    pub fn get(&self, original: &str) -> Option<&str> {
+        self.entries.get(original).map(String::as_str)
+    }
+
+    /// 词条数量
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 词典是否为空
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// 将翻译映射合并写入词典文件，返回合并后的词条数量
+pub fn export_translation_pairs(
+    path: &Path,
+    originals: &[String],
+    translations: &[String],
+) -> Result<usize> {
+    let mut dictionary = TranslationDictionary::load(path)?;
+    dictionary.extend_from_pairs(originals, translations);
+    dictionary.save(path)?;
+    Ok(dictionary.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extend_skips_empty_and_untranslated() {
+        let mut dictionary = TranslationDictionary::new();
+        let originals = vec!["Hello".to_string(), "World".to_string(), "API".to_string()];
+        let translations = vec!["你好".to_string(), String::new(), "API".to_string()];
+        dictionary.extend_from_pairs(&originals, &translations);
+
+        assert_eq!(dictionary.len(), 1);
+        assert_eq!(dictionary.get("Hello"), Some("你好"));
+        assert_eq!(dictionary.get("World"), None);
+    }
+
+    #[test]
+    fn test_export_merges_with_existing_file() {
+        let path = std::env::temp_dir().join("translation-cli-dictionary-test.json");
+        let _ = std::fs::remove_file(&path);
+
+        let count = export_translation_pairs(&path, &["Hello".to_string()], &["你好".to_string()]).unwrap();
+        assert_eq!(count, 1);
+        let count = export_translation_pairs(
+            &path,
+            &["Hello".to_string(), "World".to_string()],
+            &["您好".to_string(), "世界".to_string()],
+        )
+        .unwrap();
+        assert_eq!(count, 2);
+
+        let dictionary = TranslationDictionary::load(&path).unwrap();
+        assert_eq!(dictionary.get("Hello"), Some("您好"));
+        assert_eq!(dictionary.get("World"), Some("世界"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
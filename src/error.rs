@@ -8,6 +8,78 @@ use std::fmt;
 // 第三方crate导入
 use anyhow::Error as AnyhowError;
 
+/// 网络错误类别
+/// 
+/// 用于区分网络失败的具体原因，便于排查和判断是否值得重试
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkErrorKind {
+    /// 连接或读取超时
+    Timeout,
+    /// DNS解析失败
+    Dns,
+    /// TLS握手或证书错误
+    Tls,
+    /// 连接建立失败（拒绝连接、网络不可达等）
+    Connect,
+    /// 服务器返回了错误的HTTP状态码
+    Http,
+    /// 其它无法归类的错误
+    Other,
+}
+
+impl NetworkErrorKind {
+    /// 根据reqwest错误推断错误类别
+    pub fn from_reqwest(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            return NetworkErrorKind::Timeout;
+        }
+        if error.is_status() {
+            return NetworkErrorKind::Http;
+        }
+
+        // reqwest不直接暴露DNS/TLS错误类型，沿错误链检查底层描述
+        let mut chain = String::new();
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(error);
+        while let Some(err) = source {
+            chain.push_str(&err.to_string().to_lowercase());
+            chain.push('\n');
+            source = err.source();
+        }
+
+        if chain.contains("dns error")
+            || chain.contains("failed to lookup address")
+            || chain.contains("name or service not known")
+            || chain.contains("no such host")
+        {
+            NetworkErrorKind::Dns
+        } else if chain.contains("certificate")
+            || chain.contains("tls")
+            || chain.contains("ssl")
+            || chain.contains("handshake")
+        {
+            NetworkErrorKind::Tls
+        } else if error.is_connect() {
+            NetworkErrorKind::Connect
+        } else {
+            NetworkErrorKind::Other
+        }
+    }
+}
+
+impl fmt::Display for NetworkErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            NetworkErrorKind::Timeout => "超时",
+            NetworkErrorKind::Dns => "DNS解析失败",
+            NetworkErrorKind::Tls => "TLS错误",
+            NetworkErrorKind::Connect => "连接失败",
+            NetworkErrorKind::Http => "HTTP错误",
+            NetworkErrorKind::Other => "其它",
+        };
+        write!(f, "{}", label)
+    }
+}
+
 /// Translation CLI 统一错误类型
 /// 
 /// 定义了项目中可能出现的所有错误类型，提供统一的错误处理接口
@@ -18,7 +90,9 @@ pub enum TranslationError {
         /// 错误消息
         message: String, 
         /// HTTP状态码（如果适用）
-        status_code: Option<u16> 
+        status_code: Option<u16>,
+        /// 错误类别
+        kind: NetworkErrorKind,
     },
     
     /// HTML解析相关错误
@@ -71,6 +145,17 @@ pub enum TranslationError {
         details: String 
     },
     
+    /// 翻译API连续失败后熔断，冷却期内请求立即失败
+    CircuitOpen {
+        /// 打开熔断时的连续失败次数
+        consecutive_failures: usize,
+        /// 距离下次探测的剩余时间
+        retry_after: std::time::Duration,
+    },
+    
+    /// 收到中断信号，不再发起新的翻译请求
+    Interrupted,
+    
     /// 内部处理错误（包装anyhow::Error）
     Internal { 
         /// 包装的错误
@@ -81,11 +166,11 @@ pub enum TranslationError {
 impl fmt::Display for TranslationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            TranslationError::Network { message, status_code } => {
-                if let Some(code) = status_code {
-                    write!(f, "网络请求失败 [{}]: {}", code, message)
-                } else {
-                    write!(f, "网络请求失败: {}", message)
+            TranslationError::Network { message, status_code, kind } => {
+                match (status_code, kind) {
+                    (Some(code), _) => write!(f, "网络请求失败 [{}]: {}", code, message),
+                    (None, NetworkErrorKind::Other) => write!(f, "网络请求失败: {}", message),
+                    (None, kind) => write!(f, "网络请求失败 ({}): {}", kind, message),
                 }
             },
             TranslationError::HtmlParse { details } => {
@@ -106,6 +191,12 @@ impl fmt::Display for TranslationError {
             TranslationError::TempFileManagement { operation, details } => {
                 write!(f, "临时文件{}失败: {}", operation, details)
             },
+            TranslationError::CircuitOpen { consecutive_failures, retry_after } => {
+                write!(f, "翻译API已熔断: 连续失败 {} 次，{:?} 后探测恢复", consecutive_failures, retry_after)
+            },
+            TranslationError::Interrupted => {
+                write!(f, "收到中断信号，已停止发起新的翻译请求")
+            },
             TranslationError::Internal { source } => {
                 write!(f, "内部处理错误: {}", source)
             },
@@ -113,6 +204,76 @@ impl fmt::Display for TranslationError {
     }
 }
 
+impl TranslationError {
+    /// 判断错误是否值得重试
+    /// 
+    /// 超时、连接失败、HTTP 429和5xx视为可重试；DNS、TLS、其它4xx错误以及
+    /// 本地配置/输入类错误重试也不会成功。
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            TranslationError::Network { status_code: Some(code), .. } => {
+                *code == 429 || *code >= 500
+            },
+            TranslationError::Network { kind, .. } => matches!(
+                kind,
+                NetworkErrorKind::Timeout | NetworkErrorKind::Connect | NetworkErrorKind::Other
+            ),
+            TranslationError::TranslationApi { status_code, .. } => {
+                *status_code == 429 || *status_code >= 500
+            },
+            _ => false,
+        }
+    }
+
+    /// 针对常见失败给出用户可以照做的排查建议，没有合适建议时返回`None`
+    pub fn suggestion(&self) -> Option<&'static str> {
+        let status_code = match self {
+            TranslationError::Network { status_code, .. } => *status_code,
+            TranslationError::TranslationApi { status_code, .. } => Some(*status_code),
+            _ => None,
+        };
+        if let Some(code) = status_code {
+            return match code {
+                401 | 403 => Some("检查API密钥或令牌是否正确（--api-key，或 --api 地址中的token），以及是否有权访问该接口"),
+                404 => Some("检查 --api 地址是否正确，路径是否完整"),
+                429 => Some("请求过于频繁，降低 --concurrent-batches 后重试"),
+                500..=599 => Some("翻译服务暂时不可用，稍后重试或用 --api 切换到其他地址"),
+                _ => None,
+            };
+        }
+
+        match self {
+            TranslationError::Network { kind, .. } => match kind {
+                NetworkErrorKind::Timeout => Some("请求超时，服务可能繁忙：稍后重试，或增大 --max-retries、减小 --concurrent-batches"),
+                NetworkErrorKind::Dns => Some("无法解析API域名，检查 --api 地址拼写和本机DNS设置"),
+                NetworkErrorKind::Tls => Some("TLS握手失败，检查系统时间、证书或代理是否拦截了HTTPS"),
+                NetworkErrorKind::Connect => Some("无法连接到API：检查网络，需要代理时设置 HTTPS_PROXY/HTTP_PROXY 环境变量；使用 --local-api 时确认本地服务已启动"),
+                _ => None,
+            },
+            TranslationError::Configuration { .. } => Some("检查对应的命令行参数或 TRANSLATION_CLI_* 环境变量"),
+            TranslationError::CircuitOpen { .. } => Some("翻译API持续失败已触发熔断：先确认服务恢复，或调整 --circuit-breaker-threshold/--circuit-breaker-cooldown"),
+            TranslationError::Internal { source } => suggestion_for(source),
+            _ => None,
+        }
+    }
+
+    /// 第`attempt`次（从1开始）失败后的重试等待时间，不应重试时返回`None`
+    /// 
+    /// 超时说明服务繁忙，使用加倍的指数退避；连接失败通常意味着服务未启动，
+    /// 立即失败；其它可重试错误使用普通指数退避。
+    pub fn retry_delay(&self, attempt: u32, base: std::time::Duration) -> Option<std::time::Duration> {
+        let exponential = base * 2u32.saturating_pow(attempt.saturating_sub(1));
+        match self {
+            TranslationError::Network { status_code: None, kind: NetworkErrorKind::Timeout, .. } => {
+                Some(exponential * 2)
+            },
+            TranslationError::Network { status_code: None, kind: NetworkErrorKind::Connect, .. } => None,
+            _ if self.is_retryable() => Some(exponential),
+            _ => None,
+        }
+    }
+}
+
 impl std::error::Error for TranslationError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -122,6 +283,22 @@ impl std::error::Error for TranslationError {
     }
 }
 
+/// 所有请求都有响应却没有得到任何译文时的排查建议
+pub const EMPTY_TRANSLATIONS_SUGGESTION: &str =
+    "API有响应但没有解析出译文，协议可能不匹配：试试 --api-protocol，或用 --request-template/--response-path 适配接口格式";
+
+/// 沿错误链查找[`TranslationError`]并返回其排查建议
+pub fn suggestion_for(error: &AnyhowError) -> Option<&'static str> {
+    error.chain().find_map(|cause| {
+        if let Some(error) = cause.downcast_ref::<TranslationError>() {
+            return error.suggestion();
+        }
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .and_then(|error| TranslationError::from_reqwest_ref(error).suggestion())
+    })
+}
+
 /// Translation CLI 结果类型别名
 pub type Result<T> = std::result::Result<T, TranslationError>;
 
@@ -131,13 +308,15 @@ macro_rules! translation_error {
     (network, $msg:expr) => {
         TranslationError::Network { 
             message: $msg.to_string(), 
-            status_code: None 
+            status_code: None,
+            kind: $crate::error::NetworkErrorKind::Other,
         }
     };
     (network, $msg:expr, $code:expr) => {
         TranslationError::Network { 
             message: $msg.to_string(), 
-            status_code: Some($code) 
+            status_code: Some($code),
+            kind: $crate::error::NetworkErrorKind::Http,
         }
     };
     (html_parse, $details:expr) => {
@@ -189,10 +368,17 @@ impl From<AnyhowError> for TranslationError {
 /// 从reqwest::Error转换为TranslationError
 impl From<reqwest::Error> for TranslationError {
     fn from(error: reqwest::Error) -> Self {
-        let status_code = error.status().map(|s| s.as_u16());
+        TranslationError::from_reqwest_ref(&error)
+    }
+}
+
+impl TranslationError {
+    /// 根据reqwest错误构造网络错误，不获取所有权
+    fn from_reqwest_ref(error: &reqwest::Error) -> Self {
         TranslationError::Network {
             message: error.to_string(),
-            status_code,
+            status_code: error.status().map(|s| s.as_u16()),
+            kind: NetworkErrorKind::from_reqwest(error),
         }
     }
 }
@@ -217,6 +403,7 @@ mod tests {
         let err = TranslationError::Network {
             message: "Connection failed".to_string(),
             status_code: Some(500),
+            kind: NetworkErrorKind::Http,
         };
         
         assert_eq!(
@@ -229,14 +416,62 @@ mod tests {
     fn test_error_macro() {
         let err = translation_error!(network, "Test error", 404);
         match err {
-            TranslationError::Network { message, status_code } => {
+            TranslationError::Network { message, status_code, kind } => {
                 assert_eq!(message, "Test error");
                 assert_eq!(status_code, Some(404));
+                assert_eq!(kind, NetworkErrorKind::Http);
             },
             _ => panic!("Wrong error type"),
         }
     }
 
+    #[test]
+    fn test_network_error_kind_display() {
+        let err = TranslationError::Network {
+            message: "lookup failed".to_string(),
+            status_code: None,
+            kind: NetworkErrorKind::Dns,
+        };
+
+        assert_eq!(format!("{}", err), "网络请求失败 (DNS解析失败): lookup failed");
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let network = |status_code, kind| TranslationError::Network {
+            message: String::new(),
+            status_code,
+            kind,
+        };
+
+        assert!(network(None, NetworkErrorKind::Timeout).is_retryable());
+        assert!(network(None, NetworkErrorKind::Connect).is_retryable());
+        assert!(network(Some(503), NetworkErrorKind::Http).is_retryable());
+        assert!(network(Some(429), NetworkErrorKind::Http).is_retryable());
+        assert!(!network(Some(404), NetworkErrorKind::Http).is_retryable());
+        assert!(!network(None, NetworkErrorKind::Dns).is_retryable());
+        assert!(!network(None, NetworkErrorKind::Tls).is_retryable());
+        assert!(!translation_error!(config, "api", "invalid").is_retryable());
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        use std::time::Duration;
+
+        let base = Duration::from_millis(100);
+        let network = |status_code, kind| TranslationError::Network {
+            message: String::new(),
+            status_code,
+            kind,
+        };
+
+        assert_eq!(network(None, NetworkErrorKind::Timeout).retry_delay(1, base), Some(Duration::from_millis(200)));
+        assert_eq!(network(None, NetworkErrorKind::Timeout).retry_delay(2, base), Some(Duration::from_millis(400)));
+        assert_eq!(network(Some(503), NetworkErrorKind::Http).retry_delay(2, base), Some(Duration::from_millis(200)));
+        assert_eq!(network(None, NetworkErrorKind::Connect).retry_delay(1, base), None);
+        assert_eq!(network(Some(404), NetworkErrorKind::Http).retry_delay(1, base), None);
+    }
+
     #[test]
     fn test_anyhow_conversion() {
         let anyhow_err = anyhow::anyhow!("Test anyhow error");
@@ -249,4 +484,24 @@ mod tests {
             _ => panic!("Wrong error type"),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_suggestion() {
+        let network = |status_code, kind| TranslationError::Network {
+            message: String::new(),
+            status_code,
+            kind,
+        };
+
+        assert!(network(Some(401), NetworkErrorKind::Http).suggestion().unwrap().contains("--api-key"));
+        assert!(translation_error!(translation_api, 403, "forbidden", "http://api").suggestion().unwrap().contains("--api-key"));
+        assert!(network(None, NetworkErrorKind::Connect).suggestion().unwrap().contains("HTTPS_PROXY"));
+        assert!(network(Some(418), NetworkErrorKind::Http).suggestion().is_none());
+        assert!(translation_error!(html_parse, "bad").suggestion().is_none());
+
+        // 经过anyhow包装和上下文后仍能找到建议
+        let wrapped = AnyhowError::new(network(Some(401), NetworkErrorKind::Http)).context("批次翻译失败");
+        assert!(suggestion_for(&wrapped).unwrap().contains("--api-key"));
+        assert!(suggestion_for(&anyhow::anyhow!("plain")).is_none());
+    }
+}
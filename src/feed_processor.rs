@@ -0,0 +1,317 @@
+//! RSS/Atom feed处理模块
+//!
+//! 识别RSS/Atom feed，提取标题、摘要和正文等可翻译内容，
+//! 并在保留其它元素（链接、日期、guid等）不变的前提下重新组装feed XML
+
+// 标准库导入
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+// 第三方crate导入
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesCData, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use regex::Regex;
+
+// 本地模块导入
+use crate::html_processor::{
+    apply_translations_to_dom, extract_translatable_texts, parse_html_fragment,
+    serialize_fragment_to_html,
+};
+
+/// 需要翻译内容的feed元素（RSS 2.0 / RSS 1.0 / Atom）
+const TRANSLATABLE_FEED_ELEMENTS: &[&str] = &[
+    "title",
+    "subtitle",
+    "description",
+    "summary",
+    "content",
+    "content:encoded",
+];
+
+/// 判断内容是否为RSS/Atom feed
+pub fn is_feed_content(content: &str) -> bool {
+    let head: String = content.chars().take(2048).collect::<String>().to_ascii_lowercase();
+
+    if head.contains("<html") {
+        return false;
+    }
+
+    head.contains("<rss")
+        || head.contains("<rdf:rdf")
+        || (head.contains("<feed") && head.contains("w3.org/2005/atom"))
+}
+
+/// 从feed XML中提取可翻译文本
+///
+/// 纯文本元素直接提取；包含HTML的元素（如`description`、`content:encoded`）
+/// 按HTML片段解析后复用HTML文本提取逻辑。
+pub fn collect_feed_texts(xml: &str) -> Result<Vec<String>> {
+    let mut texts = Vec::new();
+    let mut seen_texts = HashSet::new();
+    let mut reader = Reader::from_str(xml);
+    let mut element_stack: Vec<String> = Vec::new();
+
+    loop {
+        let event = reader.read_event().context("解析feed XML失败")?;
+        let segment = match event {
+            Event::Eof => break,
+            Event::Start(ref e) => {
+                element_stack.push(String::from_utf8_lossy(e.name().as_ref()).into_owned());
+                continue;
+            }
+            Event::End(_) => {
+                element_stack.pop();
+                continue;
+            }
+            Event::Text(ref e) if is_in_translatable_element(&element_stack) => {
+                match e.unescape() {
+                    Ok(text) => text.into_owned(),
+                    Err(_) => continue,
+                }
+            }
+            Event::CData(ref e) if is_in_translatable_element(&element_stack) => {
+                match e.decode() {
+                    Ok(text) => text.into_owned(),
+                    Err(_) => continue,
+                }
+            }
+            _ => continue,
+        };
+
+        let segment_texts = if looks_like_html(&segment) {
+            extract_translatable_texts(&parse_html_fragment(&segment))
+        } else {
+            let trimmed = segment.trim();
+            if is_plain_feed_text(trimmed) {
+                vec![trimmed.to_string()]
+            } else {
+                Vec::new()
+            }
+        };
+
+        for text in segment_texts {
+            if seen_texts.insert(text.clone()) {
+                texts.push(text);
+            }
+        }
+    }
+
+    Ok(texts)
+}
+
+/// 将翻译结果应用到feed XML
+///
+/// 仅替换可翻译元素中的文本，其余事件按原样写回。
+pub fn apply_feed_translations(
+    xml: &str,
+    original_texts: &[String],
+    translations: &[String],
+) -> Result<String> {
+    let translation_map: HashMap<&str, &str> = original_texts
+        .iter()
+        .zip(translations.iter())
+        .filter(|(_, trans)| !trans.is_empty())
+        .map(|(orig, trans)| (orig.as_str(), trans.as_str()))
+        .collect();
+
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::new());
+    let mut element_stack: Vec<String> = Vec::new();
+
+    loop {
+        let event = reader.read_event().context("解析feed XML失败")?;
+        match event {
+            Event::Eof => break,
+            Event::Start(e) => {
+                element_stack.push(String::from_utf8_lossy(e.name().as_ref()).into_owned());
+                writer.write_event(Event::Start(e))?;
+            }
+            Event::End(e) => {
+                element_stack.pop();
+                writer.write_event(Event::End(e))?;
+            }
+            Event::Text(e) if is_in_translatable_element(&element_stack) => {
+                let decoded = e.unescape().map(|text| text.into_owned());
+                match decoded {
+                    Ok(text) => {
+                        let translated =
+                            translate_feed_segment(&text, &translation_map, original_texts, translations)?;
+                        writer.write_event(Event::Text(BytesText::new(&translated)))?;
+                    }
+                    Err(_) => writer.write_event(Event::Text(e))?,
+                }
+            }
+            Event::CData(e) if is_in_translatable_element(&element_stack) => {
+                let decoded = e.decode().map(|text| text.into_owned());
+                match decoded {
+                    Ok(text) => {
+                        let translated =
+                            translate_feed_segment(&text, &translation_map, original_texts, translations)?;
+                        writer.write_event(Event::CData(BytesCData::new(translated)))?;
+                    }
+                    Err(_) => writer.write_event(Event::CData(e))?,
+                }
+            }
+            other => writer.write_event(other)?,
+        }
+    }
+
+    String::from_utf8(writer.into_inner()).map_err(|e| anyhow::anyhow!("UTF-8转换失败: {}", e))
+}
+
+/// 翻译单个feed文本片段，保留片段首尾空白
+fn translate_feed_segment(
+    segment: &str,
+    translation_map: &HashMap<&str, &str>,
+    original_texts: &[String],
+    translations: &[String],
+) -> Result<String> {
+    if looks_like_html(segment) {
+        let dom = parse_html_fragment(segment);
+        let translated_dom = apply_translations_to_dom(dom, original_texts, translations)?;
+        return serialize_fragment_to_html(translated_dom);
+    }
+
+    let trimmed = segment.trim();
+    match translation_map.get(trimmed) {
+        Some(translation) => {
+            let leading = &segment[..segment.len() - segment.trim_start().len()];
+            let trailing = &segment[segment.trim_end().len()..];
+            Ok(format!("{}{}{}", leading, translation, trailing))
+        }
+        None => Ok(segment.to_string()),
+    }
+}
+
+/// 当前是否位于可翻译元素内部
+fn is_in_translatable_element(element_stack: &[String]) -> bool {
+    element_stack
+        .iter()
+        .any(|name| TRANSLATABLE_FEED_ELEMENTS.contains(&name.as_str()))
+}
+
+/// 粗略判断文本片段是否为HTML
+fn looks_like_html(text: &str) -> bool {
+    text.contains('<') && text.contains('>')
+}
+
+/// 判断feed纯文本是否值得翻译
+fn is_plain_feed_text(text: &str) -> bool {
+    text.len() > 1
+        && !text
+            .chars()
+            .all(|c| c.is_whitespace() || c.is_ascii_punctuation())
+}
+
+/// 将XML声明中的`encoding`伪属性改为`encoding`
+///
+/// 没有`encoding`伪属性时补在声明末尾；`insert_if_missing`为真且文档没有XML声明时在开头插入一个，
+/// 否则原样返回。XML解析器按声明而不是`<meta>`解码，输出编码变化时必须同步修改。
+pub fn set_xml_declared_encoding(xml: &str, encoding: &str, insert_if_missing: bool) -> String {
+    static DECLARATION: OnceLock<Regex> = OnceLock::new();
+    static ENCODING: OnceLock<Regex> = OnceLock::new();
+    let declaration = DECLARATION
+        .get_or_init(|| Regex::new(r"^(\u{feff}?\s*<\?xml\b)([^?]*?)(\s*\?>)").expect("XML声明正则无效"));
+    let encoding_attr = ENCODING
+        .get_or_init(|| Regex::new(r#"\bencoding\s*=\s*(?:"[^"]*"|'[^']*')"#).expect("encoding正则无效"));
+
+    let Some(captures) = declaration.captures(xml) else {
+        return if insert_if_missing {
+            format!("<?xml version=\"1.0\" encoding=\"{}\"?>\n{}", encoding, xml)
+        } else {
+            xml.to_string()
+        };
+    };
+    let attributes = &captures[2];
+    let attributes = if encoding_attr.is_match(attributes) {
+        encoding_attr
+            .replace(attributes, format!("encoding=\"{}\"", encoding).as_str())
+            .into_owned()
+    } else {
+        format!("{} encoding=\"{}\"", attributes, encoding)
+    };
+    let end = captures.get(0).map_or(0, |m| m.end());
+    format!("{}{}{}{}", &captures[1], attributes, &captures[3], &xml[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS_SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Example Blog</title>
+    <link>https://example.com/</link>
+    <item>
+      <title>Hello World</title>
+      <guid>https://example.com/posts/1</guid>
+      <pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate>
+      <description>First post summary</description>
+      <content:encoded><![CDATA[<p>Welcome to <b>our blog</b></p>]]></content:encoded>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn test_is_feed_content() {
+        assert!(is_feed_content(RSS_SAMPLE));
+        assert!(is_feed_content(
+            r#"<?xml version="1.0"?><feed xmlns="http://www.w3.org/2005/Atom"><title>T</title></feed>"#
+        ));
+        assert!(!is_feed_content("<html><body><p>rss</p></body></html>"));
+        assert!(!is_feed_content("<!DOCTYPE html><html><head></head></html>"));
+    }
+
+    #[test]
+    fn test_collect_feed_texts() {
+        let texts = collect_feed_texts(RSS_SAMPLE).unwrap();
+        assert!(texts.contains(&"Example Blog".to_string()));
+        assert!(texts.contains(&"Hello World".to_string()));
+        assert!(texts.contains(&"First post summary".to_string()));
+        assert!(texts.contains(&"Welcome to".to_string()));
+        assert!(texts.contains(&"our blog".to_string()));
+        assert!(!texts.iter().any(|t| t.contains("example.com")));
+    }
+
+    #[test]
+    fn test_apply_feed_translations_preserves_other_elements() {
+        let originals = vec![
+            "Hello World".to_string(),
+            "First post summary".to_string(),
+            "our blog".to_string(),
+        ];
+        let translations = vec![
+            "你好世界".to_string(),
+            "第一篇文章摘要".to_string(),
+            "我们的博客".to_string(),
+        ];
+
+        let output = apply_feed_translations(RSS_SAMPLE, &originals, &translations).unwrap();
+        assert!(output.contains("<title>你好世界</title>"));
+        assert!(output.contains("<description>第一篇文章摘要</description>"));
+        assert!(output.contains("<b>我们的博客</b>"));
+        assert!(output.contains("<![CDATA["));
+        assert!(output.contains("<guid>https://example.com/posts/1</guid>"));
+        assert!(output.contains("<pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate>"));
+        assert!(output.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    }
+
+    #[test]
+    fn test_set_xml_declared_encoding() {
+        let output = set_xml_declared_encoding(RSS_SAMPLE, "GBK", true);
+        assert!(output.starts_with(r#"<?xml version="1.0" encoding="GBK"?>"#));
+        assert_eq!(output.len(), RSS_SAMPLE.len() - 2);
+
+        assert_eq!(
+            set_xml_declared_encoding("<?xml version='1.0'?><rss/>", "Shift_JIS", true),
+            r#"<?xml version='1.0' encoding="Shift_JIS"?><rss/>"#
+        );
+        assert_eq!(
+            set_xml_declared_encoding("<rss/>", "GBK", true),
+            "<?xml version=\"1.0\" encoding=\"GBK\"?>\n<rss/>"
+        );
+        assert_eq!(set_xml_declared_encoding("<html></html>", "GBK", false), "<html></html>");
+    }
+}
@@ -0,0 +1,241 @@
+//! 字体内联模块
+//!
+//! 将HTML中`@font-face`引用的外部字体下载并内联为data URL，
+//! 保证翻译后的页面在离线环境下也能正确显示字体
+
+// 标准库导入
+use std::path::Path;
+use std::sync::Arc;
+
+// 第三方crate导入
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use futures::future::join_all;
+use regex::{Captures, Regex};
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn};
+use url::Url;
+
+// 本地模块导入
+use crate::api_constants::crawler_config;
+use crate::rate_limiter::HostThrottle;
+use crate::translator::shared_http_client;
+
+/// 根据字体文件扩展名推断MIME类型
+pub fn font_mime_type(font_url: &str) -> &'static str {
+    let path = font_url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    match Path::new(&path).extension().and_then(|ext| ext.to_str()) {
+        Some("woff2") => "font/woff2",
+        Some("woff") => "font/woff",
+        Some("ttf") => "font/ttf",
+        Some("otf") => "font/otf",
+        Some("eot") => "application/vnd.ms-fontobject",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 内联HTML中`@font-face`规则引用的字体
+///
+/// 仅处理尚未内联的引用（非`data:` URL）。相对地址基于`base_url`解析，
+/// 支持`http(s)://`和`file://`两种来源。单个字体获取失败时保留原引用并记录警告。
+///
+/// # Arguments
+///
+/// * `html` - 翻译后的HTML内容
+/// * `base_url` - 解析相对地址使用的基准URL（网页URL或本地文件的file URL）
+///
+/// # Returns
+///
+/// * `Result<String>` - 内联字体后的HTML内容
+pub async fn embed_fonts_in_html(html: &str, base_url: Option<&Url>) -> Result<String> {
+    embed_fonts_in_html_with_concurrency(
+        html,
+        base_url,
+        crawler_config::DEFAULT_MAX_RESOURCE_CONCURRENCY,
+    )
+    .await
+}
+
+/// 内联字体，并用信号量限制同时进行的字体下载数
+pub async fn embed_fonts_in_html_with_concurrency(
+    html: &str,
+    base_url: Option<&Url>,
+    max_concurrency: usize,
+) -> Result<String> {
+    embed_fonts_in_html_with_throttle(html, base_url, max_concurrency, None).await
+}
+
+/// 内联字体，限制并发下载数，并对同一主机的连续下载插入礼貌延迟
+pub async fn embed_fonts_in_html_with_throttle(
+    html: &str,
+    base_url: Option<&Url>,
+    max_concurrency: usize,
+    throttle: Option<&HostThrottle>,
+) -> Result<String> {
+    let font_face_regex = Regex::new(r"(?s)@font-face\s*\{[^}]*\}").context("编译正则表达式失败")?;
+    let font_url_regex =
+        Regex::new(r#"url\(\s*['"]?([^'")]+?)['"]?\s*\)"#).context("编译正则表达式失败")?;
+
+    // 收集需要内联的字体地址
+    let mut font_refs: Vec<String> = Vec::new();
+    for font_face in font_face_regex.find_iter(html) {
+        for captures in font_url_regex.captures_iter(font_face.as_str()) {
+            let font_ref = captures[1].trim().to_string();
+            if !font_ref.starts_with("data:") && !font_refs.contains(&font_ref) {
+                font_refs.push(font_ref);
+            }
+        }
+    }
+
+    if font_refs.is_empty() {
+        return Ok(html.to_string());
+    }
+
+    info!("🔤 检测到 {} 个外部字体引用，开始内联", font_refs.len());
+
+    // 获取字体并转换为data URL，并发数受信号量限制
+    let semaphore = Arc::new(Semaphore::new(max_concurrency.max(1)));
+    let fetches = font_refs.iter().map(|font_ref| {
+        let semaphore = Arc::clone(&semaphore);
+        async move {
+            let _permit = semaphore.acquire().await.ok()?;
+            match fetch_font_as_data_url(font_ref, base_url, throttle).await {
+                Ok(data_url) => {
+                    debug!("已内联字体: {}", font_ref);
+                    Some(data_url)
+                }
+                Err(e) => {
+                    warn!("⚠️ 字体内联失败，保留原引用 {}: {}", font_ref, e);
+                    None
+                }
+            }
+        }
+    });
+    let data_urls: Vec<Option<String>> = join_all(fetches).await;
+
+    // 只替换@font-face规则内部的引用
+    let embedded = font_face_regex.replace_all(html, |font_face: &Captures| {
+        font_url_regex
+            .replace_all(&font_face[0], |url_match: &Captures| {
+                let font_ref = url_match[1].trim();
+                font_refs
+                    .iter()
+                    .position(|r| r == font_ref)
+                    .and_then(|index| data_urls[index].as_ref())
+                    .map(|data_url| format!("url(\"{}\")", data_url))
+                    .unwrap_or_else(|| url_match[0].to_string())
+            })
+            .into_owned()
+    });
+
+    Ok(embedded.into_owned())
+}
+
+/// 获取单个字体并编码为data URL
+async fn fetch_font_as_data_url(
+    font_ref: &str,
+    base_url: Option<&Url>,
+    throttle: Option<&HostThrottle>,
+) -> Result<String> {
+    let font_url = match Url::parse(font_ref) {
+        Ok(url) => url,
+        Err(_) => {
+            let base = base_url.ok_or_else(|| anyhow::anyhow!("相对字体地址缺少基准URL"))?;
+            base.join(font_ref)
+                .with_context(|| format!("解析字体地址失败: {}", font_ref))?
+        }
+    };
+
+    let bytes = match font_url.scheme() {
+        "http" | "https" => {
+            if let Some(throttle) = throttle {
+                throttle.acquire(font_url.as_str()).await;
+            }
+            let response = shared_http_client()?
+                .get(font_url.as_str())
+                .send()
+                .await
+                .context("下载字体失败")?;
+            if !response.status().is_success() {
+                anyhow::bail!("字体下载返回错误状态: {}", response.status());
+            }
+            response.bytes().await.context("读取字体内容失败")?.to_vec()
+        }
+        "file" => {
+            let path = font_url
+                .to_file_path()
+                .map_err(|_| anyhow::anyhow!("无法转换为本地路径: {}", font_url))?;
+            std::fs::read(&path).with_context(|| format!("读取字体文件失败: {}", path.display()))?
+        }
+        scheme => anyhow::bail!("不支持的字体地址协议: {}", scheme),
+    };
+
+    Ok(format!(
+        "data:{};base64,{}",
+        font_mime_type(font_url.path()),
+        general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_mime_type() {
+        assert_eq!(font_mime_type("fonts/a.woff2"), "font/woff2");
+        assert_eq!(font_mime_type("fonts/a.WOFF?v=1"), "font/woff");
+        assert_eq!(font_mime_type("a.ttf#iefix"), "font/ttf");
+        assert_eq!(font_mime_type("a.bin"), "application/octet-stream");
+    }
+
+    #[tokio::test]
+    async fn test_embed_local_font() {
+        let dir = std::env::temp_dir().join("translation-cli-font-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("demo.woff2"), b"FONTDATA").unwrap();
+
+        let html = r#"<style>@font-face { font-family: Demo; src: url('demo.woff2') format('woff2'); }
+body { background: url(bg.png); }</style>"#;
+        let base = Url::from_file_path(dir.join("page.html")).unwrap();
+
+        let embedded = embed_fonts_in_html(html, Some(&base)).await.unwrap();
+        assert!(embedded.contains("data:font/woff2;base64,Rk9OVERBVEE="));
+        // 非@font-face中的引用保持不变
+        assert!(embedded.contains("url(bg.png)"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_embed_multiple_fonts_with_limited_concurrency() {
+        let dir = std::env::temp_dir().join("translation-cli-font-concurrency-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.woff"), b"A").unwrap();
+        std::fs::write(dir.join("b.ttf"), b"B").unwrap();
+
+        let html = r#"<style>@font-face { src: url(a.woff); } @font-face { src: url(b.ttf); }</style>"#;
+        let base = Url::from_file_path(dir.join("page.html")).unwrap();
+
+        let embedded = embed_fonts_in_html_with_concurrency(html, Some(&base), 1)
+            .await
+            .unwrap();
+        assert!(embedded.contains("data:font/woff;base64,QQ=="));
+        assert!(embedded.contains("data:font/ttf;base64,Qg=="));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_embed_keeps_unresolvable_font() {
+        let html = r#"<style>@font-face { src: url("missing.woff"); }</style>"#;
+        let embedded = embed_fonts_in_html(html, None).await.unwrap();
+        assert_eq!(embedded, html);
+    }
+}
@@ -0,0 +1,136 @@
+//! HTML实体处理模块
+//!
+//! Markdown、订阅源以及重复转义的HTML中，提取出的文本可能仍带有字面的HTML实体（`&amp;`、
+//! `&#8220;`、`&nbsp;`）。直接送翻译API会被当作普通字符串翻译或弄乱，因此送翻译前解码为
+//! 真实字符，回写时再按原文的写法重新编码
+
+// 标准库导入
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// 第三方crate导入
+use regex::{Captures, Regex};
+
+/// 支持解码的命名实体；不在表中的实体原样保留
+const NAMED_ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("hellip", '…'),
+    ("mdash", '—'),
+    ("ndash", '–'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("middot", '·'),
+    ("bull", '•'),
+    ("deg", '°'),
+    ("times", '×'),
+    ("divide", '÷'),
+    ("euro", '€'),
+    ("pound", '£'),
+    ("yen", '¥'),
+    ("cent", '¢'),
+];
+
+/// 匹配命名实体和十进制、十六进制字符引用
+fn entity_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[A-Za-z][A-Za-z0-9]{1,31});").expect("实体正则无效")
+    })
+}
+
+/// 解码单个实体（不含`&`和`;`），无法识别时返回None
+fn decode_entity(body: &str) -> Option<char> {
+    let code_point = if let Some(hex) = body.strip_prefix("#x").or_else(|| body.strip_prefix("#X")) {
+        u32::from_str_radix(hex, 16).ok()?
+    } else if let Some(decimal) = body.strip_prefix('#') {
+        decimal.parse().ok()?
+    } else {
+        return NAMED_ENTITIES
+            .iter()
+            .find(|(name, _)| *name == body)
+            .map(|(_, character)| *character);
+    };
+    char::from_u32(code_point).filter(|character| *character != '\0')
+}
+
+/// 将文本中的HTML实体解码为真实字符
+pub fn decode_html_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    entity_regex()
+        .replace_all(text, |captures: &Captures| match decode_entity(&captures[1]) {
+            Some(character) => character.to_string(),
+            None => captures[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// 按原文的实体写法重新编码译文
+///
+/// 原文中以实体出现、且从未以字面形式出现的字符，在译文中改回同样的实体写法；
+/// 原文没有实体时译文原样返回。
+pub fn reencode_html_entities(original: &str, translation: &str) -> String {
+    if !original.contains('&') {
+        return translation.to_string();
+    }
+
+    let literal_text = entity_regex().replace_all(original, "");
+    let mut encodings: HashMap<char, &str> = HashMap::new();
+    for captures in entity_regex().captures_iter(original) {
+        if let Some(character) = decode_entity(&captures[1]) {
+            if !literal_text.contains(character) {
+                encodings
+                    .entry(character)
+                    .or_insert(captures.get(0).map_or("", |entity| entity.as_str()));
+            }
+        }
+    }
+    if encodings.is_empty() {
+        return translation.to_string();
+    }
+
+    let mut encoded = String::with_capacity(translation.len());
+    for character in translation.chars() {
+        match encodings.get(&character) {
+            Some(entity) => encoded.push_str(entity),
+            None => encoded.push(character),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_html_entities() {
+        assert_eq!(decode_html_entities("Tom &amp; Jerry"), "Tom & Jerry");
+        assert_eq!(decode_html_entities("&#8220;Hi&#x201D;&nbsp;there"), "“Hi”\u{a0}there");
+        assert_eq!(decode_html_entities("AT&T &unknown; &#0;"), "AT&T &unknown; &#0;");
+    }
+
+    #[test]
+    fn test_reencode_follows_original_spelling() {
+        assert_eq!(
+            reencode_html_entities("Tom &amp; Jerry&nbsp;Show", "汤姆 & 杰瑞\u{a0}秀"),
+            "汤姆 &amp; 杰瑞&nbsp;秀"
+        );
+        // 原文中`&`也以字面形式出现时不重新编码
+        assert_eq!(reencode_html_entities("R&D &amp; QA", "研发 & 质检"), "研发 & 质检");
+        assert_eq!(reencode_html_entities("Tom & Jerry", "汤姆 & 杰瑞"), "汤姆 & 杰瑞");
+    }
+}
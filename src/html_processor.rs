@@ -8,14 +8,324 @@ use std::collections::{HashMap, HashSet, VecDeque};
 // 第三方crate导入
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine as _};
-use markup5ever_rcdom::{NodeData, RcDom};
+use html5ever::tendril::TendrilSink;
+use html5ever::{local_name, ns, parse_document, parse_fragment, QualName};
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
 use regex::Regex;
+use tracing::{debug, info};
 
 // 本地模块导入
-use crate::utils::{is_translatable_text, extract_base64_from_data_uri};
+use crate::date_localizer::localize_datetime;
+use crate::utils::{is_translatable_text, extract_base64_from_data_uri, strip_utf8_bom};
+
+/// HTML文本提取与回写的处理选项
+///
+/// 提取与回写必须使用同一份选项，保证两端处理范围一致。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlProcessingOptions {
+    /// 是否翻译`<noscript>`中的内容
+    pub translate_noscript: bool,
+    /// 是否翻译文本节点（含内嵌HTML）
+    pub translate_text: bool,
+    /// 是否从`<script>`中提取疑似界面文本的字符串（噪音较多，默认关闭）
+    pub translate_scripts: bool,
+    /// 是否翻译`title`、`alt`、`placeholder`属性
+    pub translate_attributes: bool,
+    /// 值为JSON的属性名（如`x-data`、`data-props`），其中白名单字段的字符串会被翻译
+    pub json_attributes: Vec<String>,
+    /// JSON属性中需要翻译的字段名白名单
+    pub json_fields: Vec<String>,
+    /// 设置时按该语言格式化`<time datetime>`的显示文本，代替字面翻译
+    pub date_locale: Option<String>,
+    /// 是否跳过内容为纯数字/日期的`<td>`单元格
+    pub skip_numeric_cells: bool,
+    /// 只翻译这些元素（小写标签名）的`title`属性，为空时所有元素的`title`都翻译
+    pub title_tags: Vec<String>,
+    /// 设置时跳过`lang`属性已标为该语言的区域，以最近的带`lang`的祖先为准
+    pub skip_lang: Option<String>,
+}
+
+impl Default for HtmlProcessingOptions {
+    fn default() -> Self {
+        Self {
+            translate_noscript: true,
+            translate_text: true,
+            translate_scripts: false,
+            translate_attributes: true,
+            json_attributes: Vec::new(),
+            json_fields: DEFAULT_JSON_TEXT_FIELDS.iter().map(|f| f.to_string()).collect(),
+            date_locale: None,
+            skip_numeric_cells: false,
+            title_tags: Vec::new(),
+            skip_lang: None,
+        }
+    }
+}
+
+/// JSON属性中默认翻译的界面文本字段
+pub const DEFAULT_JSON_TEXT_FIELDS: &[&str] = &[
+    "title",
+    "subtitle",
+    "heading",
+    "text",
+    "label",
+    "msg",
+    "message",
+    "description",
+    "placeholder",
+    "tooltip",
+    "caption",
+    "alt",
+];
+
+/// 图片懒加载相关属性，值为真实图片地址或加载策略，任何配置下都不翻译
+pub const LAZY_LOAD_ATTRIBUTES: &[&str] = &[
+    "data-src",
+    "data-srcset",
+    "data-sizes",
+    "data-lazy",
+    "data-lazy-src",
+    "data-lazy-srcset",
+    "data-original",
+    "loading",
+];
+
+/// 表单约束属性，值为正则、数值或类型等机器可读内容，任何配置下都不翻译
+pub const FORM_CONSTRAINT_ATTRIBUTES: &[&str] = &[
+    "pattern",
+    "min",
+    "max",
+    "step",
+    "minlength",
+    "maxlength",
+    "type",
+    "inputmode",
+    "autocomplete",
+    "accept",
+    "required",
+];
+
+/// 可携带自定义验证消息的表单元素
+const FORM_CONTROL_TAGS: &[&str] = &["input", "textarea", "select", "form"];
+
+/// 是否为表单元素上的自定义验证消息属性，如`data-error`、`data-msg`、`data-pattern-message`、
+/// `data-parsley-required-message`、`data-validation-error-msg`
+fn is_validation_message_attribute(tag_name: &str, attr_name: &str) -> bool {
+    if !FORM_CONTROL_TAGS.contains(&tag_name) {
+        return false;
+    }
+    let Some(name) = attr_name.strip_prefix("data-") else {
+        return false;
+    };
+    let last_segment = name.rsplit('-').next().unwrap_or(name);
+    matches!(last_segment, "message" | "msg" | "error" | "errormessage")
+}
+
+/// Schema.org微数据中值为自然语言文本的属性，`<meta itemprop="..." content="...">`的content按文本翻译
+pub const MICRODATA_TEXT_PROPERTIES: &[&str] = &[
+    "name",
+    "alternateName",
+    "headline",
+    "alternativeHeadline",
+    "description",
+    "disambiguatingDescription",
+    "abstract",
+    "caption",
+    "text",
+    "articleBody",
+    "reviewBody",
+    "slogan",
+    "keywords",
+];
+
+/// 是否为携带文本型微数据的`<meta itemprop>`；`itemprop`可含多个以空白分隔的属性名
+fn is_microdata_text_meta(tag_name: &str, itemprop: Option<&str>) -> bool {
+    tag_name == "meta"
+        && itemprop.is_some_and(|itemprop| {
+            itemprop
+                .split_ascii_whitespace()
+                .any(|property| MICRODATA_TEXT_PROPERTIES.contains(&property))
+        })
+}
+
+/// DOM元素的content属性是否作为微数据文本翻译
+fn translates_meta_content(tag_name: &str, attrs: &[html5ever::Attribute]) -> bool {
+    let itemprop = attrs.iter().find(|attr| attr.name.local.as_ref() == "itemprop");
+    is_microdata_text_meta(tag_name, itemprop.map(|attr| attr.value.as_ref()))
+}
+
+impl HtmlProcessingOptions {
+    /// 属性是否按普通文本翻译：`alt`、`placeholder`和表单验证消息总是翻译，`title`受元素白名单限制
+    fn is_text_attribute(&self, tag_name: &str, attr_name: &str) -> bool {
+        match attr_name {
+            "alt" | "placeholder" => true,
+            "title" => self.title_tags.is_empty() || self.title_tags.iter().any(|tag| tag.eq_ignore_ascii_case(tag_name)),
+            _ => is_validation_message_attribute(tag_name, attr_name),
+        }
+    }
+
+    /// 属性是否按JSON翻译；懒加载属性和表单约束属性即使被配置也排除在外
+    fn is_json_attribute(&self, attr_name: &str) -> bool {
+        !LAZY_LOAD_ATTRIBUTES.iter().any(|lazy| lazy.eq_ignore_ascii_case(attr_name))
+            && !FORM_CONSTRAINT_ATTRIBUTES.iter().any(|constraint| constraint.eq_ignore_ascii_case(attr_name))
+            && self.json_attributes.iter().any(|name| name == attr_name)
+    }
+}
+
+/// 两个语言标签是否表示同一语言：主语言子标签相同，且双方都带第二个子标签
+/// （地区或书写系统，如`zh-TW`与`zh-CN`）时也要相同；不区分大小写，`_`视同`-`
+pub fn is_same_language(lang: &str, target: &str) -> bool {
+    let subtags = |tag: &str| {
+        tag.trim()
+            .to_ascii_lowercase()
+            .split(['-', '_'])
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    let (lang, target) = (subtags(lang), subtags(target));
+    if lang[0].is_empty() || lang[0] != target[0] {
+        return false;
+    }
+    match (lang.get(1), target.get(1)) {
+        (Some(lang_sub), Some(target_sub)) => lang_sub == target_sub,
+        _ => true,
+    }
+}
+
+/// 节点所在区域是否已标注为要跳过的语言：以自身或最近的带`lang`属性的祖先为准，
+/// `lang=""`表示语言未知，按未标注处理
+fn in_skipped_lang_region(node: &Handle, options: &HtmlProcessingOptions) -> bool {
+    let Some(skip_lang) = options.skip_lang.as_deref() else {
+        return false;
+    };
+    let mut current = Some(node.clone());
+    while let Some(node) = current {
+        if let NodeData::Element { ref attrs, .. } = node.data {
+            if let Some(lang) = attrs.borrow().iter().find(|attr| attr.name.local.as_ref() == "lang") {
+                return is_same_language(&lang.value, skip_lang);
+            }
+        }
+        let weak = node.parent.take();
+        current = weak.as_ref().and_then(|weak| weak.upgrade());
+        node.parent.set(weak);
+    }
+    false
+}
+
+/// 文本翻译优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TextPriority {
+    /// 首屏可见的高优先级文本（标题、导航、首段等）
+    High,
+    /// 其余文本
+    Normal,
+}
+
+/// 带优先级的可翻译文本
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrioritizedText {
+    pub text: String,
+    pub priority: TextPriority,
+}
+
+/// 高优先级元素：其子树中的文本会被优先翻译
+const HIGH_PRIORITY_TAGS: &[&str] = &["title", "h1", "nav"];
+
+/// 提取带优先级的可翻译文本
+///
+/// 文本集合与[`extract_translatable_texts`]一致，位于`<title>`、`<h1>`、`<nav>`
+/// 以及文档中第一个`<p>`内的文本标记为高优先级。结果按优先级稳定排序，
+/// 高优先级文本在前，同一优先级内保持原有顺序。
+pub fn extract_prioritized_texts(
+    dom: &RcDom,
+    options: &HtmlProcessingOptions,
+) -> Vec<PrioritizedText> {
+    let mut high_priority_texts = HashSet::new();
+    let mut first_paragraph_seen = false;
+    collect_high_priority_texts(
+        &dom.document,
+        false,
+        &mut first_paragraph_seen,
+        &mut high_priority_texts,
+    );
+
+    let mut prioritized: Vec<PrioritizedText> = extract_translatable_texts_with_options(dom, options)
+        .into_iter()
+        .map(|text| {
+            let priority = if high_priority_texts.contains(&text) {
+                TextPriority::High
+            } else {
+                TextPriority::Normal
+            };
+            PrioritizedText { text, priority }
+        })
+        .collect();
+
+    prioritized.sort_by_key(|t| t.priority);
+    prioritized
+}
+
+/// 按文档顺序深度优先遍历，收集高优先级区域中的文本和属性值
+fn collect_high_priority_texts(
+    node: &Handle,
+    in_high_priority: bool,
+    first_paragraph_seen: &mut bool,
+    high_priority_texts: &mut HashSet<String>,
+) {
+    let mut in_high_priority = in_high_priority;
+
+    match node.data {
+        NodeData::Text { ref contents } if in_high_priority => {
+            high_priority_texts.insert(contents.borrow().trim().to_string());
+        }
+        NodeData::Element {
+            ref name,
+            ref attrs,
+            ..
+        } => {
+            let tag_name = name.local.as_ref();
+            if HIGH_PRIORITY_TAGS.contains(&tag_name) {
+                in_high_priority = true;
+            } else if tag_name == "p" && !*first_paragraph_seen {
+                *first_paragraph_seen = true;
+                in_high_priority = true;
+            }
+
+            if in_high_priority {
+                for attr in attrs.borrow().iter() {
+                    if matches!(attr.name.local.as_ref(), "title" | "alt" | "placeholder") {
+                        high_priority_texts.insert(attr.value.trim().to_string());
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    for child in node.children.borrow().iter() {
+        collect_high_priority_texts(
+            child,
+            in_high_priority,
+            first_paragraph_seen,
+            high_priority_texts,
+        );
+    }
+}
 
 /// 提取DOM中的可翻译文本
 pub fn extract_translatable_texts(dom: &RcDom) -> Vec<String> {
+    extract_translatable_texts_with_options(dom, &HtmlProcessingOptions::default())
+}
+
+/// 按处理选项提取DOM中的可翻译文本
+///
+/// 启用脚本解析时，html5ever将`<noscript>`内容保留为原始HTML文本，
+/// 这里将其作为HTML片段解析后再提取，与[`apply_translations_to_dom_with_options`]对称。
+pub fn extract_translatable_texts_with_options(
+    dom: &RcDom,
+    options: &HtmlProcessingOptions,
+) -> Vec<String> {
     let mut texts = Vec::new();
     let mut seen_texts = HashSet::new(); // 防止重复
     let mut queue = VecDeque::new();
@@ -28,7 +338,7 @@ pub fn extract_translatable_texts(dom: &RcDom) -> Vec<String> {
 
     while let Some(node) = queue.pop_front() {
         match node.data {
-            NodeData::Text { ref contents } => {
+            NodeData::Text { ref contents } if options.translate_text && !in_skipped_lang_region(&node, options) => {
                 let text = contents.borrow().to_string();
                 let trimmed = text.trim();
                 // 更宽松的文本过滤条件
@@ -49,24 +359,55 @@ pub fn extract_translatable_texts(dom: &RcDom) -> Vec<String> {
             } => {
                 let tag_name = name.local.as_ref();
 
+                if tag_name == "noscript" && !options.translate_noscript {
+                    continue;
+                }
+                if options.skip_numeric_cells && tag_name == "td" && is_numeric_cell(&collect_text_content(&node)) {
+                    continue;
+                }
+
+                // 已标为目标语言的区域不提取属性和脚本文本，子节点仍继续遍历以处理其中的外文区域
+                let in_skipped_lang = in_skipped_lang_region(&node, options);
+
                 // 检查可翻译属性
-                for attr in attrs.borrow().iter() {
+                let microdata_meta = translates_meta_content(tag_name, &attrs.borrow());
+                for attr in attrs.borrow().iter().filter(|_| !in_skipped_lang) {
                     let attr_name = attr.name.local.as_ref();
                     let attr_value = attr.value.trim();
 
-                    // 标准可翻译属性
-                    if matches!(attr_name, "title" | "alt" | "placeholder") {
-                        if attr_value.len() > 1
-                            && !attr_value.chars().all(|c| c.is_whitespace())
-                            && !seen_texts.contains(attr_value)
-                        {
-                            texts.push(attr_value.to_string());
-                            seen_texts.insert(attr_value.to_string());
+                    // 标准可翻译属性及微数据<meta itemprop>的content
+                    if options.translate_attributes
+                        && (options.is_text_attribute(tag_name, attr_name)
+                            || (microdata_meta && attr_name == "content"))
+                        && attr_value.len() > 1
+                        && !attr_value.chars().all(|c| c.is_whitespace())
+                        && !seen_texts.contains(attr_value)
+                    {
+                        texts.push(attr_value.to_string());
+                        seen_texts.insert(attr_value.to_string());
+                    }
+
+                    // 值为JSON的属性：提取白名单字段中的字符串
+                    if options.translate_attributes && options.is_json_attribute(attr_name) {
+                        for text in collect_json_attribute_texts(&attr.value, &options.json_fields) {
+                            if seen_texts.insert(text.clone()) {
+                                texts.push(text);
+                            }
+                        }
+                    }
+
+                    // 图片中内嵌的SVG data URI：提取其中<text>的文字
+                    if options.translate_text && tag_name == "img" && attr_name == "src" {
+                        for text in collect_svg_data_uri_texts(attr_value) {
+                            if seen_texts.insert(text.clone()) {
+                                texts.push(text);
+                            }
                         }
                     }
 
                     // 特殊处理iframe的Base64编码内容
-                    if tag_name == "iframe"
+                    if options.translate_text
+                        && tag_name == "iframe"
                         && attr_name == "src"
                         && attr_value.contains("data:text/html;")
                     {
@@ -75,7 +416,7 @@ pub fn extract_translatable_texts(dom: &RcDom) -> Vec<String> {
                                 general_purpose::STANDARD.decode(&base64_content)
                             {
                                 if let Ok(decoded_str) = String::from_utf8(decoded_html) {
-                                    println!(
+                                    debug!(
                                         "🔍 解析Base64编码的HTML内容 ({} 字符)",
                                         decoded_str.len()
                                     );
@@ -90,8 +431,8 @@ pub fn extract_translatable_texts(dom: &RcDom) -> Vec<String> {
                     }
                 }
 
-                // 处理JavaScript代码中的文本
-                if tag_name == "script" {
+                // 处理JavaScript代码中的文本（需显式开启）
+                if tag_name == "script" && options.translate_text && options.translate_scripts && !in_skipped_lang {
                     // 我们仍需要遍历script标签的子节点来获取内容
                     for child in node.children.borrow().iter() {
                         if let NodeData::Text { ref contents } = child.data {
@@ -116,8 +457,24 @@ pub fn extract_translatable_texts(dom: &RcDom) -> Vec<String> {
             _ => "",
         };
 
-        if tag_name != "script" {
-            // script标签的内容已经单独处理
+        if tag_name == "noscript" {
+            // noscript中的原始HTML文本按片段解析后提取
+            for child in node.children.borrow().iter() {
+                match child.data {
+                    NodeData::Text { .. } if in_skipped_lang_region(&node, options) => {}
+                    NodeData::Text { ref contents } => {
+                        let fragment = parse_html_fragment(&contents.borrow());
+                        for text in extract_translatable_texts_with_options(&fragment, options) {
+                            if seen_texts.insert(text.clone()) {
+                                texts.push(text);
+                            }
+                        }
+                    }
+                    _ => queue.push_back(child.clone()),
+                }
+            }
+        } else if tag_name != "script" && localized_time_text(&node, options).is_none() {
+            // script标签的内容已经单独处理，本地化的<time>文本不送翻译
             for child in node.children.borrow().iter() {
                 queue.push_back(child.clone());
             }
@@ -127,6 +484,139 @@ pub fn extract_translatable_texts(dom: &RcDom) -> Vec<String> {
     texts
 }
 
+/// 带来源上下文的可翻译文本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedText {
+    /// 文本内容（已去除首尾空白）
+    pub text: String,
+    /// 文本所在元素的标签名，属性文本为属性所属元素；脚本中提取的为`script`，
+    /// 无法定位来源（如Base64内嵌页面）时为空字符串
+    pub parent_tag: String,
+    /// 是否来自属性值
+    pub is_attribute: bool,
+    /// 来源属性名，仅属性文本有值
+    pub attr_name: Option<String>,
+}
+
+/// 文本来源：所在元素标签名和属性名
+type TextContext = (String, Option<String>);
+
+/// 提取可翻译文本及其父标签、来源属性
+///
+/// 文本集合与顺序与[`extract_translatable_texts_with_options`]一致；同一文本多处出现时
+/// 记录第一次出现处的上下文。供按标签应用不同翻译策略等精细化处理使用。
+pub fn extract_texts_with_context(
+    dom: &RcDom,
+    options: &HtmlProcessingOptions,
+) -> Vec<ExtractedText> {
+    let mut contexts: HashMap<String, TextContext> = HashMap::new();
+    let mut scripts = Vec::new();
+    collect_text_contexts(dom, options, &mut contexts, &mut scripts);
+
+    extract_translatable_texts_with_options(dom, options)
+        .into_iter()
+        .map(|text| {
+            let (parent_tag, attr_name) = contexts.get(&text).cloned().unwrap_or_else(|| {
+                let in_script = scripts.iter().any(|script| script.contains(text.as_str()));
+                (if in_script { "script" } else { "" }.to_string(), None)
+            });
+            ExtractedText {
+                text,
+                parent_tag,
+                is_attribute: attr_name.is_some(),
+                attr_name,
+            }
+        })
+        .collect()
+}
+
+/// 按与提取相同的遍历顺序记录每个文本第一次出现处的上下文，并收集脚本内容
+fn collect_text_contexts(
+    dom: &RcDom,
+    options: &HtmlProcessingOptions,
+    contexts: &mut HashMap<String, TextContext>,
+    scripts: &mut Vec<String>,
+) {
+    let mut queue = VecDeque::new();
+    queue.push_back((dom.document.clone(), String::new()));
+
+    while let Some((node, parent_tag)) = queue.pop_front() {
+        let tag_name = match node.data {
+            NodeData::Text { ref contents } => {
+                if options.translate_text && !in_skipped_lang_region(&node, options) {
+                    contexts
+                        .entry(contents.borrow().trim().to_string())
+                        .or_insert_with(|| (parent_tag, None));
+                }
+                continue;
+            }
+            NodeData::Element { ref name, ref attrs, .. } => {
+                let tag_name = name.local.to_string();
+                if tag_name == "noscript" && !options.translate_noscript {
+                    continue;
+                }
+                if options.skip_numeric_cells && tag_name == "td" && is_numeric_cell(&collect_text_content(&node)) {
+                    continue;
+                }
+
+                let microdata_meta = translates_meta_content(&tag_name, &attrs.borrow());
+                let in_skipped_lang = in_skipped_lang_region(&node, options);
+                for attr in attrs.borrow().iter().filter(|_| !in_skipped_lang) {
+                    let attr_name = attr.name.local.as_ref();
+                    let mut attr_texts = Vec::new();
+                    if options.translate_attributes
+                        && (options.is_text_attribute(&tag_name, attr_name)
+                            || (microdata_meta && attr_name == "content"))
+                    {
+                        attr_texts.push(attr.value.trim().to_string());
+                    }
+                    if options.translate_attributes && options.is_json_attribute(attr_name) {
+                        attr_texts.extend(collect_json_attribute_texts(&attr.value, &options.json_fields));
+                    }
+                    if options.translate_text && tag_name == "img" && attr_name == "src" {
+                        attr_texts.extend(collect_svg_data_uri_texts(attr.value.trim()));
+                    }
+                    for text in attr_texts {
+                        contexts
+                            .entry(text)
+                            .or_insert_with(|| (tag_name.clone(), Some(attr_name.to_string())));
+                    }
+                }
+                tag_name
+            }
+            _ => String::new(),
+        };
+
+        match tag_name.as_str() {
+            "script" => {
+                for child in node.children.borrow().iter() {
+                    if let NodeData::Text { ref contents } = child.data {
+                        scripts.push(contents.borrow().to_string());
+                    }
+                }
+            }
+            "noscript" => {
+                for child in node.children.borrow().iter() {
+                    match child.data {
+                        NodeData::Text { .. } if in_skipped_lang_region(&node, options) => {}
+                        NodeData::Text { ref contents } => {
+                            let fragment = parse_html_fragment(&contents.borrow());
+                            collect_text_contexts(&fragment, options, contexts, scripts);
+                        }
+                        _ => queue.push_back((child.clone(), tag_name.clone())),
+                    }
+                }
+            }
+            _ if localized_time_text(&node, options).is_none() => {
+                for child in node.children.borrow().iter() {
+                    queue.push_back((child.clone(), tag_name.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 /// 从HTML字符串中提取可翻译文本
 fn extract_texts_from_html_string(
     html: &str,
@@ -134,7 +624,7 @@ fn extract_texts_from_html_string(
     seen_texts: &mut HashSet<String>,
 ) {
     // 简单的HTML文本提取正则表达式
-    let html_text_regex = match Regex::new(r">([^<>{3,})<") {
+    let html_text_regex = match Regex::new(r">([^<>]{3,})<") {
         Ok(regex) => regex,
         Err(_) => {
             eprintln!("警告: 无法编译HTML文本正则表达式");
@@ -151,7 +641,7 @@ fn extract_texts_from_html_string(
                     .all(|c| c.is_whitespace() || c.is_ascii_punctuation())
                 && !seen_texts.contains(text)
             {
-                println!("🎯 从Base64 HTML中提取: '{}'", text);
+                debug!("🎯 从Base64 HTML中提取: '{}'", text);
                 texts.push(text.to_string());
                 seen_texts.insert(text.to_string());
             }
@@ -174,7 +664,7 @@ fn extract_texts_from_html_string(
                phrase.split_whitespace().count() <= 6 &&  // 避免提取过长的文本
                !seen_texts.contains(phrase)
             {
-                println!("📝 从Base64 HTML中提取英文短语: '{}'", phrase);
+                debug!("📝 从Base64 HTML中提取英文短语: '{}'", phrase);
                 texts.push(phrase.to_string());
                 seen_texts.insert(phrase.to_string());
             }
@@ -195,7 +685,7 @@ fn extract_texts_from_javascript(
         if let Some(string_match) = captures.get(1) {
             let text = string_match.as_str().trim();
             if is_translatable_text(text) && !seen_texts.contains(text) {
-                println!("🔧 从JavaScript中提取: '{}'", text);
+                debug!("🔧 从JavaScript中提取: '{}'", text);
                 texts.push(text.to_string());
                 seen_texts.insert(text.to_string());
             }
@@ -215,7 +705,7 @@ fn extract_texts_from_javascript(
         if let Some(value_match) = captures.get(1) {
             let text_value = value_match.as_str().trim();
             if is_translatable_text(text_value) && !seen_texts.contains(text_value) {
-                println!("🔨 从JavaScript JSON \"text\"中提取: '{}'", text_value);
+                debug!("🔨 从JavaScript JSON \"text\"中提取: '{}'", text_value);
                 texts.push(text_value.to_string());
                 seen_texts.insert(text_value.to_string());
             }
@@ -227,7 +717,7 @@ fn extract_texts_from_javascript(
         if let Some(prop_match) = captures.get(1) {
             let prop_name = prop_match.as_str().trim();
             if is_translatable_text(prop_name) && !seen_texts.contains(prop_name) {
-                println!("🔨 从JavaScript JSON属性中提取: '{}'", prop_name);
+                debug!("🔨 从JavaScript JSON属性中提取: '{}'", prop_name);
                 texts.push(prop_name.to_string());
                 seen_texts.insert(prop_name.to_string());
             }
@@ -254,7 +744,7 @@ fn extract_texts_from_javascript(
                     && is_translatable_text(value)
                     && !seen_texts.contains(value)
                 {
-                    println!("🎯 从JavaScript JSON \"{}\"中提取: '{}'", key, value);
+                    debug!("🎯 从JavaScript JSON \"{}\"中提取: '{}'", key, value);
                     texts.push(value.to_string());
                     seen_texts.insert(value.to_string());
                 }
@@ -269,42 +759,37 @@ pub fn apply_translations_to_dom(
     original_texts: &[String],
     translations: &[String],
 ) -> Result<RcDom> {
-    // 创建翻译映射表，添加调试信息
-    let translation_map: HashMap<String, String> = original_texts
-        .iter()
-        .zip(translations.iter())
-        .filter(|(_, trans)| !trans.is_empty())
-        .map(|(orig, trans)| {
-            println!("映射: '{}' -> '{}'", orig, trans);
-            (orig.clone(), trans.clone())
-        })
-        .collect();
+    apply_translations_to_dom_with_options(
+        dom,
+        original_texts,
+        translations,
+        &HtmlProcessingOptions::default(),
+    )
+}
+
+/// 按处理选项将翻译结果应用到DOM
+pub fn apply_translations_to_dom_with_options(
+    dom: RcDom,
+    original_texts: &[String],
+    translations: &[String],
+    options: &HtmlProcessingOptions,
+) -> Result<RcDom> {
+    let translation_map = build_translation_map(original_texts, translations);
 
-    println!("📝 创建翻译映射: {} 个翻译对", translation_map.len());
+    info!("📝 创建翻译映射: {} 个翻译对", translation_map.len());
 
-    // 遍历DOM并应用翻译
+    // 遍历DOM：属性等就地替换，文本节点先收集，遍历结束后并行准备替换内容、串行写回
     let mut queue = VecDeque::new();
     let mut applied_count = 0;
+    let mut text_nodes = Vec::new();
+    let mut node_texts = Vec::new();
     queue.push_back(dom.document.clone());
 
     while let Some(node) = queue.pop_front() {
         match node.data {
-            NodeData::Text { ref contents } => {
-                let text = contents.borrow().to_string();
-                let trimmed = text.trim();
-                if let Some(translation) = translation_map.get(trimmed) {
-                    let mut content_ref = contents.borrow_mut();
-                    content_ref.clear();
-                    content_ref.push_slice(translation);
-                    applied_count += 1;
-                    println!("✅ 应用翻译: '{}' -> '{}'", trimmed, translation);
-                } else if trimmed.len() > 1
-                    && !trimmed
-                        .chars()
-                        .all(|c| c.is_whitespace() || c.is_ascii_punctuation())
-                {
-                    println!("❌ 未找到翻译: '{}'", trimmed);
-                }
+            NodeData::Text { ref contents } if options.translate_text && !in_skipped_lang_region(&node, options) => {
+                node_texts.push(contents.borrow().to_string());
+                text_nodes.push(node.clone());
             }
             NodeData::Element {
                 ref name,
@@ -312,16 +797,38 @@ pub fn apply_translations_to_dom(
                 ..
             } => {
                 let tag_name = name.local.as_ref();
-                if !matches!(tag_name, "script" | "style" | "noscript") {
+                if tag_name == "noscript" && !options.translate_noscript {
+                    continue;
+                }
+                if options.skip_numeric_cells && tag_name == "td" && is_numeric_cell(&collect_text_content(&node)) {
+                    continue;
+                }
+                let in_skipped_lang = in_skipped_lang_region(&node, options);
+                if options.translate_attributes && !matches!(tag_name, "script" | "style") && !in_skipped_lang {
                     // 翻译属性
+                    let microdata_meta = translates_meta_content(tag_name, &attrs.borrow());
                     for attr in attrs.borrow_mut().iter_mut() {
                         let attr_name = attr.name.local.as_ref();
-                        if matches!(attr_name, "title" | "alt" | "placeholder") {
+                        if options.is_json_attribute(attr_name) {
+                            if let Some(translated) = translate_json_attribute(
+                                &attr.value,
+                                &options.json_fields,
+                                &translation_map,
+                            ) {
+                                debug!("✅ 应用JSON属性翻译: {}", attr_name);
+                                attr.value = translated.into();
+                                applied_count += 1;
+                            }
+                            continue;
+                        }
+                        if options.is_text_attribute(tag_name, attr_name)
+                            || (microdata_meta && attr_name == "content")
+                        {
                             let value = attr.value.trim().to_string(); // 避免借用问题
-                            if let Some(translation) = translation_map.get(&value) {
-                                attr.value = translation.clone().into();
+                            if let Some(translation) = translation_map.get(value.as_str()) {
+                                attr.value = (*translation).into();
                                 applied_count += 1;
-                                println!(
+                                debug!(
                                     "✅ 应用属性翻译: {}='{}' -> '{}'",
                                     attr_name, value, translation
                                 );
@@ -329,6 +836,67 @@ pub fn apply_translations_to_dom(
                         }
                     }
                 }
+
+                // <time>的显示文本按datetime本地化，不做字面翻译
+                if let Some(localized) = localized_time_text(&node, options) {
+                    let children = node.children.borrow();
+                    let text: String = children
+                        .iter()
+                        .filter_map(|child| match child.data {
+                            NodeData::Text { ref contents } => Some(contents.borrow().to_string()),
+                            _ => None,
+                        })
+                        .collect();
+                    if let NodeData::Text { ref contents } = children[0].data {
+                        let leading = &text[..text.len() - text.trim_start().len()];
+                        let trailing = &text[text.trim_end().len()..];
+                        let mut content_ref = contents.borrow_mut();
+                        content_ref.clear();
+                        content_ref.push_slice(leading);
+                        content_ref.push_slice(&localized);
+                        content_ref.push_slice(trailing);
+                    }
+                    drop(children);
+                    node.children.borrow_mut().truncate(1);
+                    applied_count += 1;
+                    debug!("📅 本地化日期: '{}' -> '{}'", text.trim(), localized);
+                    continue;
+                }
+
+                if options.translate_text && tag_name == "img" && !in_skipped_lang {
+                    for attr in attrs.borrow_mut().iter_mut() {
+                        if attr.name.local.as_ref() != "src" {
+                            continue;
+                        }
+                        if let Some(translated) = translate_svg_data_uri(&attr.value, &translation_map) {
+                            debug!("✅ 应用SVG data URI翻译");
+                            attr.value = translated.into();
+                            applied_count += 1;
+                        }
+                    }
+                }
+
+                if tag_name == "noscript" {
+                    // noscript中的原始HTML文本按片段翻译后写回，其余子节点正常遍历
+                    for child in node.children.borrow().iter() {
+                        match child.data {
+                            NodeData::Text { .. } if in_skipped_lang => {}
+                            NodeData::Text { ref contents } => {
+                                let translated = translate_raw_html_text(
+                                    &contents.borrow(),
+                                    original_texts,
+                                    translations,
+                                    options,
+                                )?;
+                                let mut content_ref = contents.borrow_mut();
+                                content_ref.clear();
+                                content_ref.push_slice(&translated);
+                            }
+                            _ => queue.push_back(child.clone()),
+                        }
+                    }
+                    continue;
+                }
             }
             _ => {}
         }
@@ -339,25 +907,1983 @@ pub fn apply_translations_to_dom(
         }
     }
 
-    println!("🎯 总共应用了 {} 个翻译", applied_count);
+    let workers = std::thread::available_parallelism().map_or(1, |workers| workers.get());
+    let replacements = prepare_text_replacements(&node_texts, &translation_map, workers);
+    for ((node, text), replacement) in text_nodes.iter().zip(&node_texts).zip(replacements) {
+        let trimmed = text.trim();
+        match replacement {
+            Some(replacement) => {
+                if let NodeData::Text { ref contents } = node.data {
+                    let mut content_ref = contents.borrow_mut();
+                    content_ref.clear();
+                    content_ref.push_slice(&replacement);
+                }
+                applied_count += 1;
+                debug!("✅ 应用翻译: '{}' -> '{}'", trimmed, replacement.trim());
+            }
+            None if trimmed.len() > 1
+                && !trimmed
+                    .chars()
+                    .all(|c| c.is_whitespace() || c.is_ascii_punctuation()) =>
+            {
+                debug!("❌ 未找到翻译: '{}'", trimmed);
+            }
+            None => {}
+        }
+    }
+
+    info!("🎯 总共应用了 {} 个翻译", applied_count);
     Ok(dom)
 }
 
-/// 序列化DOM为HTML字符串
-pub fn serialize_dom_to_html(dom: RcDom) -> Result<String> {
-    use html5ever::serialize::{serialize, SerializeOpts};
-    use markup5ever_rcdom::SerializableHandle;
-    use std::io::Cursor;
+/// 原文到译文的映射，借用调用方的文本，避免大文档回写时复制全部原文和译文
+type TranslationMap<'a> = HashMap<&'a str, &'a str>;
 
-    let mut buffer = Vec::new();
-    let cursor = Cursor::new(&mut buffer);
+/// 建立原文到译文的映射，跳过空译文
+fn build_translation_map<'a>(original_texts: &'a [String], translations: &'a [String]) -> TranslationMap<'a> {
+    original_texts
+        .iter()
+        .zip(translations)
+        .filter(|(_, translation)| !translation.is_empty())
+        .map(|(original, translation)| (original.as_str(), translation.as_str()))
+        .collect()
+}
 
-    serialize(
-        cursor,
-        &SerializableHandle::from(dom.document.clone()),
-        SerializeOpts::default(),
-    )
-    .map_err(|e| anyhow::anyhow!("HTML序列化失败: {:?}", e))?;
+/// 文本节点数达到该值时才并行准备替换内容，节点较少时线程开销得不偿失
+const PARALLEL_PREPARE_MIN_NODES: usize = 4096;
 
-    String::from_utf8(buffer).map_err(|e| anyhow::anyhow!("UTF-8转换失败: {}", e))
-}
\ No newline at end of file
+/// 准备文本节点的替换内容：命中译文时保留首尾的换行和缩进，只替换中间的文字
+fn prepare_text_replacement(text: &str, translation_map: &TranslationMap) -> Option<String> {
+    let translation = translation_map.get(text.trim())?;
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    Some(format!("{}{}{}", leading, translation, trailing))
+}
+
+/// 为收集到的文本节点准备替换内容，结果与输入一一对应
+///
+/// RcDom的节点不能跨线程，因此只把文本内容分块交给至多`workers`个线程查表、拼接，
+/// 写回仍由调用方串行完成。
+fn prepare_text_replacements(texts: &[String], translation_map: &TranslationMap, workers: usize) -> Vec<Option<String>> {
+    if workers <= 1 || texts.len() < PARALLEL_PREPARE_MIN_NODES {
+        return texts
+            .iter()
+            .map(|text| prepare_text_replacement(text, translation_map))
+            .collect();
+    }
+
+    let chunk_size = texts.len().div_ceil(workers);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = texts
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|text| prepare_text_replacement(text, translation_map))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("回写准备线程异常退出"))
+            .collect()
+    })
+}
+
+/// 遍历JSON中白名单字段（含其子结构）内的字符串
+fn visit_json_text_fields(
+    value: &mut serde_json::Value,
+    fields: &[String],
+    in_field: bool,
+    visit: &mut dyn FnMut(&mut String),
+) {
+    match value {
+        serde_json::Value::String(text) if in_field => visit(text),
+        serde_json::Value::Array(items) => {
+            for item in items {
+                visit_json_text_fields(item, fields, in_field, visit);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                let in_field = in_field || fields.iter().any(|field| field == key);
+                visit_json_text_fields(item, fields, in_field, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 提取JSON属性值中白名单字段的可翻译字符串，值不是合法JSON时返回空
+fn collect_json_attribute_texts(attr_value: &str, fields: &[String]) -> Vec<String> {
+    let mut value = match serde_json::from_str::<serde_json::Value>(attr_value) {
+        Ok(value) => value,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut texts = Vec::new();
+    visit_json_text_fields(&mut value, fields, false, &mut |text| {
+        let trimmed = text.trim();
+        if trimmed.len() > 1 && is_translatable_text(trimmed) {
+            texts.push(trimmed.to_string());
+        }
+    });
+    texts
+}
+
+/// 翻译JSON属性值中白名单字段的字符串并重新序列化
+///
+/// 值不是合法JSON或没有可替换的译文时返回`None`，调用方保持原值。
+fn translate_json_attribute(
+    attr_value: &str,
+    fields: &[String],
+    translation_map: &TranslationMap,
+) -> Option<String> {
+    let mut value = serde_json::from_str::<serde_json::Value>(attr_value).ok()?;
+
+    let mut changed = false;
+    visit_json_text_fields(&mut value, fields, false, &mut |text| {
+        if let Some(translation) = translation_map.get(text.trim()) {
+            *text = translation.to_string();
+            changed = true;
+        }
+    });
+
+    if changed {
+        serde_json::to_string(&value).ok()
+    } else {
+        None
+    }
+}
+
+/// 单元格内容是否为纯数字、金额、百分比或数字日期（如`1,234.50`、`-3%`、`$99`、`2024-01-01`）
+///
+/// 含字母的内容（如`Jan 5`、`12 items`）视为文字，仍然翻译。
+pub fn is_numeric_cell(text: &str) -> bool {
+    let text = text.trim();
+    text.chars().any(|c| c.is_ascii_digit())
+        && text.chars().all(|c| {
+            c.is_ascii_digit()
+                || c.is_whitespace()
+                || c.is_ascii_punctuation()
+                || matches!(c, '¥' | '€' | '£' | '₹' | '₩' | '￥' | '−' | '–' | '—' | '‰')
+        })
+}
+
+/// 子树中全部文本节点的内容
+fn collect_text_content(node: &Handle) -> String {
+    let mut text = String::new();
+    if let NodeData::Text { ref contents } = node.data {
+        text.push_str(&contents.borrow());
+    }
+    for child in node.children.borrow().iter() {
+        text.push_str(&collect_text_content(child));
+    }
+    text
+}
+
+/// `<time>`元素按`datetime`本地化后的显示文本
+///
+/// 未启用日期本地化、不是只含文本的`<time>`元素或日期无法解析时返回`None`。
+fn localized_time_text(node: &Handle, options: &HtmlProcessingOptions) -> Option<String> {
+    let lang = options.date_locale.as_deref().filter(|_| options.translate_text)?;
+    let (name, attrs) = match &node.data {
+        NodeData::Element { name, attrs, .. } => (name, attrs),
+        _ => return None,
+    };
+    if name.local.as_ref() != "time" {
+        return None;
+    }
+
+    let children = node.children.borrow();
+    let only_text = children
+        .iter()
+        .all(|child| matches!(child.data, NodeData::Text { .. }));
+    let has_text = children.iter().any(|child| match child.data {
+        NodeData::Text { ref contents } => !contents.borrow().trim().is_empty(),
+        _ => false,
+    });
+    if !only_text || !has_text {
+        return None;
+    }
+
+    let attrs = attrs.borrow();
+    let datetime = attrs.iter().find(|attr| attr.name.local.as_ref() == "datetime")?;
+    localize_datetime(&datetime.value, lang)
+}
+
+/// 重新编码非base64 SVG data URI时需要转义的字符
+const SVG_DATA_URI_ESCAPE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// 解码后的SVG data URI
+struct SvgDataUri {
+    /// 逗号之前的头部，如`data:image/svg+xml;base64`
+    header: String,
+    /// 是否为base64编码（否则为URL编码）
+    base64: bool,
+    /// 解码后的SVG源码
+    svg: String,
+}
+
+impl SvgDataUri {
+    /// 解析`data:image/svg+xml[;...][;base64],...`，其他类型返回`None`
+    fn parse(data_uri: &str) -> Option<Self> {
+        let data_uri = data_uri.trim();
+        let (header, payload) = data_uri.split_once(',')?;
+        let media_type = header.strip_prefix("data:")?.split(';').next()?;
+        if !media_type.eq_ignore_ascii_case("image/svg+xml") {
+            return None;
+        }
+
+        let base64 = header
+            .rsplit(';')
+            .next()
+            .is_some_and(|param| param.eq_ignore_ascii_case("base64"));
+        let svg = if base64 {
+            String::from_utf8(general_purpose::STANDARD.decode(payload.trim()).ok()?).ok()?
+        } else {
+            percent_decode_str(payload).decode_utf8().ok()?.into_owned()
+        };
+
+        Some(Self {
+            header: header.to_string(),
+            base64,
+            svg,
+        })
+    }
+
+    /// 按原编码方式重新生成data URI
+    fn encode(&self, svg: &str) -> String {
+        let payload = if self.base64 {
+            general_purpose::STANDARD.encode(svg)
+        } else {
+            utf8_percent_encode(svg, SVG_DATA_URI_ESCAPE_SET).to_string()
+        };
+        format!("{},{}", self.header, payload)
+    }
+}
+
+/// 匹配SVG中`<text>`/`<tspan>`的直接文本内容
+fn svg_text_regex() -> Regex {
+    Regex::new(r"(<(?:text|tspan)\b[^>]*>)([^<]+)").expect("SVG文本正则无效")
+}
+
+/// 提取SVG data URI中`<text>`/`<tspan>`的可翻译文本
+fn collect_svg_data_uri_texts(data_uri: &str) -> Vec<String> {
+    let svg = match SvgDataUri::parse(data_uri) {
+        Some(svg) => svg.svg,
+        None => return Vec::new(),
+    };
+
+    svg_text_regex()
+        .captures_iter(&svg)
+        .filter_map(|captures| {
+            let raw = captures.get(2)?.as_str();
+            let text = quick_xml::escape::unescape(raw).ok()?;
+            let trimmed = text.trim();
+            (trimmed.len() > 1 && is_translatable_text(trimmed)).then(|| trimmed.to_string())
+        })
+        .collect()
+}
+
+/// 翻译SVG data URI中的文本并按原编码方式重新编码
+///
+/// 不是SVG data URI或没有可替换的译文时返回`None`，调用方保持原值。
+fn translate_svg_data_uri(
+    data_uri: &str,
+    translation_map: &TranslationMap,
+) -> Option<String> {
+    let parsed = SvgDataUri::parse(data_uri)?;
+
+    let mut changed = false;
+    let translated = svg_text_regex().replace_all(&parsed.svg, |captures: &regex::Captures| {
+        let raw = &captures[2];
+        let translation = quick_xml::escape::unescape(raw)
+            .ok()
+            .and_then(|text| translation_map.get(text.trim()).map(|translation| translation.to_string()));
+        match translation {
+            Some(translation) => {
+                changed = true;
+                format!("{}{}", &captures[1], quick_xml::escape::escape(&translation))
+            }
+            None => captures[0].to_string(),
+        }
+    });
+
+    changed.then(|| parsed.encode(&translated))
+}
+
+/// 翻译结果允许的元素数量偏差比例
+const MAX_STRUCTURE_DEVIATION_RATIO: f64 = 0.05;
+
+/// 翻译结果HTML的校验报告
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HtmlValidationReport {
+    /// 翻译结果比原文多出的解析错误
+    pub new_parse_errors: Vec<String>,
+    /// 原文的元素数量
+    pub original_elements: usize,
+    /// 翻译结果的元素数量
+    pub translated_elements: usize,
+}
+
+impl HtmlValidationReport {
+    /// 元素数量偏差是否超出允许范围
+    pub fn has_structure_deviation(&self) -> bool {
+        let difference = self.original_elements.abs_diff(self.translated_elements) as f64;
+        difference > (self.original_elements as f64 * MAX_STRUCTURE_DEVIATION_RATIO).max(1.0)
+    }
+
+    /// 是否通过校验
+    pub fn is_valid(&self) -> bool {
+        self.new_parse_errors.is_empty() && !self.has_structure_deviation()
+    }
+
+    /// 问题描述列表
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues: Vec<String> = self
+            .new_parse_errors
+            .iter()
+            .map(|error| format!("解析错误: {}", error))
+            .collect();
+        if self.has_structure_deviation() {
+            issues.push(format!(
+                "结构偏差: 元素数量 {} -> {}",
+                self.original_elements, self.translated_elements
+            ));
+        }
+        issues
+    }
+}
+
+/// 校验翻译结果HTML
+///
+/// 用html5ever分别重新解析原文和翻译结果，报告翻译结果中新增的解析错误，
+/// 并比较两者的元素数量，用于发现DOM改写破坏HTML结构的问题。
+pub fn validate_translated_html(original_html: &str, translated_html: &str) -> HtmlValidationReport {
+    let original = parse_document(RcDom::default(), Default::default()).one(original_html);
+    let translated = parse_document(RcDom::default(), Default::default()).one(translated_html);
+
+    // 原文本身可能不规范，只报告翻译后新增的错误
+    let mut original_errors: Vec<String> = original
+        .errors
+        .borrow()
+        .iter()
+        .map(|error| error.to_string())
+        .collect();
+    let mut new_parse_errors = Vec::new();
+    for error in translated.errors.borrow().iter() {
+        match original_errors.iter().position(|e| e == error.as_ref()) {
+            Some(position) => {
+                original_errors.swap_remove(position);
+            }
+            None => new_parse_errors.push(error.to_string()),
+        }
+    }
+
+    HtmlValidationReport {
+        new_parse_errors,
+        original_elements: count_elements(&original.document),
+        translated_elements: count_elements(&translated.document),
+    }
+}
+
+/// 统计子树中的元素数量
+fn count_elements(node: &Handle) -> usize {
+    let own = usize::from(matches!(node.data, NodeData::Element { .. }));
+    own + node
+        .children
+        .borrow()
+        .iter()
+        .map(count_elements)
+        .sum::<usize>()
+}
+
+/// 将原始HTML文本（如`<noscript>`内容）按片段解析、翻译并重新序列化
+fn translate_raw_html_text(
+    raw_html: &str,
+    original_texts: &[String],
+    translations: &[String],
+    options: &HtmlProcessingOptions,
+) -> Result<String> {
+    let fragment = parse_html_fragment(raw_html);
+    let translated =
+        apply_translations_to_dom_with_options(fragment, original_texts, translations, options)?;
+    serialize_fragment_to_html(translated)
+}
+
+/// 内容按原始文本处理、不含标签的元素
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "xmp", "iframe", "noembed", "noframes"];
+
+/// 内容为可翻译文本、不含标签的元素
+const RCDATA_ELEMENTS: &[&str] = &["title", "textarea"];
+
+/// 在原始HTML源码上回写翻译，保留原文的换行、缩进、属性引号等格式
+///
+/// 与[`apply_translations_to_dom_with_options`]处理范围一致（文本节点、可翻译属性、
+/// JSON属性和SVG data URI），但不经过DOM序列化：只替换译文对应的字节区间，
+/// 其余内容逐字节保留，便于在版本控制中对比。
+pub fn apply_translations_to_source(
+    html: &str,
+    original_texts: &[String],
+    translations: &[String],
+    options: &HtmlProcessingOptions,
+) -> String {
+    let translation_map = build_translation_map(original_texts, translations);
+
+    // ASCII小写不改变字节偏移，用于大小写不敏感地查找结束标签
+    let lower = html.to_ascii_lowercase();
+    let tag_regex = Regex::new(r"<[^>]*>").expect("标签正则无效");
+    let mut output = String::with_capacity(html.len());
+    let mut pos = 0;
+    let mut noscript_depth = 0usize;
+    // 带lang属性的元素构成的语言区域：小写标签名、是否为跳过的语言、同名元素的嵌套深度
+    let mut lang_scopes: Vec<(String, bool, usize)> = Vec::new();
+
+    while pos < html.len() {
+        let in_skipped_noscript = noscript_depth > 0 && !options.translate_noscript;
+        let in_skipped_lang = lang_scopes.last().is_some_and(|(_, skipped, _)| *skipped);
+        let tag_start = find_source_tag_start(html, pos).unwrap_or(html.len());
+        if tag_start > pos {
+            let text = &html[pos..tag_start];
+            if options.translate_text && !in_skipped_noscript && !in_skipped_lang {
+                output.push_str(&translate_source_text(text, &translation_map));
+            } else {
+                output.push_str(text);
+            }
+            pos = tag_start;
+            continue;
+        }
+
+        let rest = &html[pos..];
+        if rest.starts_with("<!--") {
+            let end = html[pos + 4..].find("-->").map_or(html.len(), |i| pos + 4 + i + 3);
+            output.push_str(&html[pos..end]);
+            pos = end;
+        } else if rest.starts_with("</") || rest.starts_with("<!") || rest.starts_with("<?") {
+            let end = rest.find('>').map_or(html.len(), |i| pos + i + 1);
+            let closing = &lower[pos..end];
+            if closing.starts_with("</noscript") {
+                noscript_depth = noscript_depth.saturating_sub(1);
+            }
+            if let Some((scope_tag, _, depth)) = lang_scopes.last_mut() {
+                let closing_name = closing
+                    .trim_start_matches("</")
+                    .split(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+                    .next()
+                    .unwrap_or_default();
+                if closing_name == scope_tag.as_str() {
+                    *depth -= 1;
+                    if *depth == 0 {
+                        lang_scopes.pop();
+                    }
+                }
+            }
+            output.push_str(&html[pos..end]);
+            pos = end;
+        } else {
+            let tag = parse_source_start_tag(rest);
+            let tag_name = tag.name.to_ascii_lowercase();
+            let attribute_value = |attr_name: &str| {
+                tag.attributes.iter().find(|(name, _, _)| name == attr_name).map(|(_, range, quote)| {
+                    let raw = &rest[range.clone()];
+                    decode_html_entities(if quote.is_some() && raw.len() >= 2 { &raw[1..raw.len() - 1] } else { raw })
+                })
+            };
+
+            // 元素自身带lang时开启新的语言区域，与DOM中「最近的带lang祖先」规则一致
+            let mut in_skipped_lang = in_skipped_lang;
+            if let Some(skip_lang) = options.skip_lang.as_deref() {
+                let opens_element = !tag.self_closing && !VOID_TAGS.contains(&tag_name.as_str());
+                match attribute_value("lang") {
+                    Some(lang) => {
+                        in_skipped_lang = is_same_language(&lang, skip_lang);
+                        if opens_element {
+                            lang_scopes.push((tag_name.clone(), in_skipped_lang, 1));
+                        }
+                    }
+                    None => {
+                        if let Some((scope_tag, _, depth)) = lang_scopes.last_mut() {
+                            if opens_element && *scope_tag == tag_name {
+                                *depth += 1;
+                            }
+                        }
+                    }
+                }
+            }
+
+            let skip_attributes = in_skipped_noscript
+                || in_skipped_lang
+                || (tag_name == "noscript" && !options.translate_noscript);
+            output.push_str(&rewrite_source_start_tag(rest, &tag, &tag_name, skip_attributes, options, &translation_map));
+            let datetime = attribute_value("datetime");
+            pos += tag.end;
+
+            // 纯数字单元格原样保留，与DOM回写一致
+            if tag_name == "td" && options.skip_numeric_cells {
+                let close = find_source_closing_tag(&lower, pos, "td");
+                let content = &html[pos..close];
+                let text = tag_regex.replace_all(content, "");
+                if !content.contains("<table") && is_numeric_cell(&decode_html_entities(&text)) {
+                    output.push_str(content);
+                    pos = close;
+                    continue;
+                }
+            }
+
+            // <time>的显示文本按datetime本地化，与DOM回写一致
+            if tag_name == "time" && options.translate_text && !in_skipped_noscript {
+                let localized = options
+                    .date_locale
+                    .as_deref()
+                    .zip(datetime)
+                    .and_then(|(lang, datetime)| localize_datetime(&datetime, lang));
+                let close = find_source_closing_tag(&lower, pos, "time");
+                let content = &html[pos..close];
+                if let Some(localized) = localized.filter(|_| !content.contains('<') && !content.trim().is_empty()) {
+                    let leading = &content[..content.len() - content.trim_start().len()];
+                    let trailing = &content[content.trim_end().len()..];
+                    output.push_str(leading);
+                    output.push_str(&escape_text_content(&localized));
+                    output.push_str(trailing);
+                    pos = close;
+                }
+            }
+
+            if RAW_TEXT_ELEMENTS.contains(&tag_name.as_str()) || RCDATA_ELEMENTS.contains(&tag_name.as_str()) {
+                let close = find_source_closing_tag(&lower, pos, &tag_name);
+                let content = &html[pos..close];
+                if RCDATA_ELEMENTS.contains(&tag_name.as_str())
+                    && options.translate_text
+                    && !in_skipped_noscript
+                    && !in_skipped_lang
+                {
+                    output.push_str(&translate_source_text(content, &translation_map));
+                } else {
+                    output.push_str(content);
+                }
+                pos = close;
+            } else if tag_name == "noscript" && !tag.self_closing {
+                noscript_depth += 1;
+            }
+        }
+    }
+
+    output
+}
+
+/// 源码中的开始标签
+struct SourceStartTag<'a> {
+    /// 标签名（保持原始大小写）
+    name: &'a str,
+    /// 属性：小写属性名、值在标签内的字节区间（含引号）及引号字符
+    attributes: Vec<(String, std::ops::Range<usize>, Option<char>)>,
+    /// 标签结束位置（`>`之后）相对标签开头的偏移
+    end: usize,
+    /// 是否为`<tag/>`形式
+    self_closing: bool,
+}
+
+/// 查找下一个标签的起始位置，未跟随标签名等字符的`<`视为普通文本
+fn find_source_tag_start(html: &str, from: usize) -> Option<usize> {
+    let bytes = html.as_bytes();
+    let mut pos = from;
+    while let Some(offset) = html[pos..].find('<') {
+        let start = pos + offset;
+        match bytes.get(start + 1) {
+            Some(next) if next.is_ascii_alphabetic() || matches!(next, b'/' | b'!' | b'?') => {
+                return Some(start)
+            }
+            _ => pos = start + 1,
+        }
+    }
+    None
+}
+
+/// 解析以`<`开头的开始标签
+fn parse_source_start_tag(tag: &str) -> SourceStartTag<'_> {
+    let bytes = tag.as_bytes();
+    let is_name_end = |b: u8| b.is_ascii_whitespace() || matches!(b, b'/' | b'>');
+
+    let mut pos = 1;
+    while pos < bytes.len() && !is_name_end(bytes[pos]) {
+        pos += 1;
+    }
+    let name = &tag[1..pos];
+
+    let mut attributes = Vec::new();
+    let mut self_closing = false;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'>' => {
+                return SourceStartTag { name, attributes, end: pos + 1, self_closing };
+            }
+            b'/' => {
+                self_closing = bytes.get(pos + 1) == Some(&b'>');
+                pos += 1;
+                continue;
+            }
+            b if b.is_ascii_whitespace() => {
+                pos += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        let name_start = pos;
+        while pos < bytes.len() && !is_name_end(bytes[pos]) && bytes[pos] != b'=' {
+            pos += 1;
+        }
+        let attr_name = tag[name_start..pos].to_ascii_lowercase();
+
+        let mut lookahead = pos;
+        while lookahead < bytes.len() && bytes[lookahead].is_ascii_whitespace() {
+            lookahead += 1;
+        }
+        if bytes.get(lookahead) != Some(&b'=') {
+            continue;
+        }
+        pos = lookahead + 1;
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+
+        let value_start = pos;
+        let quote = match bytes.get(pos) {
+            Some(&q @ (b'"' | b'\'')) => Some(q as char),
+            _ => None,
+        };
+        match quote {
+            Some(q) => {
+                pos = tag[pos + 1..].find(q).map_or(bytes.len(), |i| pos + 1 + i + 1);
+            }
+            None => {
+                while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() && bytes[pos] != b'>' {
+                    pos += 1;
+                }
+            }
+        }
+        attributes.push((attr_name, value_start..pos, quote));
+    }
+
+    SourceStartTag { name, attributes, end: bytes.len(), self_closing }
+}
+
+/// 翻译开始标签中的属性值，未翻译的部分保持原样
+fn rewrite_source_start_tag(
+    tag_source: &str,
+    tag: &SourceStartTag<'_>,
+    tag_name: &str,
+    skip_attributes: bool,
+    options: &HtmlProcessingOptions,
+    translation_map: &TranslationMap,
+) -> String {
+    let tag_source = &tag_source[..tag.end];
+    if skip_attributes {
+        return tag_source.to_string();
+    }
+
+    let itemprop = tag
+        .attributes
+        .iter()
+        .find(|(attr_name, _, _)| attr_name == "itemprop")
+        .map(|(_, range, _)| decode_html_entities(tag_source[range.clone()].trim_matches(|c| c == '"' || c == '\'')));
+    let microdata_meta = is_microdata_text_meta(tag_name, itemprop.as_deref());
+
+    let mut output = String::with_capacity(tag_source.len());
+    let mut last = 0;
+    for (attr_name, range, quote) in &tag.attributes {
+        let raw_value = &tag_source[range.clone()];
+        let raw_value = match quote {
+            Some(_) if raw_value.len() >= 2 => &raw_value[1..raw_value.len() - 1],
+            _ => raw_value,
+        };
+        let value = decode_html_entities(raw_value);
+
+        let mut translated = None;
+        if options.translate_attributes && !matches!(tag_name, "script" | "style") {
+            if options.is_json_attribute(attr_name) {
+                translated = translate_json_attribute(&value, &options.json_fields, translation_map);
+            } else if options.is_text_attribute(tag_name, attr_name)
+                || (microdata_meta && attr_name == "content")
+            {
+                translated = translation_map.get(value.trim()).map(|translation| translation.to_string());
+            }
+        }
+        if options.translate_text && tag_name == "img" && attr_name == "src" {
+            translated = translate_svg_data_uri(&value, translation_map);
+        }
+
+        if let Some(translated) = translated {
+            let quote = quote.unwrap_or('"');
+            output.push_str(&tag_source[last..range.start]);
+            output.push(quote);
+            output.push_str(&escape_attribute_value(&translated, quote));
+            output.push(quote);
+            last = range.end;
+        }
+    }
+    output.push_str(&tag_source[last..]);
+    output
+}
+
+/// 查找原始文本元素的结束标签位置，找不到时返回文档末尾
+fn find_source_closing_tag(lower: &str, from: usize, tag_name: &str) -> usize {
+    let needle = format!("</{}", tag_name);
+    let mut pos = from;
+    while let Some(offset) = lower[pos..].find(&needle) {
+        let start = pos + offset;
+        match lower.as_bytes().get(start + needle.len()) {
+            Some(b) if !(b.is_ascii_whitespace() || matches!(b, b'/' | b'>')) => pos = start + 1,
+            _ => return start,
+        }
+    }
+    lower.len()
+}
+
+/// 翻译源码中的一段文本，保留首尾空白
+fn translate_source_text(raw: &str, translation_map: &TranslationMap) -> String {
+    let core = raw.trim();
+    if core.is_empty() {
+        return raw.to_string();
+    }
+
+    match translation_map.get(decode_html_entities(core).trim()) {
+        Some(translation) => {
+            let leading = &raw[..raw.len() - raw.trim_start().len()];
+            let trailing = &raw[raw.trim_end().len()..];
+            format!("{}{}{}", leading, escape_text_content(translation), trailing)
+        }
+        None => raw.to_string(),
+    }
+}
+
+/// 解码HTML字符引用，与解析器对文本节点的处理一致
+fn decode_html_entities(raw: &str) -> String {
+    if !raw.contains('&') {
+        return raw.to_string();
+    }
+
+    fn collect_text(node: &Handle, text: &mut String) {
+        if let NodeData::Text { ref contents } = node.data {
+            text.push_str(&contents.borrow());
+        }
+        for child in node.children.borrow().iter() {
+            collect_text(child, text);
+        }
+    }
+
+    // 先转义`<`，保证整段按文本解析
+    let fragment = parse_html_fragment(&raw.replace('<', "&lt;"));
+    let mut text = String::new();
+    collect_text(&fragment.document, &mut text);
+    text
+}
+
+/// 转义文本内容中的特殊字符
+fn escape_text_content(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// 转义属性值中的特殊字符
+fn escape_attribute_value(value: &str, quote: char) -> String {
+    let escaped = value.replace('&', "&amp;");
+    match quote {
+        '\'' => escaped.replace('\'', "&#39;"),
+        _ => escaped.replace('"', "&quot;"),
+    }
+}
+
+/// 匹配字符集声明（`<meta charset>`属性或`content`中的`charset=`）中的字符集名
+const CHARSET_DECLARATION_PATTERN: &str = r#"(?i)(\bcharset\s*=\s*["']?)([^"'\s/>;]+)"#;
+
+/// 将文档的字符集声明修正为UTF-8，缺失时在`<head>`开头注入`<meta charset="utf-8">`
+///
+/// 输出总是以UTF-8写出，原文声明的其他字符集（如`iso-8859-1`）会让浏览器把译文显示为乱码。
+/// 返回是否修改了文档。
+pub fn ensure_utf8_charset(dom: &RcDom) -> bool {
+    use markup5ever_rcdom::Node;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn visit(node: &Handle, head: &mut Option<Handle>, declared: &mut bool, changed: &mut bool, pattern: &Regex) {
+        if let NodeData::Element { ref name, ref attrs, .. } = node.data {
+            match name.local.as_ref() {
+                "head" if head.is_none() => *head = Some(node.clone()),
+                "meta" => {
+                    for attr in attrs.borrow_mut().iter_mut() {
+                        let attr_name = attr.name.local.as_ref();
+                        let value = attr.value.to_string();
+                        let fixed = if attr_name == "charset" {
+                            Some("utf-8".to_string())
+                        } else if attr_name == "content" && pattern.is_match(&value) {
+                            Some(pattern.replace_all(&value, "${1}utf-8").into_owned())
+                        } else {
+                            None
+                        };
+                        if let Some(fixed) = fixed {
+                            *declared = true;
+                            if !fixed.eq_ignore_ascii_case(&value) {
+                                attr.value = fixed.into();
+                                *changed = true;
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for child in node.children.borrow().iter() {
+            visit(child, head, declared, changed, pattern);
+        }
+    }
+
+    let pattern = Regex::new(CHARSET_DECLARATION_PATTERN).expect("字符集正则无效");
+    let (mut head, mut declared, mut changed) = (None, false, false);
+    visit(&dom.document, &mut head, &mut declared, &mut changed, &pattern);
+
+    if declared {
+        return changed;
+    }
+    let head = match head {
+        Some(head) => head,
+        None => return false,
+    };
+    let meta = Node::new(NodeData::Element {
+        name: QualName::new(None, ns!(html), local_name!("meta")),
+        attrs: RefCell::new(vec![html5ever::Attribute {
+            name: QualName::new(None, ns!(), local_name!("charset")),
+            value: "utf-8".into(),
+        }]),
+        template_contents: RefCell::new(None),
+        mathml_annotation_xml_integration_point: false,
+    });
+    meta.parent.set(Some(Rc::downgrade(&head)));
+    head.children.borrow_mut().insert(0, meta);
+    true
+}
+
+/// 在原始源码上修正字符集声明为UTF-8，与[`ensure_utf8_charset`]对应，用于保留格式的输出
+///
+/// 缺少声明时依次尝试插入到`<head>`、`<html>`、`<!DOCTYPE>`之后，都没有时插入到开头。
+pub fn ensure_utf8_charset_in_source(html: &str) -> String {
+    set_charset_in_source(html, "utf-8")
+}
+
+/// 在源码上将字符集声明改为`charset`，缺失时按[`ensure_utf8_charset_in_source`]的规则插入
+pub fn set_charset_in_source(html: &str, charset: &str) -> String {
+    let pattern = Regex::new(CHARSET_DECLARATION_PATTERN).expect("字符集正则无效");
+    let meta_regex = Regex::new(r"(?is)<meta\b[^>]*>").expect("meta正则无效");
+
+    let mut declared = false;
+    let fixed = meta_regex.replace_all(html, |captures: &regex::Captures| {
+        let tag = &captures[0];
+        if pattern.is_match(tag) {
+            declared = true;
+            pattern
+                .replace_all(tag, |caps: &regex::Captures| format!("{}{}", &caps[1], charset))
+                .into_owned()
+        } else {
+            tag.to_string()
+        }
+    });
+    if declared {
+        return fixed.into_owned();
+    }
+
+    let meta = format!("<meta charset=\"{}\">", charset);
+    let insert_at = [r"(?i)<head\b[^>]*>", r"(?i)<html\b[^>]*>", r"(?i)<!doctype\b[^>]*>"]
+        .iter()
+        .find_map(|anchor| Regex::new(anchor).ok()?.find(html).map(|m| m.end()))
+        .unwrap_or(0);
+    format!("{}{}{}", &html[..insert_at], meta, &html[insert_at..])
+}
+
+/// 判断字符是否不应出现在HTML输出中
+///
+/// 包括除`\t`、`\n`、`\x0C`、`\r`外的C0控制字符、DEL、C1控制字符和Unicode非字符，
+/// 它们在HTML中属于解析错误，部分浏览器和XML解析器会因此拒绝打开文件。
+pub fn is_invalid_html_char(c: char) -> bool {
+    match c {
+        '\t' | '\n' | '\u{0C}' | '\r' => false,
+        '\u{00}'..='\u{1F}' | '\u{7F}'..='\u{9F}' | '\u{FDD0}'..='\u{FDEF}' => true,
+        _ => (c as u32) & 0xFFFE == 0xFFFE,
+    }
+}
+
+/// 移除输出中的非法控制字符和非字符，返回清理后的文本和移除的字符数
+pub fn strip_invalid_html_chars(text: &str) -> (String, usize) {
+    let removed = text.chars().filter(|&c| is_invalid_html_char(c)).count();
+    if removed == 0 {
+        return (text.to_string(), 0);
+    }
+    (text.chars().filter(|&c| !is_invalid_html_char(c)).collect(), removed)
+}
+
+/// 分块翻译时骨架中代替容器内容的占位注释
+pub const CHUNK_PLACEHOLDER: &str = "<!--translation-cli-chunks-->";
+
+/// 可继续向下拆分的单一包裹元素（其内容按`<body>`上下文解析不会改变结构）
+const CHUNK_WRAPPER_TAGS: &[&str] = &["div", "section", "main", "article"];
+
+/// 超大HTML文档的分块结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlChunks {
+    /// 文档骨架：容器内容被替换为[`CHUNK_PLACEHOLDER`]
+    pub skeleton: String,
+    /// 容器内容按顶层节点边界切分的片段，依次拼接即为原容器内容
+    pub chunks: Vec<String>,
+}
+
+/// 顶层节点：(起始偏移, 元素内容起始, 元素内容结束, 结束偏移, 标签名)，文本等非元素节点标签名为空
+type TopLevelNode = (usize, usize, usize, usize, String);
+
+/// 将超大文档的`<body>`内容按顶层节点边界切成不超过约`max_chunk_bytes`的片段
+///
+/// 只在嵌套深度为0的节点之间切分，保证每个片段的标签完整闭合；`<body>`只有单个
+/// `<div>`/`<section>`等包裹元素时进入其内部切分。省略结束标签（如`<p>`、`<li>`）
+/// 会使深度无法回到0，此时不切分。无法切成两块以上时返回`None`。
+pub fn split_html_into_chunks(html: &str, max_chunk_bytes: usize) -> Option<HtmlChunks> {
+    let body_start = Regex::new(r"(?is)<body\b[^>]*>").ok()?.find(html)?.end();
+    let body_end = html.to_ascii_lowercase().rfind("</body")?;
+    if body_end < body_start {
+        return None;
+    }
+
+    let (mut start, mut end) = (body_start, body_end);
+    let nodes = loop {
+        let nodes = scan_top_level_nodes(&html[start..end])?;
+        let mut elements = nodes
+            .iter()
+            .filter(|node| !node.4.is_empty() || !html[start + node.0..start + node.3].trim().is_empty());
+        match (elements.next(), elements.next()) {
+            (Some(only), None) if CHUNK_WRAPPER_TAGS.contains(&only.4.as_str()) => {
+                end = start + only.2;
+                start += only.1;
+            }
+            _ => break nodes,
+        }
+    };
+
+    let mut chunks = Vec::new();
+    let mut chunk_start = 0;
+    for node in &nodes {
+        if node.3 - chunk_start >= max_chunk_bytes.max(1) {
+            chunks.push(html[start + chunk_start..start + node.3].to_string());
+            chunk_start = node.3;
+        }
+    }
+    if chunk_start < end - start {
+        chunks.push(html[start + chunk_start..end].to_string());
+    }
+    if chunks.len() < 2 {
+        return None;
+    }
+
+    Some(HtmlChunks {
+        skeleton: format!("{}{}{}", &html[..start], CHUNK_PLACEHOLDER, &html[end..]),
+        chunks,
+    })
+}
+
+/// 没有结束标签的void元素
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+/// 内容按原始文本处理的元素，其中的`<`不是标签
+const RAW_TEXT_TAGS: &[&str] = &[
+    "script", "style", "textarea", "title", "xmp", "iframe", "noembed", "noframes",
+];
+
+/// 扫描HTML片段的顶层节点，标签未能正确闭合时返回`None`
+fn scan_top_level_nodes(content: &str) -> Option<Vec<TopLevelNode>> {
+    let lowered = content.to_ascii_lowercase();
+    let bytes = content.as_bytes();
+    let mut nodes = Vec::new();
+    let mut depth = 0usize;
+    // 当前顶层节点：(起始偏移, 元素内容起始, 标签名)
+    let mut current: Option<(usize, usize, String)> = None;
+    let mut position = 0;
+
+    while position < content.len() {
+        let next_tag = match lowered[position..].find('<') {
+            Some(offset) => position + offset,
+            None => content.len(),
+        };
+        // 顶层文本作为单独节点
+        if next_tag > position && depth == 0 {
+            nodes.push((position, position, next_tag, next_tag, String::new()));
+        }
+        if next_tag >= content.len() {
+            break;
+        }
+        position = next_tag;
+
+        let rest = &lowered[position..];
+        let (token_end, opened, closed) = if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(content.len(), |offset| position + offset + 3);
+            (end, None, false)
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            let end = rest.find('>').map_or(content.len(), |offset| position + offset + 1);
+            (end, None, false)
+        } else if rest.starts_with("</") {
+            let end = rest.find('>').map_or(content.len(), |offset| position + offset + 1);
+            (end, None, true)
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let name_len = rest[1..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+                .unwrap_or(rest.len() - 1);
+            let name = rest[1..1 + name_len].to_string();
+            let end = find_tag_end(bytes, position)?;
+            if VOID_TAGS.contains(&name.as_str()) || bytes[end - 2] == b'/' {
+                (end, None, false)
+            } else if RAW_TEXT_TAGS.contains(&name.as_str()) {
+                let close = lowered[end..].find(&format!("</{}", name))? + end;
+                let close_end = lowered[close..].find('>')? + close + 1;
+                (close_end, None, false)
+            } else {
+                (end, Some(name), false)
+            }
+        } else {
+            // 孤立的`<`按文本处理
+            (position + 1, None, false)
+        };
+
+        if let Some(name) = opened {
+            if depth == 0 {
+                current = Some((position, token_end, name));
+            }
+            depth += 1;
+        } else if closed {
+            depth = depth.checked_sub(1)?;
+            if depth == 0 {
+                let (node_start, inner_start, name) = current.take()?;
+                nodes.push((node_start, inner_start, position, token_end, name));
+            }
+        } else if depth == 0 {
+            nodes.push((position, position, token_end, token_end, String::new()));
+        }
+        position = token_end;
+    }
+
+    (depth == 0).then_some(nodes)
+}
+
+/// 查找从`start`开始的开始标签的结束位置（`>`之后），跳过引号内的`>`
+fn find_tag_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut quote = None;
+    for (offset, &byte) in bytes[start..].iter().enumerate() {
+        match (quote, byte) {
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (Some(q), _) if byte == q => quote = None,
+            (None, b'>') => return Some(start + offset + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 判断文档是否声明为XHTML（开头的DOCTYPE为XHTML或带`<?xml`声明）
+pub fn is_xhtml_document(html: &str) -> bool {
+    let head = strip_utf8_bom(html).trim_start();
+    if head.starts_with("<?xml") {
+        return true;
+    }
+    let head = &head[..head.len().min(1024)];
+    let lowered = head.to_ascii_lowercase();
+    lowered
+        .find("<!doctype")
+        .and_then(|start| lowered[start..].find('>').map(|end| &lowered[start..start + end]))
+        .is_some_and(|doctype| doctype.contains("xhtml"))
+}
+
+/// 将序列化结果中的void元素改写为XHTML自闭合形式（`<br>` → `<br/>`）
+///
+/// 跳过注释、声明和原始文本元素（script、style等）的内容，属性值中的`>`不影响标签识别。
+pub fn to_xhtml_void_elements(html: &str) -> String {
+    let lowered = html.to_ascii_lowercase();
+    let bytes = html.as_bytes();
+    let mut output = String::with_capacity(html.len() + html.len() / 64);
+    let mut last = 0;
+    let mut position = 0;
+
+    while let Some(offset) = lowered[position..].find('<') {
+        position += offset;
+        let rest = &lowered[position..];
+        if rest.starts_with("<!--") {
+            position = rest.find("-->").map_or(html.len(), |end| position + end + 3);
+            continue;
+        }
+        if !rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            position += 1;
+            continue;
+        }
+
+        let name_len = rest[1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+            .unwrap_or(rest.len() - 1);
+        let name = &rest[1..1 + name_len];
+        let Some(end) = find_tag_end(bytes, position) else {
+            break;
+        };
+        if VOID_TAGS.contains(&name) && bytes[end - 2] != b'/' {
+            output.push_str(&html[last..end - 1]);
+            output.push_str("/>");
+            last = end;
+        }
+        position = end;
+        if RAW_TEXT_TAGS.contains(&name) {
+            position = lowered[position..]
+                .find(&format!("</{}", name))
+                .map_or(html.len(), |close| position + close);
+        }
+    }
+    output.push_str(&html[last..]);
+    output
+}
+
+/// 序列化DOM为HTML字符串
+pub fn serialize_dom_to_html(dom: RcDom) -> Result<String> {
+    serialize_dom_snapshot(&dom)
+}
+
+/// 在不转移所有权的情况下序列化DOM，用于输出中间结果（如优先级预览）
+pub fn serialize_dom_snapshot(dom: &RcDom) -> Result<String> {
+    use html5ever::serialize::{serialize, SerializeOpts};
+    use markup5ever_rcdom::SerializableHandle;
+    use std::io::Cursor;
+
+    let mut buffer = Vec::new();
+    let cursor = Cursor::new(&mut buffer);
+
+    serialize(
+        cursor,
+        &SerializableHandle::from(dom.document.clone()),
+        SerializeOpts::default(),
+    )
+    .map_err(|e| anyhow::anyhow!("HTML序列化失败: {:?}", e))?;
+
+    String::from_utf8(buffer).map_err(|e| anyhow::anyhow!("UTF-8转换失败: {}", e))
+}
+
+/// 解析HTML片段（非完整文档）为DOM
+///
+/// 以`<body>`为上下文元素解析，片段节点挂在文档根`<html>`元素下。
+pub fn parse_html_fragment(html: &str) -> RcDom {
+    parse_fragment(
+        RcDom::default(),
+        Default::default(),
+        QualName::new(None, ns!(html), local_name!("body")),
+        vec![],
+        false,
+    )
+    .one(html)
+}
+
+/// 序列化由`parse_html_fragment`得到的DOM，只输出片段本身
+pub fn serialize_fragment_to_html(dom: RcDom) -> Result<String> {
+    serialize_fragment_snapshot(&dom)
+}
+
+/// 在不转移所有权的情况下序列化片段DOM，与[`serialize_dom_snapshot`]对应
+pub fn serialize_fragment_snapshot(dom: &RcDom) -> Result<String> {
+    use html5ever::serialize::{serialize, SerializeOpts};
+    use markup5ever_rcdom::SerializableHandle;
+    use std::io::Cursor;
+
+    let root = match dom.document.children.borrow().first() {
+        Some(root) => root.clone(),
+        None => return Ok(String::new()),
+    };
+
+    let mut buffer = Vec::new();
+    let cursor = Cursor::new(&mut buffer);
+
+    serialize(cursor, &SerializableHandle::from(root), SerializeOpts::default())
+        .map_err(|e| anyhow::anyhow!("HTML片段序列化失败: {:?}", e))?;
+
+    String::from_utf8(buffer).map_err(|e| anyhow::anyhow!("UTF-8转换失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use html5ever::parse_document;
+
+    fn parse(html: &str) -> RcDom {
+        parse_document(RcDom::default(), Default::default()).one(html)
+    }
+
+    #[test]
+    fn test_extract_prioritized_texts() {
+        let dom = parse(
+            "<html><head><title>Page Title</title></head><body>\
+             <div><p>Body text later</p></div>\
+             <nav><a href=\"/\">Home link</a></nav>\
+             <h1>Main Heading</h1>\
+             <p>Second paragraph</p>\
+             <footer>Footer text</footer></body></html>",
+        );
+
+        let prioritized = extract_prioritized_texts(&dom, &HtmlProcessingOptions::default());
+        let high: Vec<&str> = prioritized
+            .iter()
+            .filter(|t| t.priority == TextPriority::High)
+            .map(|t| t.text.as_str())
+            .collect();
+
+        assert_eq!(prioritized.len(), 6);
+        assert!(high.contains(&"Page Title"));
+        assert!(high.contains(&"Home link"));
+        assert!(high.contains(&"Main Heading"));
+        // 文档中的第一个<p>
+        assert!(high.contains(&"Body text later"));
+        assert!(!high.contains(&"Second paragraph"));
+        assert!(!high.contains(&"Footer text"));
+
+        // 高优先级文本排在前面
+        let first_normal = prioritized
+            .iter()
+            .position(|t| t.priority == TextPriority::Normal)
+            .unwrap();
+        assert!(prioritized[first_normal..]
+            .iter()
+            .all(|t| t.priority == TextPriority::Normal));
+    }
+
+    const NOSCRIPT_SAMPLE: &str = "<html><body><p>Visible text</p>\
+        <noscript><p>Please enable JavaScript</p></noscript></body></html>";
+
+    #[test]
+    fn test_noscript_extraction_and_apply_are_symmetric() {
+        let options = HtmlProcessingOptions::default();
+        let dom = parse(NOSCRIPT_SAMPLE);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert!(texts.contains(&"Please enable JavaScript".to_string()));
+        assert!(!texts.iter().any(|t| t.contains('<')));
+
+        let translations: Vec<String> = texts.iter().map(|t| format!("译:{}", t)).collect();
+        let translated = apply_translations_to_dom_with_options(dom, &texts, &translations, &options)
+            .unwrap();
+        let html = serialize_dom_to_html(translated).unwrap();
+        assert!(html.contains("<noscript><p>译:Please enable JavaScript</p></noscript>"));
+        assert!(html.contains("<p>译:Visible text</p>"));
+    }
+
+    #[test]
+    fn test_skip_noscript_is_symmetric() {
+        let options = HtmlProcessingOptions {
+            translate_noscript: false,
+            ..Default::default()
+        };
+        let dom = parse(NOSCRIPT_SAMPLE);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert_eq!(texts, vec!["Visible text".to_string()]);
+
+        // 即使映射中包含noscript文本，回写时也不应修改
+        let originals = vec!["Visible text".to_string(), "Please enable JavaScript".to_string()];
+        let translations = vec!["可见文本".to_string(), "请启用JavaScript".to_string()];
+        let translated =
+            apply_translations_to_dom_with_options(dom, &originals, &translations, &options).unwrap();
+        let html = serialize_dom_to_html(translated).unwrap();
+        assert!(html.contains("<p>可见文本</p>"));
+        assert!(html.contains("Please enable JavaScript"));
+    }
+
+    const SCOPE_SAMPLE: &str = "<html><body><p title=\"Tooltip text\">Body text</p>\
+        <img alt=\"Image caption\"></body></html>";
+
+    #[test]
+    fn test_text_only_skips_attributes() {
+        let options = HtmlProcessingOptions {
+            translate_attributes: false,
+            ..Default::default()
+        };
+        let dom = parse(SCOPE_SAMPLE);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert_eq!(texts, vec!["Body text".to_string()]);
+
+        let originals = vec!["Body text".to_string(), "Tooltip text".to_string()];
+        let translations = vec!["正文".to_string(), "提示".to_string()];
+        let translated =
+            apply_translations_to_dom_with_options(dom, &originals, &translations, &options).unwrap();
+        let html = serialize_dom_to_html(translated).unwrap();
+        assert!(html.contains(">正文</p>"));
+        assert!(html.contains("title=\"Tooltip text\""));
+    }
+
+    #[test]
+    fn test_attributes_only_skips_text() {
+        let options = HtmlProcessingOptions {
+            translate_text: false,
+            ..Default::default()
+        };
+        let dom = parse(SCOPE_SAMPLE);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert!(texts.contains(&"Tooltip text".to_string()));
+        assert!(texts.contains(&"Image caption".to_string()));
+        assert!(!texts.contains(&"Body text".to_string()));
+
+        let originals = vec!["Body text".to_string(), "Tooltip text".to_string()];
+        let translations = vec!["正文".to_string(), "提示".to_string()];
+        let translated =
+            apply_translations_to_dom_with_options(dom, &originals, &translations, &options).unwrap();
+        let html = serialize_dom_to_html(translated).unwrap();
+        assert!(html.contains(">Body text</p>"));
+        assert!(html.contains("title=\"提示\""));
+    }
+
+    #[test]
+    fn test_validate_translated_html() {
+        let original = "<html><body><p title=\"Tip\">Hello</p><div>World</div></body></html>";
+
+        let report = validate_translated_html(original, "<html><body><p title=\"提示\">你好</p><div>世界</div></body></html>");
+        assert!(report.is_valid(), "{:?}", report.issues());
+
+        // 译文中混入未转义的标签会改变结构
+        let report = validate_translated_html(original, "<html><body><p>你好</p></div><div>世界</body></html>");
+        assert!(!report.new_parse_errors.is_empty());
+        assert!(!report.is_valid());
+
+        let report = validate_translated_html(original, "<html><body>你好 世界</body></html>");
+        assert!(report.has_structure_deviation());
+    }
+
+    #[test]
+    fn test_json_attribute_translation() {
+        let options = HtmlProcessingOptions {
+            json_attributes: vec!["data-props".to_string(), "x-data".to_string()],
+            ..Default::default()
+        };
+        let html = "<html><body>\
+            <div data-props='{\"title\":\"Welcome\",\"id\":\"main-panel\",\"items\":[{\"label\":\"Next page\"}]}'></div>\
+            <div x-data=\"{ msg: 'Not JSON' }\"></div></body></html>";
+        let dom = parse(html);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert!(texts.contains(&"Welcome".to_string()));
+        assert!(texts.contains(&"Next page".to_string()));
+        assert!(!texts.contains(&"main-panel".to_string()));
+        assert!(!texts.iter().any(|t| t.contains("Not JSON")));
+
+        let originals = vec!["Welcome".to_string(), "Next page".to_string()];
+        let translations = vec!["欢迎".to_string(), "下一页".to_string()];
+        let translated =
+            apply_translations_to_dom_with_options(dom, &originals, &translations, &options).unwrap();
+        let output = serialize_dom_to_html(translated).unwrap();
+        assert!(output.contains(
+            "data-props=\"{&quot;title&quot;:&quot;欢迎&quot;,&quot;id&quot;:&quot;main-panel&quot;,&quot;items&quot;:[{&quot;label&quot;:&quot;下一页&quot;}]}\""
+        ));
+        // 非法JSON原样保留
+        assert!(output.contains("x-data=\"{ msg: 'Not JSON' }\""));
+    }
+
+    #[test]
+    fn test_svg_data_uri_translation() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"><text x="4" y="12">Sign up</text><text><tspan>Tom &amp; Jerry</tspan></text></svg>"#;
+        let base64_uri = format!("data:image/svg+xml;base64,{}", general_purpose::STANDARD.encode(svg));
+        let encoded_uri = format!("data:image/svg+xml,{}", utf8_percent_encode(svg, SVG_DATA_URI_ESCAPE_SET));
+        let html = format!(
+            "<html><body><img src=\"{}\"><img src=\"{}\"><img src=\"data:image/png;base64,AAAA\"></body></html>",
+            base64_uri,
+            encoded_uri.replace('&', "&amp;")
+        );
+        let options = HtmlProcessingOptions::default();
+        let dom = parse(&html);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert!(texts.contains(&"Sign up".to_string()));
+        assert!(texts.contains(&"Tom & Jerry".to_string()));
+
+        let originals = vec!["Sign up".to_string(), "Tom & Jerry".to_string()];
+        let translations = vec!["注册".to_string(), "汤姆<和>杰瑞".to_string()];
+        let translated =
+            apply_translations_to_dom_with_options(dom, &originals, &translations, &options).unwrap();
+        let output = serialize_dom_to_html(translated).unwrap();
+
+        let src_regex = Regex::new(r#"src="([^"]+)""#).unwrap();
+        let sources: Vec<String> = src_regex
+            .captures_iter(&output)
+            .map(|captures| captures[1].replace("&amp;", "&"))
+            .collect();
+        assert_eq!(sources.len(), 3);
+        assert!(sources[0].starts_with("data:image/svg+xml;base64,"));
+        assert!(sources[1].starts_with("data:image/svg+xml,"));
+        for source in &sources[..2] {
+            let decoded = SvgDataUri::parse(source).unwrap().svg;
+            assert!(decoded.contains(">注册</text>"));
+            assert!(decoded.contains("<tspan>汤姆&lt;和&gt;杰瑞</tspan>"));
+        }
+        assert_eq!(sources[2], "data:image/png;base64,AAAA");
+    }
+
+    #[test]
+    fn test_apply_translations_to_source_preserves_formatting() {
+        let html = r#"<!doctype html>
+<html lang='en'>
+  <head>
+    <title>Home Page</title>
+    <script>var label = "Hello world";</script>
+  </head>
+  <body>
+    <!-- Hello world -->
+    <div   class="intro"   >
+      <p>
+        Hello world
+      </p>
+      <img src=logo.png alt=Logo>
+      <p>Tom &amp; Jerry</p>
+      <br/>
+    </div>
+  </body>
+</html>
+"#;
+        let originals: Vec<String> = ["Home Page", "Hello world", "Logo", "Tom & Jerry"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let translations: Vec<String> = ["首页", "你好世界", "标志", "汤姆 & 杰瑞"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let output = apply_translations_to_source(html, &originals, &translations, &HtmlProcessingOptions::default());
+        let expected_changes = [
+            ("    <title>Home Page</title>", "    <title>首页</title>"),
+            ("        Hello world", "        你好世界"),
+            ("      <img src=logo.png alt=Logo>", "      <img src=logo.png alt=\"标志\">"),
+            ("      <p>Tom &amp; Jerry</p>", "      <p>汤姆 &amp; 杰瑞</p>"),
+        ];
+
+        let original_lines: Vec<&str> = html.lines().collect();
+        let output_lines: Vec<&str> = output.lines().collect();
+        assert_eq!(original_lines.len(), output_lines.len());
+        for (original, translated) in original_lines.iter().zip(&output_lines) {
+            match expected_changes.iter().find(|(from, _)| from == original) {
+                Some((_, to)) => assert_eq!(translated, to),
+                // 非翻译行（含脚本和注释中的同名文本）逐字节保持不变
+                None => assert_eq!(translated, original),
+            }
+        }
+        assert!(output.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn test_dom_apply_keeps_surrounding_whitespace() {
+        let dom = parse("<html><body><p>\n    Hello world\n  </p></body></html>");
+        let translated = apply_translations_to_dom(
+            dom,
+            &["Hello world".to_string()],
+            &["你好世界".to_string()],
+        )
+        .unwrap();
+        let output = serialize_dom_to_html(translated).unwrap();
+        assert!(output.contains("<p>\n    你好世界\n  </p>"));
+    }
+
+    #[test]
+    fn test_time_datetime_attribute_is_never_translated() {
+        let html = "<html><body><time datetime=\"2024-01-01\">January 1, 2024</time></body></html>";
+        let dom = parse(html);
+        let texts = extract_translatable_texts(&dom);
+        assert_eq!(texts, vec!["January 1, 2024".to_string()]);
+
+        // 即使译文表中恰好包含datetime的值，属性也保持机器格式
+        let originals = vec!["January 1, 2024".to_string(), "2024-01-01".to_string()];
+        let translations = vec!["2024年1月1日".to_string(), "二〇二四".to_string()];
+        let output = serialize_dom_to_html(apply_translations_to_dom(dom, &originals, &translations).unwrap()).unwrap();
+        assert!(output.contains("<time datetime=\"2024-01-01\">2024年1月1日</time>"));
+    }
+
+    #[test]
+    fn test_localize_dates() {
+        let options = HtmlProcessingOptions {
+            date_locale: Some("zh".to_string()),
+            ..Default::default()
+        };
+        let html = "<html><body>\
+            <time datetime=\"2024-01-01\"> January 1, 2024 </time>\
+            <time datetime=\"2024-01-01\"><b>New Year</b></time>\
+            <time datetime=\"P2D\">two days</time></body></html>";
+        let dom = parse(html);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert!(!texts.contains(&"January 1, 2024".to_string()));
+        // 含子元素或无法解析的<time>仍按普通文本翻译
+        assert!(texts.contains(&"New Year".to_string()));
+        assert!(texts.contains(&"two days".to_string()));
+
+        let output = serialize_dom_to_html(
+            apply_translations_to_dom_with_options(dom, &[], &[], &options).unwrap(),
+        )
+        .unwrap();
+        assert!(output.contains("<time datetime=\"2024-01-01\"> 2024年1月1日 </time>"));
+
+        let source = apply_translations_to_source(html, &[], &[], &options);
+        assert!(source.contains("<time datetime=\"2024-01-01\"> 2024年1月1日 </time>"));
+        assert!(source.contains("<time datetime=\"P2D\">two days</time>"));
+    }
+
+    #[test]
+    fn test_script_extraction_is_opt_in() {
+        let html = "<html><body><p>Visible text</p>\
+            <script>showToast(\"Saved successfully\"); var apiKey = \"settings-panel\";</script></body></html>";
+        let dom = parse(html);
+        assert_eq!(extract_translatable_texts(&dom), vec!["Visible text".to_string()]);
+
+        let options = HtmlProcessingOptions {
+            translate_scripts: true,
+            ..Default::default()
+        };
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert!(texts.contains(&"Saved successfully".to_string()));
+    }
+
+    #[test]
+    fn test_ensure_utf8_charset() {
+        let dom = parse("<html><head><meta charset=\"ISO-8859-1\"><title>T</title></head><body></body></html>");
+        assert!(ensure_utf8_charset(&dom));
+        let output = serialize_dom_to_html(dom).unwrap();
+        assert!(output.contains("<meta charset=\"utf-8\">"));
+        assert!(!output.contains("ISO-8859-1"));
+
+        let dom = parse("<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1252\"></head></html>");
+        assert!(ensure_utf8_charset(&dom));
+        assert!(serialize_dom_to_html(dom).unwrap().contains("content=\"text/html; charset=utf-8\""));
+
+        // 缺失时注入到<head>开头；已是UTF-8时不修改
+        let dom = parse("<html><head><title>T</title></head><body></body></html>");
+        assert!(ensure_utf8_charset(&dom));
+        assert!(serialize_dom_to_html(dom).unwrap().contains("<head><meta charset=\"utf-8\"><title>"));
+        let dom = parse("<html><head><meta charset=\"UTF-8\"></head></html>");
+        assert!(!ensure_utf8_charset(&dom));
+    }
+
+    #[test]
+    fn test_ensure_utf8_charset_in_source() {
+        assert_eq!(
+            ensure_utf8_charset_in_source("<head>\n  <meta charset='iso-8859-1'>\n</head>"),
+            "<head>\n  <meta charset='utf-8'>\n</head>"
+        );
+        assert_eq!(
+            ensure_utf8_charset_in_source("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<title>T</title>"),
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head><meta charset=\"utf-8\">\n<title>T</title>"
+        );
+        assert_eq!(ensure_utf8_charset_in_source("<p>Hi</p>"), "<meta charset=\"utf-8\"><p>Hi</p>");
+        assert_eq!(
+            set_charset_in_source("<head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=utf-8\"></head>", "GBK"),
+            "<head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=GBK\"></head>"
+        );
+    }
+
+    #[test]
+    fn test_skip_numeric_cells() {
+        let html = "<html><body><table>\
+            <tr><th>Region</th><th>Revenue</th><th>Date</th></tr>\
+            <tr><td>North America</td><td>$1,234.50</td><td>2024-01-01</td></tr>\
+            <tr><td>Growth</td><td>-3.5%</td><td>12 items</td></tr>\
+            </table><p>2024-01-01</p></body></html>";
+        let options = HtmlProcessingOptions {
+            skip_numeric_cells: true,
+            ..Default::default()
+        };
+        let dom = parse(html);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        for header in ["Region", "Revenue", "Date", "North America", "Growth", "12 items"] {
+            assert!(texts.contains(&header.to_string()), "{}", header);
+        }
+        assert!(!texts.contains(&"$1,234.50".to_string()));
+        assert!(!texts.contains(&"-3.5%".to_string()));
+
+        // 正文中的同名日期被翻译时，数字单元格仍保持原样
+        let originals = vec!["Revenue".to_string(), "2024-01-01".to_string()];
+        let translations = vec!["收入".to_string(), "2024年1月1日".to_string()];
+        let output = serialize_dom_to_html(
+            apply_translations_to_dom_with_options(dom, &originals, &translations, &options).unwrap(),
+        )
+        .unwrap();
+        assert!(output.contains("<th>收入</th>"));
+        assert!(output.contains("<td>2024-01-01</td>"));
+        assert!(output.contains("<p>2024年1月1日</p>"));
+
+        let source = apply_translations_to_source(html, &originals, &translations, &options);
+        assert!(source.contains("<th>收入</th>"));
+        assert!(source.contains("<td>2024-01-01</td>"));
+        assert!(source.contains("<p>2024年1月1日</p>"));
+    }
+
+    #[test]
+    fn test_strip_invalid_html_chars() {
+        let (cleaned, removed) = strip_invalid_html_chars("<p>A\u{0}B\u{1B}C\u{85}D\u{FFFE}E\u{FDD0}</p>\t\r\n\u{0C}");
+        assert_eq!(cleaned, "<p>ABCDE</p>\t\r\n\u{0C}");
+        assert_eq!(removed, 5);
+
+        let (unchanged, removed) = strip_invalid_html_chars("<p>你好 \u{1F600} ok</p>");
+        assert_eq!(unchanged, "<p>你好 \u{1F600} ok</p>");
+        assert_eq!(removed, 0);
+    }
+
+    #[test]
+    fn test_interactive_elements_are_translated() {
+        let html = r#"<html><body>
+<details open><summary>Show more details</summary><p>Hidden content</p></details>
+<dialog id="confirm"><p>Are you sure?</p><form method="dialog"><button>Confirm action</button></form></dialog>
+<menu><li><button title="Copy text">Copy item</button></li></menu>
+</body></html>"#;
+        let options = HtmlProcessingOptions::default();
+        let texts = extract_translatable_texts_with_options(&parse(html), &options);
+        for expected in ["Show more details", "Hidden content", "Are you sure?", "Confirm action", "Copy item", "Copy text"] {
+            assert!(texts.contains(&expected.to_string()), "未提取: {}", expected);
+        }
+
+        let translations: Vec<String> = texts.iter().map(|t| format!("译:{}", t)).collect();
+        let translated =
+            apply_translations_to_dom_with_options(parse(html), &texts, &translations, &options).unwrap();
+        let dom_html = serialize_dom_to_html(translated).unwrap();
+        let source_html = apply_translations_to_source(html, &texts, &translations, &options);
+        for output in [&dom_html, &source_html] {
+            assert!(output.contains("<summary>译:Show more details</summary>"));
+            assert!(output.contains("<p>译:Hidden content</p>"));
+            assert!(output.contains("<p>译:Are you sure?</p>"));
+            assert!(output.contains("<button>译:Confirm action</button>"));
+            assert!(output.contains(r#"<button title="译:Copy text">译:Copy item</button>"#));
+            // 元素自身的属性保持不变
+            assert!(output.contains(r#"<dialog id="confirm">"#));
+            assert!(output.contains(r#"<form method="dialog">"#));
+        }
+        assert!(source_html.contains("<details open>"));
+    }
+
+    #[test]
+    fn test_extract_texts_with_context() {
+        let dom = parse(r#"<html><head><title>Page title</title></head><body>
+<h2>Heading</h2><p>Read <a href="/x">the docs</a> now</p>
+<img alt="A cat" src="cat.png"><script>var s = "Hello script";</script></body></html>"#);
+        let options = HtmlProcessingOptions {
+            translate_scripts: true,
+            ..HtmlProcessingOptions::default()
+        };
+
+        let extracted = extract_texts_with_context(&dom, &options);
+        let texts: Vec<&str> = extracted.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            extract_translatable_texts_with_options(&dom, &options)
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+        );
+
+        let find = |text: &str| extracted.iter().find(|e| e.text == text).unwrap();
+        assert_eq!(find("Page title").parent_tag, "title");
+        assert_eq!(find("Heading").parent_tag, "h2");
+        assert_eq!(find("the docs").parent_tag, "a");
+        assert!(!find("the docs").is_attribute);
+        let alt = find("A cat");
+        assert_eq!((alt.parent_tag.as_str(), alt.is_attribute, alt.attr_name.as_deref()), ("img", true, Some("alt")));
+        assert_eq!(find("Hello script").parent_tag, "script");
+    }
+
+    #[test]
+    fn test_split_html_into_chunks() {
+        let html = concat!(
+            "<html><head><title>T</title></head><body>\n",
+            "<div id=\"app\"><section><p>One</p></section>\n<section><p title=\"a>b\">Two</p><br></section>\n",
+            "<script>if (a < b) { x = \"</div>\"; }</script><!-- <div> --><section><p>Three</p></section></div>\n",
+            "</body></html>"
+        );
+        let chunks = split_html_into_chunks(html, 20).unwrap();
+        // 单一包裹元素#app内部按顶层section切分，骨架保留包裹元素
+        assert!(chunks.skeleton.contains(&format!("<div id=\"app\">{}</div>", CHUNK_PLACEHOLDER)));
+        assert_eq!(chunks.chunks.len(), 4);
+        assert_eq!(chunks.chunks[0], "<section><p>One</p></section>");
+        // 引号内的`>`、脚本和注释中的标签不影响切分
+        assert!(chunks.chunks[2].starts_with("\n<script>"));
+        assert!(chunks.chunks[3].ends_with("<section><p>Three</p></section>"));
+        assert_eq!(
+            chunks.skeleton.replace(CHUNK_PLACEHOLDER, &chunks.chunks.concat()),
+            html
+        );
+
+        // 块大小足够容纳全部内容，或省略了结束标签时不切分
+        assert!(split_html_into_chunks(html, 10_000).is_none());
+        assert!(split_html_into_chunks("<body><p>One<p>Two<p>Three</body>", 1).is_none());
+    }
+
+    #[test]
+    fn test_parallel_text_replacements_match_serial() {
+        let originals: Vec<String> = (0..PARALLEL_PREPARE_MIN_NODES * 2).map(|index| format!("Text {}", index)).collect();
+        let translations: Vec<String> = originals
+            .iter()
+            .enumerate()
+            .map(|(index, text)| if index % 3 == 0 { String::new() } else { format!("文本{}", &text[5..]) })
+            .collect();
+        let translation_map = build_translation_map(&originals, &translations);
+        let node_texts: Vec<String> = originals.iter().map(|text| format!("\n  {} ", text)).collect();
+
+        let serial = prepare_text_replacements(&node_texts, &translation_map, 1);
+        assert_eq!(prepare_text_replacements(&node_texts, &translation_map, 4), serial);
+        assert_eq!(serial[0], None);
+        assert_eq!(serial[1].as_deref(), Some("\n  文本1 "));
+    }
+
+    #[test]
+    fn test_form_constraints_kept_and_validation_messages_translated() {
+        let html = r#"<html><body><form data-msg="Please fix the errors">
+<input name="zip" pattern="[0-9]{6}" min="1" max="10" step="1" title="Enter a six-digit postal code" data-error="Invalid postal code" data-rule="zip">
+</form><div data-error="Not a form control"></div></body></html>"#;
+        let options = HtmlProcessingOptions {
+            json_attributes: vec!["pattern".to_string()],
+            ..Default::default()
+        };
+
+        let dom = parse(html);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        for value in ["Enter a six-digit postal code", "Invalid postal code", "Please fix the errors"] {
+            assert!(texts.contains(&value.to_string()), "{} 应被提取", value);
+        }
+        for value in ["[0-9]{6}", "1", "10", "zip", "Not a form control"] {
+            assert!(!texts.contains(&value.to_string()), "{} 不应被提取", value);
+        }
+
+        // 即使译文表中包含约束属性的值，回写时也保持原样
+        let originals: Vec<String> = ["Enter a six-digit postal code", "Invalid postal code", "Please fix the errors", "[0-9]{6}", "10"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let translations: Vec<String> = ["请输入六位邮政编码", "邮政编码无效", "请修正错误", "[零-九]{6}", "十"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let output = serialize_dom_to_html(
+            apply_translations_to_dom_with_options(dom, &originals, &translations, &options).unwrap(),
+        )
+        .unwrap();
+        let source = apply_translations_to_source(html, &originals, &translations, &options);
+        for rendered in [&output, &source] {
+            assert!(rendered.contains(r#"pattern="[0-9]{6}""#));
+            assert!(rendered.contains(r#"max="10""#));
+            assert!(rendered.contains(r#"title="请输入六位邮政编码""#));
+            assert!(rendered.contains(r#"data-error="邮政编码无效""#));
+            assert!(rendered.contains(r#"data-msg="请修正错误""#));
+            assert!(rendered.contains(r#"data-error="Not a form control""#));
+        }
+    }
+
+    #[test]
+    fn test_lazy_load_attributes_are_never_translated() {
+        let html = r#"<html><body><p>Gallery</p>
+<img src="placeholder.gif" data-src='{"title":"Real image"}' data-srcset='{"title":"Big image"}'
+ data-lazy='{"title":"Lazy"}' loading='{"title":"Eager"}' alt="Sunset" data-props='{"title":"Caption"}'>
+</body></html>"#;
+        let lazy_attributes = ["data-src", "data-srcset", "data-lazy", "loading"];
+        let all_configured = HtmlProcessingOptions {
+            json_attributes: lazy_attributes
+                .iter()
+                .map(|name| name.to_string())
+                .chain(std::iter::once("data-props".to_string()))
+                .collect(),
+            ..Default::default()
+        };
+
+        for options in [HtmlProcessingOptions::default(), all_configured] {
+            let dom = parse(html);
+            let texts = extract_translatable_texts_with_options(&dom, &options);
+            for value in ["Real image", "Big image", "Lazy", "Eager"] {
+                assert!(!texts.iter().any(|t| t.contains(value)), "{} 不应被提取", value);
+            }
+            assert!(texts.contains(&"Sunset".to_string()));
+            assert_eq!(texts.contains(&"Caption".to_string()), !options.json_attributes.is_empty());
+
+            // 即使译文表中包含这些值，回写时也不改动懒加载属性
+            let originals: Vec<String> = ["Real image", "Big image", "Lazy", "Eager", "Sunset", "Gallery"]
+                .iter()
+                .map(|t| t.to_string())
+                .collect();
+            let translations: Vec<String> = ["真图", "大图", "懒", "立即", "日落", "图库"]
+                .iter()
+                .map(|t| t.to_string())
+                .collect();
+            let output = serialize_dom_to_html(
+                apply_translations_to_dom_with_options(dom, &originals, &translations, &options).unwrap(),
+            )
+            .unwrap();
+            let source = apply_translations_to_source(html, &originals, &translations, &options);
+            for rendered in [&output, &source] {
+                for value in ["Real image", "Big image", "Lazy", "Eager"] {
+                    assert!(rendered.contains(value), "{} 应原样保留", value);
+                }
+                assert!(rendered.contains("日落"));
+                assert!(rendered.contains("placeholder.gif"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_picture_img_alt_is_translated_and_sources_are_kept() {
+        let html = r#"<html><body><picture>
+<source media="(min-width: 800px)" srcset="hero-large.webp 1x, hero-large@2x.webp 2x" type="image/webp">
+<source media="(max-width: 799px)" srcset="hero-small.jpg">
+<img src="hero.jpg" alt="Mountain view at sunrise" title="Hero image">
+</picture></body></html>"#;
+        let options = HtmlProcessingOptions::default();
+        let dom = parse(html);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert_eq!(texts, vec!["Mountain view at sunrise".to_string(), "Hero image".to_string()]);
+
+        let translations = vec!["日出时的山景".to_string(), "主图".to_string()];
+        let output = serialize_dom_to_html(
+            apply_translations_to_dom_with_options(dom, &texts, &translations, &options).unwrap(),
+        )
+        .unwrap();
+        let source = apply_translations_to_source(html, &texts, &translations, &options);
+        for rendered in [&output, &source] {
+            assert!(rendered.contains(r#"alt="日出时的山景""#));
+            assert!(rendered.contains(r#"title="主图""#));
+            assert!(rendered.contains(r#"media="(min-width: 800px)""#));
+            assert!(rendered.contains(r#"srcset="hero-large.webp 1x, hero-large@2x.webp 2x""#));
+            assert!(rendered.contains(r#"media="(max-width: 799px)" srcset="hero-small.jpg""#));
+            assert!(rendered.contains(r#"type="image/webp""#));
+        }
+    }
+
+    #[test]
+    fn test_microdata_meta_content_is_translated() {
+        let html = r#"<html><body><div itemscope itemtype="https://schema.org/Product">
+<span itemprop="name">Trail Shoe</span>
+<meta itemprop="description" content="Lightweight running shoe">
+<meta itemprop="price" content="89.99">
+<meta itemprop="image" content="shoe.jpg">
+</div></body></html>"#;
+        let options = HtmlProcessingOptions::default();
+        let dom = parse(html);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert_eq!(texts.len(), 2);
+        assert!(texts.contains(&"Trail Shoe".to_string()));
+        assert!(texts.contains(&"Lightweight running shoe".to_string()));
+
+        let translations: Vec<String> = texts
+            .iter()
+            .map(|text| if text == "Trail Shoe" { "越野跑鞋" } else { "轻量跑鞋" }.to_string())
+            .collect();
+        let output = serialize_dom_to_html(
+            apply_translations_to_dom_with_options(dom, &texts, &translations, &options).unwrap(),
+        )
+        .unwrap();
+        let source = apply_translations_to_source(html, &texts, &translations, &options);
+        for rendered in [&output, &source] {
+            assert!(rendered.contains(r#"<meta itemprop="description" content="轻量跑鞋">"#));
+            assert!(rendered.contains(r#"content="89.99""#));
+            assert!(rendered.contains(r#"content="shoe.jpg""#));
+        }
+
+        assert!(is_microdata_text_meta("meta", Some("name headline")));
+        assert!(!is_microdata_text_meta("meta", None));
+        assert!(!is_microdata_text_meta("span", Some("name")));
+    }
+
+    #[test]
+    fn test_to_xhtml_void_elements() {
+        let html = r#"<p>A<br>B<img src="a.png" alt="x > y"><br/></p><script>if (a<br) {}</script><!-- <hr> --><input disabled>"#;
+        assert_eq!(
+            to_xhtml_void_elements(html),
+            r#"<p>A<br/>B<img src="a.png" alt="x > y"/><br/></p><script>if (a<br) {}</script><!-- <hr> --><input disabled/>"#
+        );
+    }
+
+    #[test]
+    fn test_is_xhtml_document() {
+        assert!(is_xhtml_document(
+            r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd"><html></html>"#
+        ));
+        assert!(is_xhtml_document("\u{feff}<?xml version=\"1.0\"?><html></html>"));
+        assert!(!is_xhtml_document("<!DOCTYPE html><html><body>xhtml</body></html>"));
+        assert!(!is_xhtml_document("<html></html>"));
+    }
+
+    #[test]
+    fn test_title_translate_tags_whitelist() {
+        let html = r#"<html><body><p><abbr title="World Health Organization">WHO</abbr>
+<span title="sku-1234-x">Item</span><a href="/" title="Go home">Home</a><img src="a.png" alt="Logo image"></p></body></html>"#;
+        let options = HtmlProcessingOptions {
+            title_tags: vec!["abbr".to_string(), "a".to_string()],
+            ..HtmlProcessingOptions::default()
+        };
+        let dom = parse(html);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert!(texts.contains(&"World Health Organization".to_string()));
+        assert!(texts.contains(&"Go home".to_string()));
+        assert!(texts.contains(&"Logo image".to_string()));
+        assert!(!texts.contains(&"sku-1234-x".to_string()));
+
+        let translations: Vec<String> = texts.iter().map(|text| format!("译:{}", text)).collect();
+        let output = serialize_dom_to_html(
+            apply_translations_to_dom_with_options(parse(html), &texts, &translations, &options).unwrap(),
+        )
+        .unwrap();
+        let source = apply_translations_to_source(html, &texts, &translations, &options);
+        for rendered in [&output, &source] {
+            assert!(rendered.contains(r#"title="译:World Health Organization""#));
+            assert!(rendered.contains(r#"title="sku-1234-x""#));
+        }
+    }
+
+    const MIXED_LANG_SAMPLE: &str = "<html lang=\"zh-CN\"><body><p>Shared</p>\
+        <div lang=\"en\"><p title=\"Tip\">Shared</p><span lang=\"zh\">Shared</span></div>\
+        <div lang=\"EN-us\"><div><p>Nested</p></div><p>Tail</p></div><p>Footer</p></body></html>";
+
+    #[test]
+    fn test_respect_lang_translates_only_foreign_regions() {
+        let options = HtmlProcessingOptions {
+            skip_lang: Some("zh".to_string()),
+            ..Default::default()
+        };
+        let dom = parse(MIXED_LANG_SAMPLE);
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        assert_eq!(texts.len(), 4);
+        for text in ["Shared", "Tip", "Nested", "Tail"] {
+            assert!(texts.contains(&text.to_string()), "缺少 {}", text);
+        }
+
+        // 同一文本只在外文区域被替换，已是中文的区域保持原样
+        let originals = vec!["Shared", "Tip", "Nested", "Tail", "Footer"].into_iter().map(String::from).collect::<Vec<_>>();
+        let translations = vec!["共享", "提示", "嵌套", "结尾", "页脚"].into_iter().map(String::from).collect::<Vec<_>>();
+        let translated =
+            apply_translations_to_dom_with_options(dom, &originals, &translations, &options).unwrap();
+        let html = serialize_dom_to_html(translated).unwrap();
+        assert!(html.contains("<body><p>Shared</p>"));
+        assert!(html.contains("<div lang=\"en\"><p title=\"提示\">共享</p><span lang=\"zh\">Shared</span></div>"));
+        assert!(html.contains("<p>嵌套</p></div><p>结尾</p></div><p>Footer</p>"));
+
+        // 保留格式回写与DOM回写范围一致
+        let source = apply_translations_to_source(MIXED_LANG_SAMPLE, &originals, &translations, &options);
+        assert!(source.contains("<body><p>Shared</p>"));
+        assert!(source.contains("<div lang=\"en\"><p title=\"提示\">共享</p><span lang=\"zh\">Shared</span></div>"));
+        assert!(source.contains("<p>嵌套</p></div><p>结尾</p></div><p>Footer</p>"));
+    }
+
+    #[test]
+    fn test_is_same_language() {
+        assert!(is_same_language("zh-CN", "zh"));
+        assert!(is_same_language("ZH_cn", "zh-CN"));
+        assert!(!is_same_language("zh-TW", "zh-CN"));
+        assert!(!is_same_language("en", "zh"));
+        assert!(!is_same_language("", "zh"));
+    }
+}
@@ -0,0 +1,445 @@
+//! HTML清洗模块
+//!
+//! 参照ammonia的白名单思路清洗翻译结果：只保留白名单内的元素和属性，所有URL属性及CSS中的
+//! `url()`都检查协议，避免爬取页面中的恶意代码随翻译结果分发给他人时被执行
+
+// 标准库导入
+use std::rc::Rc;
+
+// 第三方crate导入
+use anyhow::Result;
+use html5ever::parse_document;
+use html5ever::tendril::TendrilSink;
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+
+// 本地模块导入
+use crate::html_processor::serialize_dom_to_html;
+
+/// 允许保留的元素（HTML、SVG、MathML，按小写本地名比较）
+///
+/// 不在白名单、也不在[`REMOVED_ELEMENTS`]中的元素（如自定义组件）只去掉标签、保留子节点，
+/// 避免丢失其中的译文。
+const ALLOWED_ELEMENTS: &[&str] = &[
+    // 文档结构
+    "html", "head", "body", "title", "meta", "link", "style",
+    // 分区与文本
+    "address", "article", "aside", "footer", "header", "h1", "h2", "h3", "h4", "h5", "h6", "hgroup",
+    "main", "nav", "section", "blockquote", "dd", "div", "dl", "dt", "figcaption", "figure", "hr",
+    "li", "ol", "p", "pre", "ul", "a", "abbr", "b", "bdi", "bdo", "br", "cite", "code", "data",
+    "dfn", "em", "i", "kbd", "mark", "q", "rp", "rt", "ruby", "s", "samp", "small", "span", "strong",
+    "sub", "sup", "time", "u", "var", "wbr", "del", "ins", "center", "font", "big", "tt", "strike",
+    "details", "summary", "dialog", "template",
+    // 媒体
+    "area", "audio", "img", "map", "track", "video", "picture", "source",
+    // 表格
+    "caption", "col", "colgroup", "table", "tbody", "td", "tfoot", "th", "thead", "tr",
+    // 表单
+    "form", "fieldset", "legend", "label", "input", "button", "select", "option", "optgroup",
+    "textarea", "output", "progress", "meter", "datalist",
+    // SVG（不含animate/set等可改写属性的动画元素和foreignObject）
+    "svg", "g", "defs", "symbol", "use", "path", "rect", "circle", "ellipse", "line", "polyline",
+    "polygon", "text", "tspan", "textpath", "desc", "lineargradient", "radialgradient", "stop",
+    "clippath", "mask", "pattern", "image", "marker", "filter", "fegaussianblur", "feoffset",
+    "feblend", "fecolormatrix", "feflood", "fecomposite", "femerge", "femergenode",
+    // MathML（不含annotation-xml）
+    "math", "mi", "mn", "mo", "ms", "mtext", "mrow", "msub", "msup", "msubsup", "mfrac", "msqrt",
+    "mroot", "mspace", "mtable", "mtr", "mtd", "munder", "mover", "munderover", "mpadded",
+    "mphantom", "menclose", "semantics", "annotation",
+];
+
+/// 整体移除的元素（含子树）
+const REMOVED_ELEMENTS: &[&str] = &[
+    "script", "object", "embed", "applet", "iframe", "frame", "frameset", "base", "noscript",
+    "noembed", "noframes", "xmp", "plaintext", "portal", "param", "animate", "animatemotion",
+    "animatetransform", "animatecolor", "set", "discard", "handler", "listener",
+    "foreignobject", "annotation-xml",
+];
+
+/// 允许保留的属性；`data-*`和`aria-*`属性另行放行
+const ALLOWED_ATTRIBUTES: &[&str] = &[
+    // 全局属性
+    "id", "class", "title", "lang", "dir", "hidden", "translate", "role", "tabindex", "accesskey",
+    "xml:lang", "xml:space", "xmlns", "xmlns:xlink",
+    // 链接与媒体
+    "alt", "width", "height", "sizes", "hreflang", "rel", "target", "type", "media", "download",
+    "referrerpolicy", "crossorigin", "loading", "decoding", "controls", "autoplay", "loop", "muted",
+    "playsinline", "preload", "kind", "srclang", "label", "default", "usemap", "ismap", "shape",
+    "coords", "charset", "name", "content", "http-equiv", "property", "itemprop", "itemscope",
+    "itemtype", "integrity", "as",
+    // 表格与列表
+    "colspan", "rowspan", "headers", "scope", "span", "abbr", "start", "reversed", "value",
+    "align", "valign", "border", "cellpadding", "cellspacing", "bgcolor", "color", "face", "size",
+    "nowrap", "datetime", "open",
+    // 表单
+    "method", "enctype", "for", "placeholder", "disabled", "checked", "selected", "readonly",
+    "required", "multiple", "maxlength", "minlength", "min", "max", "step", "pattern",
+    "autocomplete", "rows", "cols", "wrap", "list", "form", "novalidate", "accept", "low", "high",
+    "optimum",
+    // SVG展示属性
+    "viewbox", "preserveaspectratio", "d", "x", "y", "x1", "y1", "x2", "y2", "cx", "cy", "r", "rx",
+    "ry", "dx", "dy", "fx", "fy", "points", "transform", "fill", "fill-opacity", "fill-rule",
+    "stroke", "stroke-width", "stroke-opacity", "stroke-linecap", "stroke-linejoin",
+    "stroke-dasharray", "stroke-dashoffset", "stroke-miterlimit", "opacity", "clip-path",
+    "clip-rule", "clippathunits", "mask", "maskunits", "offset", "stop-color", "stop-opacity",
+    "gradientunits", "gradienttransform", "spreadmethod", "patternunits", "patterntransform",
+    "text-anchor", "dominant-baseline", "font-size", "font-family", "font-weight", "font-style",
+    "letter-spacing", "textlength", "lengthadjust", "markerwidth", "markerheight", "refx", "refy",
+    "orient", "markerunits", "marker-start", "marker-mid", "marker-end", "filter", "stddeviation",
+    "in", "in2", "result", "mode", "operator", "values", "flood-color", "flood-opacity", "version",
+    "focusable", "vector-effect", "visibility", "display",
+    // MathML
+    "mathvariant", "mathsize", "mathcolor", "displaystyle", "scriptlevel", "linethickness",
+    "fence", "separator", "stretchy", "notation", "encoding",
+];
+
+/// 值为URL、需要检查协议的属性
+const URL_ATTRIBUTES: &[&str] = &[
+    "href", "src", "action", "formaction", "poster", "background", "cite", "data", "xlink:href",
+    "longdesc", "srcset",
+];
+
+/// 允许的URL协议
+const ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto", "tel", "ftp", "data"];
+
+/// `data:`URL允许的媒体类型前缀（图片、字体、音视频和样式表为Monolith内联资源所需）
+const ALLOWED_DATA_MEDIA_TYPES: &[&str] = &[
+    "image/",
+    "font/",
+    "audio/",
+    "video/",
+    "text/css",
+    "application/font-",
+    "application/x-font-",
+    "application/vnd.ms-fontobject",
+];
+
+/// 清洗结果统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SanitizeReport {
+    /// 移除的元素数量
+    pub removed_elements: usize,
+    /// 移除的属性数量
+    pub removed_attributes: usize,
+}
+
+impl SanitizeReport {
+    /// 是否做了任何修改
+    pub fn is_clean(&self) -> bool {
+        self.removed_elements == 0 && self.removed_attributes == 0
+    }
+}
+
+/// 清洗HTML，返回清洗后的HTML和统计
+///
+/// - 只保留白名单内的元素；`<script>`、`<iframe>`、`<object>`、SVG动画元素等连同子树移除，
+///   其余未知元素去掉标签、保留内容，`http-equiv="refresh"`的`<meta>`整体移除
+/// - 只保留白名单内的属性（另放行`data-*`、`aria-*`），`on*`事件属性、`srcdoc`等一律移除
+/// - URL属性只允许http(s)、mailto、tel、ftp、相对地址，以及图片/字体/样式等安全媒体类型的`data:`
+/// - `style`属性和`<style>`内容中的`url()`、`@import`同样检查协议，含`expression(`、
+///   `javascript:`等的样式被移除
+pub fn sanitize_html(html: &str) -> Result<(String, SanitizeReport)> {
+    let dom = parse_document(RcDom::default(), Default::default()).one(html);
+    let mut report = SanitizeReport::default();
+    sanitize_node(&dom.document, &mut report);
+    Ok((serialize_dom_to_html(dom)?, report))
+}
+
+/// 元素的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ElementAction {
+    /// 保留元素，清洗其属性
+    Keep,
+    /// 去掉标签，子节点提升到父节点
+    Unwrap,
+    /// 连同子树移除
+    Remove,
+}
+
+/// 递归清洗节点的子节点
+fn sanitize_node(node: &Handle, report: &mut SanitizeReport) {
+    let children = std::mem::take(&mut *node.children.borrow_mut());
+    let mut kept = Vec::with_capacity(children.len());
+    for child in children {
+        match element_action(&child) {
+            ElementAction::Remove => report.removed_elements += 1,
+            ElementAction::Unwrap => {
+                report.removed_elements += 1;
+                sanitize_node(&child, report);
+                for grandchild in child.children.borrow_mut().drain(..) {
+                    grandchild.parent.set(Some(Rc::downgrade(node)));
+                    kept.push(grandchild);
+                }
+            }
+            ElementAction::Keep => {
+                sanitize_attributes(&child, report);
+                sanitize_node(&child, report);
+                kept.push(child);
+            }
+        }
+    }
+    *node.children.borrow_mut() = kept;
+
+    // <template>内容位于独立的文档片段中
+    if let NodeData::Element {
+        template_contents: ref contents,
+        ..
+    } = node.data
+    {
+        if let Some(contents) = contents.borrow().as_ref() {
+            sanitize_node(contents, report);
+        }
+    }
+}
+
+/// 移除元素上不在白名单或值不安全的属性
+fn sanitize_attributes(node: &Handle, report: &mut SanitizeReport) {
+    if let NodeData::Element { ref attrs, .. } = node.data {
+        attrs.borrow_mut().retain(|attr| {
+            let name = attr.name.local.as_ref().to_ascii_lowercase();
+            let name = match &attr.name.prefix {
+                Some(prefix) => format!("{}:{}", prefix.as_ref(), name),
+                None => name,
+            };
+            let keep = is_safe_attribute(&name, &attr.value);
+            report.removed_attributes += usize::from(!keep);
+            keep
+        });
+    }
+}
+
+/// 判断节点的处理方式
+fn element_action(node: &Handle) -> ElementAction {
+    let (name, attrs) = match &node.data {
+        NodeData::Element { name, attrs, .. } => (name.local.as_ref().to_ascii_lowercase(), attrs.borrow()),
+        NodeData::ProcessingInstruction { .. } => return ElementAction::Remove,
+        _ => return ElementAction::Keep,
+    };
+
+    if REMOVED_ELEMENTS.contains(&name.as_str()) {
+        return ElementAction::Remove;
+    }
+    if name == "meta"
+        && attrs.iter().any(|attr| {
+            attr.name.local.as_ref().eq_ignore_ascii_case("http-equiv")
+                && attr.value.trim().eq_ignore_ascii_case("refresh")
+        })
+    {
+        return ElementAction::Remove;
+    }
+    if name == "style" && !is_safe_css(&text_content(node)) {
+        return ElementAction::Remove;
+    }
+    if ALLOWED_ELEMENTS.contains(&name.as_str()) {
+        ElementAction::Keep
+    } else {
+        ElementAction::Unwrap
+    }
+}
+
+/// 拼接节点下的全部文本
+fn text_content(node: &Handle) -> String {
+    let mut text = String::new();
+    for child in node.children.borrow().iter() {
+        match &child.data {
+            NodeData::Text { contents } => text.push_str(&contents.borrow()),
+            NodeData::Element { .. } => text.push_str(&text_content(child)),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// 属性是否可以保留
+fn is_safe_attribute(name: &str, value: &str) -> bool {
+    if name == "style" {
+        return is_safe_css(value);
+    }
+    if name == "srcset" {
+        return value
+            .split(',')
+            .filter_map(|candidate| candidate.split_whitespace().next())
+            .all(is_safe_url);
+    }
+    if URL_ATTRIBUTES.contains(&name) {
+        return is_safe_url(value);
+    }
+    ALLOWED_ATTRIBUTES.contains(&name) || name.starts_with("data-") || name.starts_with("aria-")
+}
+
+/// 解码CSS转义（`\6a`、`\"`等），避免以转义拼出危险关键字
+fn decode_css_escapes(css: &str) -> String {
+    let mut decoded = String::with_capacity(css.len());
+    let mut chars = css.chars().peekable();
+    while let Some(character) = chars.next() {
+        if character != '\\' {
+            decoded.push(character);
+            continue;
+        }
+        let mut hex = String::new();
+        while hex.len() < 6 && chars.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+            hex.extend(chars.next());
+        }
+        if hex.is_empty() {
+            decoded.extend(chars.next());
+            continue;
+        }
+        if chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        decoded.push(
+            u32::from_str_radix(&hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+        );
+    }
+    decoded
+}
+
+/// CSS是否安全：不含脚本表达式，`url()`和`@import`引用的地址均使用允许的协议
+fn is_safe_css(css: &str) -> bool {
+    let normalized: String = decode_css_escapes(css)
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if ["expression(", "javascript:", "vbscript:", "-moz-binding", "behavior:"]
+        .iter()
+        .any(|pattern| normalized.contains(pattern))
+    {
+        return false;
+    }
+
+    let urls = normalized.match_indices("url(").map(|(pos, _)| pos + "url(".len());
+    let imports = normalized
+        .match_indices("@import")
+        .map(|(pos, _)| pos + "@import".len())
+        .filter(|&pos| normalized[pos..].starts_with(['"', '\'']));
+    urls.chain(imports).all(|start| {
+        let reference = normalized[start..].trim_start_matches(['"', '\'']);
+        let end = reference.find(['"', '\'', ')', ';']).unwrap_or(reference.len());
+        is_safe_url(&reference[..end])
+    })
+}
+
+/// URL是否使用允许的协议
+fn is_safe_url(url: &str) -> bool {
+    // 浏览器会忽略URL中的空白和控制字符，如"java\tscript:"
+    let normalized: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    let scheme = match normalized.find(':') {
+        Some(pos) if !normalized[..pos].contains(['/', '?', '#']) => &normalized[..pos],
+        // 没有协议的相对地址或片段
+        _ => return true,
+    };
+
+    if !ALLOWED_SCHEMES.contains(&scheme) {
+        return false;
+    }
+    if scheme == "data" {
+        let media_type = normalized["data:".len()..]
+            .split([';', ','])
+            .next()
+            .unwrap_or_default();
+        return ALLOWED_DATA_MEDIA_TYPES
+            .iter()
+            .any(|allowed| media_type.starts_with(allowed));
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_html() {
+        let html = r#"<html><head><base href="https://evil.example/"><meta http-equiv="refresh" content="0;url=javascript:alert(1)">
+            <script>alert(1)</script></head><body>
+            <a href="javascript:alert(1)" onclick="steal()">点击</a>
+            <a href=" JaVa&#9;Script:alert(1)">编码绕过</a>
+            <a href="/docs#intro" title="文档">文档</a>
+            <img src="data:image/png;base64,AAAA" onerror="steal()" alt="图片">
+            <iframe src="data:text/html;base64,PHNjcmlwdD4=" srcdoc="<script>x</script>"></iframe>
+            <div style="width: expression(alert(1))">样式</div>
+            <p style="color: red">正文</p>
+            <embed src="movie.swf">
+            </body></html>"#;
+
+        let (output, report) = sanitize_html(html).unwrap();
+        assert!(!output.contains("<script"));
+        assert!(!output.contains("<base"));
+        assert!(!output.contains("refresh"));
+        assert!(!output.contains("<embed"));
+        assert!(!output.contains("onclick"));
+        assert!(!output.contains("onerror"));
+        assert!(!output.contains("srcdoc"));
+        assert!(!output.to_ascii_lowercase().contains("javascript"));
+        assert!(!output.contains("data:text/html"));
+        assert!(!output.contains("expression("));
+        // 安全内容原样保留
+        assert!(output.contains(r#"<a href="/docs#intro" title="文档">文档</a>"#));
+        assert!(output.contains(r#"src="data:image/png;base64,AAAA""#));
+        assert!(output.contains(r#"<p style="color: red">正文</p>"#));
+        assert!(!output.contains("<iframe"));
+        assert_eq!(report.removed_elements, 5);
+        assert_eq!(report.removed_attributes, 5);
+    }
+
+    #[test]
+    fn test_allowlist_blocks_known_bypasses() {
+        let html = r##"<html><head>
+            <style>body { background: url( 'java\73 cript:alert(1)') }</style>
+            <style>@import "data:text/html,x"; p { color: red }</style>
+            <style>.safe { background: url("data:image/png;base64,AAAA") }</style>
+            </head><body>
+            <svg><a><animate attributeName="href" values="javascript:alert(1)"/><text>一</text></a></svg>
+            <svg><a xlink:href="#x"><set attributeName="href" to="javascript:alert(2)"/><text>二</text></a></svg>
+            <svg><foreignObject><p>嵌入</p></foreignObject></svg>
+            <iframe src="https://evil.example/"></iframe>
+            <div style="background-image: url(javascript:alert(3))">背景</div>
+            <my-widget data-id="7" formaction2="x"><p>组件内文本</p></my-widget>
+            <p ping="https://tracker.example/" aria-label="说明">正文</p>
+            </body></html>"##;
+
+        let (output, _) = sanitize_html(html).unwrap();
+        let lowered = output.to_ascii_lowercase();
+        assert!(!lowered.contains("javascript"));
+        assert!(!lowered.contains("<animate"));
+        assert!(!lowered.contains("<set"));
+        assert!(!lowered.contains("foreignobject"));
+        assert!(!output.contains("<iframe"));
+        assert!(!output.contains("data:text/html"));
+        assert!(!output.contains("ping="));
+        // 安全样式保留，未知元素去掉标签但保留内容
+        assert!(output.contains(r#"url("data:image/png;base64,AAAA")"#));
+        assert!(output.contains("<text>一</text>"));
+        assert!(!output.contains("<my-widget"));
+        assert!(output.contains("<p>组件内文本</p>"));
+        assert!(output.contains(r#"<p aria-label="说明">正文</p>"#));
+    }
+
+    #[test]
+    fn test_is_safe_css() {
+        assert!(is_safe_css("color: red; background: url(/img/bg.png)"));
+        assert!(is_safe_css("src: url(data:font/woff2;base64,AAAA)"));
+        assert!(!is_safe_css("background: url(JAVASCRIPT:alert(1))"));
+        assert!(!is_safe_css(r#"background: url("\6a avascript:alert(1)")"#));
+        assert!(!is_safe_css("width: expression(alert(1))"));
+        assert!(!is_safe_css("@import 'vbscript:x'"));
+    }
+
+    #[test]
+    fn test_is_safe_url() {
+        assert!(is_safe_url("https://example.com/a?b=c:d"));
+        assert!(is_safe_url("page.html?next=javascript:x"));
+        assert!(is_safe_url("mailto:a@example.com"));
+        assert!(is_safe_url("data:font/woff2;base64,AAAA"));
+        assert!(!is_safe_url("vbscript:msgbox"));
+        assert!(!is_safe_url("data:text/html,<script>alert(1)</script>"));
+    }
+}
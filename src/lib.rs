@@ -7,12 +7,36 @@ pub mod temp_manager;
 pub mod translator;
 pub mod utils;
 pub mod html_processor;
+pub mod feed_processor;
+pub mod markdown_processor;
+pub mod readability;
+pub mod html_sanitizer;
+pub mod date_localizer;
+pub mod font_embedder;
+pub mod translation_cache;
+pub mod page_cache;
+pub mod dictionary;
+pub mod review_export;
+pub mod source_map;
+pub mod coverage_report;
+pub mod quality_check;
+pub mod rate_limiter;
+pub mod circuit_breaker;
+pub mod shutdown;
+pub mod watch;
+pub mod memory_monitor;
+pub mod output_sink;
+pub mod symbol_protection;
+pub mod pii_redaction;
+pub mod html_entities;
+pub mod brand_protection;
 pub mod error;
 pub mod config;
 pub mod stats;
 pub mod api_constants;
+pub mod build_info;
 
 // 导出核心类型
-pub use error::{TranslationError, Result};
+pub use error::{NetworkErrorKind, TranslationError, Result};
 pub use config::{LocalTranslationConfig, Cli};
 pub use utils::InputSource;
\ No newline at end of file
@@ -3,45 +3,139 @@
 //! 高性能HTML翻译命令行工具，支持文件和URL两种输入模式
 
 // 标准库导入
+use std::process::ExitCode;
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 
 // 第三方crate导入
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, FromArgMatches};
+use futures::StreamExt;
 use tracing::{error, info, warn};
 
 // 本地模块导入
-mod api_constants;
-mod config;
-mod error;
-mod stats;
-mod utils;
-mod html_processor;
-mod translator;
-mod web_crawler;
-mod temp_manager;
-
-use config::{Cli, LocalTranslationConfig, LocalTranslationStats};
-use error::TranslationError;
-use stats::{TranslationStats, print_performance_stats, format_duration};
-use utils::{init_logging, validate_input_source, generate_output_path_for_source, InputSource};
-use translator::translate_with_indexed_mode;
-use web_crawler::WebCrawler;
-use temp_manager::TempManager;
-use api_constants::{get_api_url, get_batch_size};
+use translation_cli::config::{explain_cli_config, ApiProtocol, CacheArgs, Cli, Command, LocalTranslationConfig, LocalTranslationStats};
+use translation_cli::translation_cache::{SharedTranslations, TranslationCache};
+use translation_cli::page_cache::PageCache;
+use translation_cli::pii_redaction::PiiRedactor;
+use translation_cli::memory_monitor::MemoryMonitor;
+use translation_cli::error::suggestion_for;
+use translation_cli::build_info::build_info_report;
+use translation_cli::stats::{BatchSummary, TranslationStats, batch_progress_json, failed_result_json, print_batch_summary, print_performance_stats, format_duration, write_result_json};
+use translation_cli::utils::{init_logging, validate_input_source, generate_output_path_for_source, generate_output_path_in_dir, strip_utf8_bom, detect_content_type, write_output_file, batch_output_path, collect_batch_input_files, is_batch_input_file, ContentType, InputSource, OutputPathRegistry, sample_items, count_unmappable_chars, declare_output_encoding, write_output_bytes, preview_output_path};
+use translation_cli::output_sink::{FileSink, HttpPutSink, MultiSink, OutputSink};
+use translation_cli::font_embedder::{embed_fonts_in_html_with_concurrency, embed_fonts_in_html_with_throttle};
+use translation_cli::html_processor::{strip_invalid_html_chars, validate_translated_html};
+use translation_cli::readability::extract_readable_html;
+use translation_cli::html_sanitizer::sanitize_html;
+use translation_cli::translator::{check_translation_api, render_request_template, HEALTH_CHECK_TEXT, translate_feed_with_report, translate_markdown_with_report, translate_with_outcome, TranslationReport};
+use translation_cli::web_crawler::{inline_resource_inventory, robots_noindex, WebCrawler};
+use translation_cli::temp_manager::TempManager;
+use translation_cli::shutdown::{ShutdownSignal, INTERRUPTED_EXIT_CODE};
+use translation_cli::watch::{ChangeWatcher, WATCH_DEBOUNCE};
+use translation_cli::api_constants::{api_config, get_api_url, get_batch_size};
+
+/// 进程级的中断停止标志，进入翻译流程前安装
+static SHUTDOWN: OnceLock<ShutdownSignal> = OnceLock::new();
+
+/// 是否已收到中断信号
+fn shutdown_requested() -> bool {
+    SHUTDOWN.get().is_some_and(ShutdownSignal::is_requested)
+}
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() -> Result<ExitCode> {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
     // 初始化日志系统
     init_logging(cli.verbose, cli.quiet);
 
+    // 子命令不进入翻译流程
+    match &cli.command {
+        Some(Command::Cache(args)) => return run_cache_command(args).map(|()| ExitCode::SUCCESS),
+        Some(Command::Info) => {
+            println!("{}", build_info_report());
+            return Ok(ExitCode::SUCCESS);
+        }
+        None => {}
+    }
+
+    // 打印合并后的有效配置，--dry-run 时到此为止
+    if cli.explain {
+        println!("{}", explain_cli_config(&matches));
+        if cli.dry_run {
+            return Ok(ExitCode::SUCCESS);
+        }
+    }
+
+    // Ctrl-C/SIGTERM时停止发起新批次和新文件，写出已完成的结果后退出
+    let _ = SHUTDOWN.set(ShutdownSignal::install());
+
     // 验证输入源（文件或URL）
-    let input_source = validate_input_source(&cli.input)?;
+    let input = cli.input.as_deref().context("缺少 --input 参数")?;
+    let input_source = validate_input_source(input)?;
 
-    // 生成输出文件路径
-    let output_path = generate_output_path_for_source(&input_source, &cli.output, &cli.lang);
+    // 验证自定义请求模板，避免每个批次都因模板错误失败
+    if let Some(template) = &cli.request_template {
+        render_request_template(template, "", &cli.lang, "auto")
+            .context("--request-template 无效")?;
+    }
+
+    // 输入为目录时只能指定输出目录
+    let is_directory_input = matches!(&input_source, InputSource::File(path) if path.is_dir());
+    if is_directory_input && cli.output.is_some() {
+        Cli::command()
+            .error(ErrorKind::ArgumentConflict, "输入为目录时不能使用 --output，请用 --output-dir 指定输出根目录")
+            .exit();
+    }
+
+    // 生成输出文件路径，指定输出目录时放入该目录
+    let output_path = match &cli.output_dir {
+        Some(output_dir) => generate_output_path_in_dir(&input_source, output_dir, &cli.lang),
+        None => generate_output_path_for_source(&input_source, &cli.output, &cli.lang),
+    };
+    if let Some(output_dir) = cli.output_dir.as_ref().filter(|_| !is_directory_input) {
+        std::fs::create_dir_all(output_dir)
+            .with_context(|| format!("创建输出目录失败: {}", output_dir.display()))?;
+    }
+
+    // 开始翻译前探测一次API，批量任务不必每个文件重复
+    if cli.health_check {
+        if let Err(e) = run_health_check(&build_translation_config(&cli, &output_path)).await {
+            error!("❌ {:#}", e);
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+
+    // 单文件、批量和监听模式共用同一个内存监控器，批量和监听模式在每个文件前后检查
+    let memory_monitor = MemoryMonitor::new(cli.memory_warning as u64 * 1024 * 1024);
+
+    // 监听模式：首次翻译后常驻，输入变化时自动重新翻译
+    if cli.watch {
+        return run_watch_mode(&cli, &input_source, &output_path, memory_monitor)
+            .await
+            .map(|()| ExitCode::SUCCESS);
+    }
+
+    // 输入为目录时进入批量翻译
+    if let InputSource::File(dir) = &input_source {
+        if is_directory_input {
+            let summary = translate_directory(&cli, dir, memory_monitor).await?;
+            if shutdown_requested() {
+                warn!("🛑 批量任务已中断：已完成的文件已写出，译文已保存到翻译缓存，重新运行将从缓存续跑");
+                return Ok(interrupted_exit_code());
+            }
+            if summary.has_failures() {
+                return Ok(ExitCode::FAILURE);
+            }
+            if below_min_success_rate(&cli, summary.success_rate(), summary.texts_translated, summary.texts_collected) {
+                return Ok(ExitCode::from(2));
+            }
+            return Ok(ExitCode::SUCCESS);
+        }
+    }
 
     if !cli.quiet {
         info!("🚀 启动HTML翻译 - 目标: 亚秒级性能");
@@ -55,19 +149,53 @@ async fn main() -> Result<()> {
 
     // 开始性能计时
     let total_start = Instant::now();
+    memory_monitor.check("翻译前");
 
     // 执行翻译
-    match translate_source(&cli, &input_source, &output_path).await {
+    let result = translate_source(&cli, &input_source, &output_path).await;
+    memory_monitor.check("翻译后");
+
+    if let Some(path) = &cli.result_json {
+        let total_duration = total_start.elapsed();
+        let value = match &result {
+            Ok(stats) => file_result_json(&cli, stats, input, &output_path, total_duration),
+            Err(e) => failed_result_json("failed", input, &output_path, resolve_api_url(&cli), Some(&format!("{:#}", e)), total_duration),
+        };
+        write_result_json(path, &value)?;
+    }
+
+    if shutdown_requested() {
+        if let Err(e) = &result {
+            error!("❌ 翻译失败: {:#}", e);
+        }
+        warn!("🛑 任务已中断：已完成的译文已写出并保存到翻译缓存，重新运行将从缓存续跑");
+        return Ok(interrupted_exit_code());
+    }
+
+    match result {
+        Ok(stats) if stats.skipped => {
+            // 没有写出输出文件，不打印输出路径，也不参与成功率门控
+            if !cli.quiet {
+                info!("⏭️ 页面已跳过，未写出输出文件");
+            }
+        }
         Ok(stats) => {
             let total_duration = total_start.elapsed();
 
-            if !cli.quiet {
+            if cli.quiet {
+                print_output_path(&output_path);
+            } else {
                 info!("✅ 翻译完成！总耗时: {:.3}秒", total_duration.as_secs_f64());
             }
 
             // 显示性能统计
             if cli.stats || cli.verbose {
-                print_performance_stats(&stats, total_duration);
+                print_performance_stats(&stats, total_duration, cli.quiet);
+            }
+
+            // 成功率门控：结果已写出，但以非零退出码告知脚本调用方
+            if below_min_success_rate(&cli, stats.success_rate(), stats.texts_translated, stats.texts_collected) {
+                return Ok(ExitCode::from(2));
             }
 
             // 检查是否达到亚秒级性能目标
@@ -83,22 +211,274 @@ async fn main() -> Result<()> {
             }
         }
         Err(e) => {
-            error!("❌ 翻译失败: {}", e);
-            std::process::exit(1);
+            error!("❌ 翻译失败: {:#}", e);
+            if let Some(suggestion) = suggestion_for(&e) {
+                error!("💡 建议: {}", suggestion);
+            }
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 翻译成功率是否低于`--min-success-rate`，低于时输出错误；未设置该选项时始终为false
+fn below_min_success_rate(cli: &Cli, success_rate: f64, translated: usize, collected: usize) -> bool {
+    let Some(min_rate) = cli.min_success_rate else {
+        return false;
+    };
+    if success_rate >= min_rate {
+        return false;
+    }
+    eprintln!(
+        "❌ 翻译成功率 {:.1}% ({}/{}) 低于要求的 {:.1}%",
+        success_rate * 100.0,
+        translated,
+        collected,
+        min_rate * 100.0
+    );
+    true
+}
+
+/// 中断退出码；以返回值退出而不是`process::exit`，保证临时文件等资源的析构函数执行
+fn interrupted_exit_code() -> ExitCode {
+    ExitCode::from(INTERRUPTED_EXIT_CODE as u8)
+}
+
+/// 根据命令行参数构建输出目标：始终写本地文件，可选同时上传
+fn build_output_sink(cli: &Cli) -> MultiSink {
+    let sink = MultiSink::new().with_sink(FileSink {
+        bom: cli.bom,
+        backup: cli.backup,
+        encoding: cli.output_encoding,
+    });
+    match &cli.output_url {
+        Some(url) => sink.with_sink(HttpPutSink::new(url.clone(), cli.bom).with_encoding(cli.output_encoding)),
+        None => sink,
+    }
+}
+
+/// 校验翻译结果HTML，发现问题时逐条告警并返回错误
+fn validate_output_html(original_html: &str, translated_html: &str) -> Result<()> {
+    let report = validate_translated_html(original_html, translated_html);
+    if report.is_valid() {
+        info!("✅ 输出HTML校验通过（{} 个元素）", report.translated_elements);
+        return Ok(());
+    }
+
+    let issues = report.issues();
+    for issue in &issues {
+        warn!("⚠️ 输出HTML校验: {}", issue);
+    }
+    anyhow::bail!("输出HTML校验失败: 发现 {} 个问题", issues.len())
+}
+
+/// 按`--readability`剥离非正文内容，未找到正文时保留原页面
+fn apply_readability(cli: &Cli, html: String) -> Result<String> {
+    let extracted = match extract_readable_html(&html)? {
+        Some(extracted) => extracted,
+        None => {
+            warn!("⚠️ 未能识别正文，翻译完整页面");
+            return Ok(html);
         }
+    };
+
+    if cli.verbose {
+        info!("📰 正文提取完成: {} 字节 → {} 字节", html.len(), extracted.len());
+    }
+    if let Some(path) = &cli.readability_output {
+        write_output_file(path, &extracted, false)?;
+        info!("📄 正文已保存到: {}", path.display());
+    }
+    Ok(extracted)
+}
+
+/// 根据命令行参数确定实际使用的翻译API地址
+fn resolve_api_url(cli: &Cli) -> &str {
+    match cli.api_protocol {
+        // 未显式指定 --api 时使用Azure全局端点
+        ApiProtocol::Azure if cli.api == api_config::DEFAULT_API_URL => api_config::AZURE_API_URL,
+        _ => get_api_url(cli.local_api, Some(&cli.api)),
+    }
+}
+
+/// 根据命令行参数构建翻译配置
+fn build_translation_config(cli: &Cli, output_path: &std::path::Path) -> LocalTranslationConfig {
+    // 动态优化配置，使用API常量
+    let api_url = resolve_api_url(cli);
+    let batch_size = get_batch_size(cli.large_batch, Some(cli.batch_size));
+
+    LocalTranslationConfig::new()
+        .target_language(&cli.lang)
+        .with_api_url(api_url)
+        .with_api_protocol(cli.api_protocol)
+        .with_api_key(cli.api_key.clone())
+        .with_azure_region(cli.azure_region.clone())
+        .enable_cache(!cli.no_cache)
+        .with_batch_size(batch_size)
+        .with_max_retries(cli.max_retries)
+        .with_concurrent_batches(cli.concurrent_batches)
+        .enforce_consistency(cli.enforce_consistency)
+        .fail_fast(cli.fail_fast)
+        .single_retry_fallback(cli.single_retry_fallback)
+        .builtin_brands(!cli.no_builtin_brands)
+        .stream_large(cli.stream_large)
+        .with_max_translation_ratio(cli.max_translation_ratio)
+        .with_xhtml_output(cli.xhtml)
+        .fragment(cli.fragment)
+        .with_circuit_breaker(cli.circuit_breaker_threshold, cli.circuit_breaker_cooldown)
+        .with_shutdown_signal(SHUTDOWN.get().cloned())
+        .with_pii_redactor(build_pii_redactor(cli))
+        .balance_batches(cli.balance_batches)
+        .with_max_batch_chars(cli.max_batch_chars)
+        .preserve_formatting(cli.preserve_formatting)
+        .localize_dates(cli.localize_dates)
+        .translate_scripts(cli.translate_scripts)
+        .skip_numeric_cells(cli.skip_numeric_cells)
+        .with_request_template(cli.request_template.clone())
+        .with_response_path(cli.response_path.clone())
+        .with_context(cli.context.clone())
+        .with_dictionary_output(cli.export_dict.clone())
+        .with_review_output(cli.review_file.clone())
+        .with_review_csv(cli.review_csv.clone())
+        .with_translation_overrides(cli.import_overrides.clone())
+        .with_source_map_output(cli.source_map.clone())
+        .with_coverage_report(cli.coverage_report.clone())
+        .with_quality_report(cli.quality_report.clone())
+        .with_rate_control(cli.rate_control.clone())
+        .priority_first(cli.priority_first)
+        .skip_noscript(cli.skip_noscript)
+        .respect_lang(cli.respect_lang)
+        .text_only(cli.text_only)
+        .attributes_only(cli.attributes_only)
+        .with_json_attributes(cli.json_attributes.clone())
+        .with_title_translate_tags(cli.title_translate_tags.clone())
+        .with_json_fields(cli.json_fields.clone())
+        .with_preview_output(cli.priority_first.then(|| preview_output_path(output_path)))
+}
+
+/// 根据`--redact-pii`和`--redact-pattern`构建脱敏器
+fn build_pii_redactor(cli: &Cli) -> Option<PiiRedactor> {
+    cli.redact_pii.then(|| {
+        cli.redact_pattern
+            .iter()
+            .enumerate()
+            .fold(PiiRedactor::new(), |redactor, (index, pattern)| {
+                redactor.with_rule(&format!("custom{}", index + 1), pattern.clone())
+            })
+    })
+}
+
+/// 翻译开始前探测翻译API，失败时中止
+async fn run_health_check(config: &LocalTranslationConfig) -> Result<()> {
+    let probe_start = Instant::now();
+    let translation = check_translation_api(config).await?;
+    info!(
+        "🩺 翻译API健康检查通过: \"{}\" -> \"{}\" ({})",
+        HEALTH_CHECK_TEXT,
+        translation,
+        format_duration(probe_start.elapsed())
+    );
+    Ok(())
+}
+
+/// 翻译HTML并在详细模式下列出未得到译文的文本，返回翻译结果和成功率统计
+async fn translate_html(
+    cli: &Cli,
+    html_content: &str,
+    config: &LocalTranslationConfig,
+) -> Result<(String, TranslationReport)> {
+    let outcome = translate_with_outcome(html_content, config, cli.verbose).await?;
+    if cli.verbose && !outcome.failed_texts.is_empty() {
+        const MAX_LISTED: usize = 10;
+        warn!("⚠️ {} 条文本未得到译文:", outcome.failed_texts.len());
+        for text in outcome.failed_texts.iter().take(MAX_LISTED) {
+            warn!("   - {}", text.chars().take(80).collect::<String>());
+        }
+        if outcome.failed_texts.len() > MAX_LISTED {
+            warn!("   ... 其余 {} 条省略", outcome.failed_texts.len() - MAX_LISTED);
+        }
+    }
+    let report = outcome.report();
+    Ok((outcome.html, report))
+}
+
+/// 按`--sanitize-output`清洗翻译结果HTML
+fn sanitize_output_html(cli: &Cli, translated_html: String) -> Result<String> {
+    if !cli.sanitize_output {
+        return Ok(translated_html);
+    }
+
+    let (sanitized, report) = sanitize_html(&translated_html)?;
+    if !report.is_clean() {
+        info!(
+            "🧹 输出HTML已清洗: 移除 {} 个元素、{} 个属性",
+            report.removed_elements, report.removed_attributes
+        );
+    }
+    Ok(sanitized)
+}
+
+/// 写出前移除译文或DOM处理带入的非法控制字符，避免输出文件无法打开
+fn strip_invalid_output_chars(content: String) -> String {
+    let (cleaned, removed) = strip_invalid_html_chars(&content);
+    if removed == 0 {
+        return content;
+    }
+    warn!("⚠️ 输出中含有 {} 个非法控制字符，已移除", removed);
+    cleaned
+}
+
+/// 按`--output-encoding`更新HTML的charset声明和XML声明的encoding，并对无法表示的字符告警
+fn prepare_output_encoding(cli: &Cli, content: String, content_type: ContentType) -> String {
+    let encoding = match cli.output_encoding {
+        Some(encoding) => encoding,
+        None => return content,
+    };
+    let unmappable = count_unmappable_chars(&content, encoding);
+    if unmappable > 0 {
+        warn!("⚠️ 输出中有 {} 个字符无法用 {} 表示，已写为HTML数字实体", unmappable, encoding.name());
+    }
+    declare_output_encoding(&content, encoding.name(), content_type, cli.fragment)
+}
+
+/// 执行`cache`子命令
+fn run_cache_command(args: &CacheArgs) -> Result<()> {
+    let cache = TranslationCache::open_default()?;
+
+    if args.size {
+        let usage = cache.usage()?;
+        println!("📁 缓存目录: {}", cache.dir().display());
+        println!(
+            "💾 缓存占用: {} 个条目，{:.2} MB",
+            usage.entries,
+            usage.bytes as f64 / (1024.0 * 1024.0)
+        );
+    }
+
+    if args.prune {
+        if let Some(max_age) = args.older_than {
+            let removed = cache.prune_older_than(max_age)?;
+            println!("🧹 已删除 {} 个过期缓存条目", removed);
+        }
+    }
+
+    if args.clear {
+        let removed = cache.clear()?;
+        println!("🗑️ 已清空缓存: 删除 {} 个条目", removed);
     }
 
     Ok(())
 }
 
 /// 根据输入源类型分发翻译任务的主路由函数
-async fn translate_source(cli: &Cli, input_source: &InputSource, output_path: &std::path::PathBuf) -> Result<TranslationStats> {
+async fn translate_source(cli: &Cli, input_source: &InputSource, output_path: &std::path::Path) -> Result<TranslationStats> {
     match input_source {
         InputSource::File(file_path) => {
             if !cli.quiet {
                 info!("📁 开始文件翻译模式");
             }
-            translate_from_file(cli, file_path, output_path).await
+            translate_from_file(cli, file_path, output_path, None).await
         },
         InputSource::Url(url) => {
             if !cli.quiet {
@@ -108,21 +488,303 @@ async fn translate_source(cli: &Cli, input_source: &InputSource, output_path: &s
         }
     }
 }
+/// 批量翻译目录中的文件，单个文件失败或超时不影响其余文件
+async fn translate_directory(cli: &Cli, dir: &std::path::Path, memory_monitor: MemoryMonitor) -> Result<BatchSummary> {
+    let mut files = collect_batch_input_files(dir, &cli.lang)?;
+    if let Some(sample) = cli.sample {
+        let total = files.len();
+        files = sample_items(files, sample, cli.random);
+        info!(
+            "🧪 试跑采样: 从 {} 个文件中{}选取 {} 个",
+            total,
+            if cli.random { "随机" } else { "按顺序" },
+            files.len()
+        );
+    }
+    if !cli.quiet {
+        info!("📦 批量翻译目录: {}，共 {} 个文件", dir.display(), files.len());
+        if let Some(timeout) = cli.per_file_timeout {
+            info!("⏰ 单文件时限: {}", format_duration(timeout));
+        }
+    }
+
+    let batch_start = Instant::now();
+    let total_files = files.len();
+    let file_concurrency = batch_file_concurrency(cli);
+    if !cli.quiet && file_concurrency > 1 {
+        info!("🔀 文件间并发: 同时翻译 {} 个文件", file_concurrency);
+    }
+
+    // 输出路径按文件顺序预先分配，冲突时的改名结果与并发度无关
+    let output_paths = OutputPathRegistry::new();
+    let mut jobs = Vec::with_capacity(files.len());
+    for (index, file) in files.into_iter().enumerate() {
+        let planned_path = batch_output_path(dir, &file, &cli.output_dir, &cli.lang);
+        let output_path = output_paths.claim(&planned_path);
+        if output_path != planned_path {
+            warn!("⚠️ 输出路径冲突: {} 已被其它输入使用，改为 {}", planned_path.display(), output_path.display());
+        }
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("创建输出目录失败: {}", parent.display()))?;
+        }
+        jobs.push((index, file, output_path));
+    }
+
+    let summary = std::sync::Mutex::new(BatchSummary::default());
+    // 各页面共享同一个内存译文字典，导航、页脚等重复文本只翻译一次
+    let shared_translations = Arc::new(SharedTranslations::new());
+    let mut outcomes = futures::stream::iter(jobs)
+        .map(|(index, file, output_path)| {
+            let summary = &summary;
+            let shared_translations = &shared_translations;
+            async move {
+                let input = file.display().to_string();
+                // 收到中断信号后不再开始新文件，剩余文件记为未处理
+                if shutdown_requested() {
+                    return (file, output_path, input, BatchFileOutcome::Interrupted(None), std::time::Duration::ZERO);
+                }
+                if !cli.quiet {
+                    info!("📄 [{}] {} → {}", index + 1, file.display(), output_path.display());
+                }
+
+                write_batch_progress(cli, &summary.lock().unwrap(), total_files, Some(&file), batch_start);
+                let file_start = Instant::now();
+                memory_monitor.check(&format!("翻译 {} 前", file.display()));
+                let task = translate_from_file(cli, &file, &output_path, Some(shared_translations));
+                let outcome = match cli.per_file_timeout {
+                    Some(timeout) => match tokio::time::timeout(timeout, task).await {
+                        Ok(result) => BatchFileOutcome::Finished(result),
+                        Err(_) => BatchFileOutcome::TimedOut,
+                    },
+                    None => BatchFileOutcome::Finished(task.await),
+                };
+                memory_monitor.check(&format!("翻译 {} 后", file.display()));
+                // 中断时在途文件不再发起新批次，未翻译完的部分译文不算成功
+                let outcome = match outcome {
+                    BatchFileOutcome::Finished(Ok(stats)) if shutdown_requested() && stats.is_incomplete() => {
+                        BatchFileOutcome::Interrupted(Some(stats))
+                    }
+                    outcome => outcome,
+                };
+                (file, output_path, input, outcome, file_start.elapsed())
+            }
+        })
+        .buffered(file_concurrency);
+
+    // buffered按输入顺序产出结果，结果JSON与文件顺序一致
+    let mut results = Vec::new();
+    while let Some((file, output_path, input, outcome, elapsed)) = outcomes.next().await {
+        let mut summary = summary.lock().unwrap();
+        match outcome {
+            BatchFileOutcome::Finished(Ok(stats)) => {
+                if cli.quiet {
+                    print_output_path(&output_path);
+                }
+                results.push(file_result_json(cli, &stats, &input, &output_path, elapsed));
+                summary.record_success(file, &stats);
+            }
+            BatchFileOutcome::Finished(Err(e)) => {
+                error!("❌ 翻译失败: {}: {:#}", file.display(), e);
+                let reason = format!("{:#}", e);
+                results.push(failed_result_json("failed", &input, &output_path, resolve_api_url(cli), Some(&reason), elapsed));
+                summary.failed.push((file, reason));
+            }
+            BatchFileOutcome::TimedOut => {
+                warn!("⏰ 翻译超时，已跳过: {}", file.display());
+                results.push(failed_result_json("timed_out", &input, &output_path, resolve_api_url(cli), None, elapsed));
+                summary.timed_out.push(file);
+            }
+            BatchFileOutcome::Interrupted(partial) => {
+                results.push(match partial {
+                    Some(stats) => {
+                        warn!("🛑 中断时未翻译完，已写出部分译文: {}", output_path.display());
+                        file_result_json(cli, &stats, &input, &output_path, elapsed)
+                    }
+                    None => failed_result_json("interrupted", &input, &output_path, resolve_api_url(cli), None, elapsed),
+                });
+                summary.interrupted.push(file);
+            }
+        }
+    }
+    drop(outcomes);
+    let summary = summary.into_inner().unwrap();
+    if !summary.interrupted.is_empty() {
+        warn!("🛑 收到中断信号，{} 个文件未开始或未翻译完", summary.interrupted.len());
+    }
+
+    write_batch_progress(cli, &summary, total_files, None, batch_start);
+    if !cli.quiet && !shared_translations.is_empty() {
+        info!("♻️ 批量运行内共享译文 {} 条，跨页面重复文本已复用", shared_translations.len());
+    }
+    if !cli.quiet {
+        print_batch_summary(&summary, batch_start.elapsed());
+    }
+    if let Some(path) = &cli.result_json {
+        write_result_json(path, &serde_json::Value::Array(results))?;
+    }
+    Ok(summary)
+}
+
+/// `--watch`：先完整翻译一次，之后输入变化时只重新翻译变化的文件，直到收到中断信号
+///
+/// 单次翻译失败只记录错误，不退出监听。
+async fn run_watch_mode(
+    cli: &Cli,
+    input_source: &InputSource,
+    output_path: &std::path::Path,
+    memory_monitor: MemoryMonitor,
+) -> Result<()> {
+    let InputSource::File(input) = input_source else {
+        anyhow::bail!("--watch 只支持本地文件或目录输入");
+    };
+    let is_directory = input.is_dir();
+    // 先开始监听，首次翻译期间的修改也不会遗漏
+    let mut watcher = ChangeWatcher::watch(input)?;
+
+    if is_directory {
+        if let Err(e) = translate_directory(cli, input, memory_monitor).await {
+            error!("❌ 批量翻译失败: {:#}", e);
+        }
+    } else {
+        translate_watched_file(cli, input, output_path, memory_monitor).await;
+    }
+
+    // 输出目录位于输入目录内时，其中的文件是译文而非输入
+    let output_dir = cli.output_dir.as_ref().and_then(|dir| dir.canonicalize().ok());
+    if !cli.quiet {
+        info!("👀 正在监听 {} 的变化，Ctrl-C 退出", input.display());
+    }
+    while let Some(changes) = watcher.next_changes(WATCH_DEBOUNCE, shutdown_requested).await {
+        for file in changes {
+            let is_input = if is_directory {
+                is_batch_input_file(&file, &cli.lang)
+                    && !output_dir.as_ref().is_some_and(|dir| file.starts_with(dir))
+            } else {
+                file.file_name() == input.file_name()
+            };
+            if !is_input || !file.is_file() {
+                continue;
+            }
+
+            if is_directory {
+                let output_path = batch_output_path(input, &file, &cli.output_dir, &cli.lang);
+                if let Some(parent) = output_path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .with_context(|| format!("创建输出目录失败: {}", parent.display()))?;
+                }
+                translate_watched_file(cli, &file, &output_path, memory_monitor).await;
+            } else {
+                translate_watched_file(cli, input, output_path, memory_monitor).await;
+            }
+        }
+    }
+
+    if !cli.quiet {
+        info!("👋 已停止监听");
+    }
+    Ok(())
+}
+
+/// 监听模式下翻译单个文件，失败只记录错误
+async fn translate_watched_file(
+    cli: &Cli,
+    file: &std::path::PathBuf,
+    output_path: &std::path::Path,
+    memory_monitor: MemoryMonitor,
+) {
+    if !cli.quiet {
+        info!("🔄 翻译: {} → {}", file.display(), output_path.display());
+    }
+    let start = Instant::now();
+    memory_monitor.check(&format!("翻译 {} 前", file.display()));
+    let result = translate_from_file(cli, file, output_path, None).await;
+    memory_monitor.check(&format!("翻译 {} 后", file.display()));
+    match result {
+        Ok(_) if cli.quiet => print_output_path(output_path),
+        Ok(_) => info!("✅ 已更新 {}，耗时 {}", output_path.display(), format_duration(start.elapsed())),
+        Err(e) => error!("❌ 翻译失败: {}: {:#}", file.display(), e),
+    }
+}
+
+/// 静默模式下把生成的文件路径作为纯文本写到stdout，供脚本捕获
+fn print_output_path(path: &std::path::Path) {
+    println!("{}", path.display());
+}
+
+/// 批量模式下单个文件的处理结果
+enum BatchFileOutcome {
+    /// 翻译完成（成功或失败）
+    Finished(Result<TranslationStats>),
+    /// 超过单文件时限
+    TimedOut,
+    /// 收到中断信号：未开始翻译为None，翻译到一半时带部分结果的统计
+    Interrupted(Option<TranslationStats>),
+}
+
+/// 生成单个文件的结果摘要；收到中断信号时未翻译完的文件状态记为`interrupted`
+fn file_result_json(
+    cli: &Cli,
+    stats: &TranslationStats,
+    input: &str,
+    output_path: &std::path::Path,
+    elapsed: std::time::Duration,
+) -> serde_json::Value {
+    let mut value = stats.to_result_json(input, output_path, resolve_api_url(cli), elapsed);
+    if shutdown_requested() && stats.is_incomplete() {
+        value["status"] = serde_json::json!("interrupted");
+    }
+    value
+}
+
+/// 批量模式的文件间并发度：`--sequential`时强制为1
+fn batch_file_concurrency(cli: &Cli) -> usize {
+    if cli.sequential {
+        1
+    } else {
+        cli.batch_concurrency.max(1)
+    }
+}
+
+/// 指定了`--progress-file`时原子写入当前批量进度，写入失败只告警不中断任务
+fn write_batch_progress(
+    cli: &Cli,
+    summary: &BatchSummary,
+    total: usize,
+    current_file: Option<&std::path::Path>,
+    batch_start: Instant,
+) {
+    let Some(path) = &cli.progress_file else {
+        return;
+    };
+    let failed = summary.failed.len() + summary.timed_out.len();
+    let mut progress = batch_progress_json(
+        summary.succeeded.len() + failed,
+        failed,
+        total,
+        current_file,
+        batch_start.elapsed(),
+    );
+    if shutdown_requested() {
+        progress["status"] = serde_json::json!("interrupted");
+        progress["eta_seconds"] = serde_json::Value::Null;
+    }
+    let content = serde_json::to_string_pretty(&progress).unwrap_or_default() + "\n";
+    if let Err(e) = write_output_bytes(path, content.as_bytes()) {
+        warn!("⚠️ 写入进度文件失败: {:#}", e);
+    }
+}
+
 /// 处理本地文件翻译的核心函数
-async fn translate_from_file(cli: &Cli, file_path: &std::path::PathBuf, output_path: &std::path::PathBuf) -> Result<TranslationStats> {
+async fn translate_from_file(
+    cli: &Cli,
+    file_path: &std::path::PathBuf,
+    output_path: &std::path::Path,
+    shared_translations: Option<&Arc<SharedTranslations>>,
+) -> Result<TranslationStats> {
     let config_start = Instant::now();
 
-    // 动态优化配置，使用API常量
-    let api_url = get_api_url(cli.local_api, Some(&cli.api));
-    let batch_size = get_batch_size(cli.large_batch, Some(cli.batch_size));
-
-    // 创建本地配置（替代TranslationConfig）
-    let _config = LocalTranslationConfig::new()
-        .target_language(&cli.lang)
-        .with_api_url(api_url)
-        .enable_cache(!cli.no_cache)
-        .with_batch_size(batch_size)
-        .with_max_retries(cli.max_retries);
+    let config = build_translation_config(cli, output_path).with_shared_translations(shared_translations.cloned());
 
     let config_duration = config_start.elapsed();
 
@@ -130,6 +792,7 @@ async fn translate_from_file(cli: &Cli, file_path: &std::path::PathBuf, output_p
     let read_start = Instant::now();
     let html_content = std::fs::read_to_string(file_path)
         .with_context(|| format!("读取文件失败: {}", file_path.display()))?;
+    let html_content = strip_utf8_bom(&html_content).to_string();
     let read_duration = read_start.elapsed();
 
     if cli.verbose {
@@ -140,10 +803,57 @@ async fn translate_from_file(cli: &Cli, file_path: &std::path::PathBuf, output_p
         info!("🔀 并发批次数量: {}", cli.concurrent_batches);
     }
 
-    // 使用内置高性能索引翻译（完全独立实现）
+    // 按探测到的内容类型分派翻译管线，Markdown和RSS/Atom feed走专用流程
     let translate_start = Instant::now();
-    let translated_content = translate_with_indexed_mode(&html_content, api_url, cli.concurrent_batches, cli.verbose)
-        .await?;
+    let content_type = detect_content_type(Some(file_path), html_content.as_bytes());
+    let html_content = if cli.readability && matches!(content_type, ContentType::Html | ContentType::Unknown) {
+        apply_readability(cli, html_content)?
+    } else {
+        html_content
+    };
+    let (translated_content, report) = match content_type {
+        ContentType::Markdown => {
+            if cli.verbose {
+                info!("📝 检测到Markdown文件，使用Markdown翻译模式");
+            }
+            translate_markdown_with_report(&html_content, &config, cli.verbose).await?
+        }
+        ContentType::Feed => {
+            if cli.verbose {
+                info!("📰 检测到RSS/Atom feed，使用feed翻译模式");
+            }
+            translate_feed_with_report(&html_content, &config, cli.verbose).await?
+        }
+        ContentType::Xml => {
+            warn!("⚠️ 检测到非feed的XML文档，按HTML处理，结果可能不理想");
+            translate_html(cli, &html_content, &config).await?
+        }
+        ContentType::Html | ContentType::Unknown => {
+            translate_html(cli, &html_content, &config).await?
+        }
+    };
+
+    // 可选：内联字体，相对地址基于输入文件所在目录解析
+    let translated_content = if cli.embed_fonts {
+        let base_url = url::Url::from_file_path(file_path).ok();
+        embed_fonts_in_html_with_concurrency(&translated_content, base_url.as_ref(), cli.max_resource_concurrency).await?
+    } else {
+        translated_content
+    };
+
+    // 可选：校验HTML输出（Markdown和feed不适用）
+    if cli.validate_output && !matches!(content_type, ContentType::Markdown | ContentType::Feed) {
+        validate_output_html(&html_content, &translated_content)?;
+    }
+
+    // 可选：清洗HTML输出（Markdown和feed不适用）
+    let translated_content = if matches!(content_type, ContentType::Markdown | ContentType::Feed) {
+        translated_content
+    } else {
+        sanitize_output_html(cli, translated_content)?
+    };
+    let translated_content = strip_invalid_output_chars(translated_content);
+    let translated_content = prepare_output_encoding(cli, translated_content, content_type);
     let translate_duration = translate_start.elapsed();
 
     if cli.verbose {
@@ -153,8 +863,9 @@ async fn translate_from_file(cli: &Cli, file_path: &std::path::PathBuf, output_p
 
     // 创建本地统计信息
     let local_stats = LocalTranslationStats {
-        texts_collected: 0, // 这些统计信息在索引翻译模式中不直接适用
-        texts_filtered: 0,
+        texts_collected: report.total,
+        texts_translated: report.translated,
+        texts_filtered: 0, // 这些统计信息在索引翻译模式中不直接适用
         cache_hits: 0,
         cache_misses: 0,
         batches_created: cli.concurrent_batches,
@@ -162,13 +873,13 @@ async fn translate_from_file(cli: &Cli, file_path: &std::path::PathBuf, output_p
 
     // 写入文件
     let write_start = Instant::now();
-    std::fs::write(output_path, &translated_content)
-        .with_context(|| format!("写入文件失败: {}", output_path.display()))?;
+    let sink = build_output_sink(cli);
+    sink.write(output_path, &translated_content).await?;
     let write_duration = write_start.elapsed();
 
     if cli.verbose {
         info!("💾 文件写入完成，耗时: {:.3}秒", write_duration.as_secs_f64());
-        info!("✅ 翻译文件已保存: {}", output_path.display());
+        info!("✅ 翻译文件已保存: {}", sink.describe(output_path));
     }
 
     Ok(TranslationStats {
@@ -180,6 +891,7 @@ async fn translate_from_file(cli: &Cli, file_path: &std::path::PathBuf, output_p
         input_size: html_content.len(),
         output_size: translated_content.len(),
         texts_collected: local_stats.texts_collected,
+        texts_translated: local_stats.texts_translated,
         texts_filtered: local_stats.texts_filtered,
         cache_hits: local_stats.cache_hits,
         cache_misses: local_stats.cache_misses,
@@ -189,25 +901,31 @@ async fn translate_from_file(cli: &Cli, file_path: &std::path::PathBuf, output_p
         crawl_retries: 0,
         temp_file_size: 0,
         final_url: None,
+        skipped: false,
     })
 }
 
+/// 输出页面中内联资源（data URI）的类型分布，用于诊断页面资源缺失
+fn log_inline_resources(html: &str, html_only: bool) {
+    let inventory = inline_resource_inventory(html);
+    let origin = if html_only { "页面自带" } else { "Monolith内联" };
+    info!(
+        "📦 {}的data URI资源: {} 个，约 {} 字节",
+        origin,
+        inventory.total_count(),
+        inventory.total_bytes()
+    );
+    for (media_type, stats) in &inventory.by_type {
+        info!("   {}: {} 个，约 {} 字节", media_type, stats.count, stats.bytes);
+    }
+}
+
 /// 处理URL翻译的主流程函数
 /// 集成WebCrawler、TempManager和翻译引擎的完整流程
-async fn translate_from_url(cli: &Cli, url: &url::Url, output_path: &std::path::PathBuf) -> Result<TranslationStats> {
+async fn translate_from_url(cli: &Cli, url: &url::Url, output_path: &std::path::Path) -> Result<TranslationStats> {
     let config_start = Instant::now();
 
-    // 动态优化配置，使用API常量
-    let api_url = get_api_url(cli.local_api, Some(&cli.api));
-    let batch_size = get_batch_size(cli.large_batch, Some(cli.batch_size));
-
-    // 创建本地配置
-    let _config = LocalTranslationConfig::new()
-        .target_language(&cli.lang)
-        .with_api_url(api_url)
-        .enable_cache(!cli.no_cache)
-        .with_batch_size(batch_size)
-        .with_max_retries(cli.max_retries);
+    let config = build_translation_config(cli, output_path);
 
     let config_duration = config_start.elapsed();
 
@@ -226,12 +944,46 @@ async fn translate_from_url(cli: &Cli, url: &url::Url, output_path: &std::path::
     // 使用WebCrawler爬取网页
     let crawl_start = Instant::now();
     
-    let web_crawler = WebCrawler::with_url(url.as_str())
-        .include_resources(true, false, true) // 包含CSS和图片，不包含JS避免安全问题
-        .timeout(30);
+    let web_crawler = if cli.html_only {
+        // 只抓取HTML文档，可利用爬取缓存发送条件请求
+        let page_cache = if cli.no_cache { None } else { PageCache::open_default().ok() };
+        WebCrawler::with_url(url.as_str())
+            .include_resources(false, false, false)
+            .embed_fonts(false)
+            .page_cache(page_cache)
+    } else {
+        WebCrawler::with_url(url.as_str())
+            .include_resources(true, false, true) // 包含CSS和图片，不包含JS避免安全问题
+            .embed_fonts(true)
+    }
+    .timeout(30)
+    .max_resource_concurrency(cli.max_resource_concurrency)
+    .crawl_delay(std::time::Duration::from_millis(cli.crawl_delay));
 
     let (html_content, _temp_path) = web_crawler.crawl().await
         .with_context(|| format!("网页爬取失败: {}", url))?;
+    let html_content = strip_utf8_bom(&html_content).to_string();
+
+    if cli.verbose {
+        log_inline_resources(&html_content, cli.html_only);
+    }
+
+    // 尊重站长通过meta robots声明的noindex，不处理该页面
+    if cli.respect_robots && robots_noindex(&html_content) {
+        warn!("🤖 页面声明了 meta robots noindex，按 --respect-robots 跳过翻译: {}", url);
+        return Ok(TranslationStats {
+            crawl_time: crawl_start.elapsed(),
+            final_url: Some(url.to_string()),
+            skipped: true,
+            ..TranslationStats::default()
+        });
+    }
+
+    let html_content = if cli.readability {
+        apply_readability(cli, html_content)?
+    } else {
+        html_content
+    };
     
     let crawl_duration = crawl_start.elapsed();
 
@@ -243,7 +995,8 @@ async fn translate_from_url(cli: &Cli, url: &url::Url, output_path: &std::path::
     }
 
     // 创建临时HTML文件用于翻译处理
-    let temp_html_path = temp_manager.create_temp_html_from_crawl(&html_content, url.as_str())
+    let temp_html_path = temp_manager.create_temp_html_from_crawl_async(&html_content, url.as_str())
+        .await
         .with_context(|| "创建临时HTML文件失败")?;
 
     if cli.verbose {
@@ -252,9 +1005,33 @@ async fn translate_from_url(cli: &Cli, url: &url::Url, output_path: &std::path::
 
     // 使用内置高性能索引翻译
     let translate_start = Instant::now();
-    let translated_content = translate_with_indexed_mode(&html_content, api_url, cli.concurrent_batches, cli.verbose)
+    let (translated_content, report) = translate_html(cli, &html_content, &config)
         .await
         .with_context(|| "翻译处理失败")?;
+
+    // 可选：内联Monolith未能内联的字体
+    let translated_content = if cli.embed_fonts {
+        let crawler_config = web_crawler.config();
+        embed_fonts_in_html_with_throttle(
+            &translated_content,
+            Some(url),
+            crawler_config.max_resource_concurrency,
+            crawler_config.host_throttle.as_deref(),
+        )
+        .await?
+    } else {
+        translated_content
+    };
+
+    // 可选：校验HTML输出
+    if cli.validate_output {
+        validate_output_html(&html_content, &translated_content)?;
+    }
+
+    // 可选：清洗HTML输出
+    let translated_content = sanitize_output_html(cli, translated_content)?;
+    let translated_content = strip_invalid_output_chars(translated_content);
+    let translated_content = prepare_output_encoding(cli, translated_content, ContentType::Html);
     let translate_duration = translate_start.elapsed();
 
     if cli.verbose {
@@ -264,8 +1041,9 @@ async fn translate_from_url(cli: &Cli, url: &url::Url, output_path: &std::path::
 
     // 创建本地统计信息
     let local_stats = LocalTranslationStats {
-        texts_collected: 0, // 这些统计信息在索引翻译模式中不直接适用
-        texts_filtered: 0,
+        texts_collected: report.total,
+        texts_translated: report.translated,
+        texts_filtered: 0, // 这些统计信息在索引翻译模式中不直接适用
         cache_hits: 0,
         cache_misses: 0,
         batches_created: cli.concurrent_batches,
@@ -273,13 +1051,13 @@ async fn translate_from_url(cli: &Cli, url: &url::Url, output_path: &std::path::
 
     // 写入最终文件
     let write_start = Instant::now();
-    std::fs::write(output_path, &translated_content)
-        .with_context(|| format!("写入输出文件失败: {}", output_path.display()))?;
+    let sink = build_output_sink(cli);
+    sink.write(output_path, &translated_content).await?;
     let write_duration = write_start.elapsed();
 
     if cli.verbose {
         info!("💾 文件写入完成，耗时: {:.3}秒", write_duration.as_secs_f64());
-        info!("✅ 翻译文件已保存: {}", output_path.display());
+        info!("✅ 翻译文件已保存: {}", sink.describe(output_path));
     }
 
     // 临时文件会在TempManager被drop时自动清理
@@ -296,6 +1074,7 @@ async fn translate_from_url(cli: &Cli, url: &url::Url, output_path: &std::path::
         input_size: html_content.len(),
         output_size: translated_content.len(),
         texts_collected: local_stats.texts_collected,
+        texts_translated: local_stats.texts_translated,
         texts_filtered: local_stats.texts_filtered,
         cache_hits: local_stats.cache_hits,
         cache_misses: local_stats.cache_misses,
@@ -305,5 +1084,6 @@ async fn translate_from_url(cli: &Cli, url: &url::Url, output_path: &std::path::
         crawl_retries: 0, // TODO: 从WebCrawler获取重试次数
         temp_file_size: html_content.len(),
         final_url: Some(url.to_string()),
+        skipped: false,
     })
 }
\ No newline at end of file
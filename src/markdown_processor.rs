@@ -0,0 +1,188 @@
+//! Markdown处理模块
+//!
+//! 解析Markdown文档，仅提取段落、标题、列表项、表格单元等文本节点进行翻译，
+//! 代码块、行内代码、链接URL、图片路径和HTML块保持不变，翻译后重新渲染为Markdown
+
+// 标准库导入
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+// 第三方crate导入
+use anyhow::Result;
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd, TextMergeStream};
+
+/// Markdown文件扩展名
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// 判断文件是否为Markdown文件
+pub fn is_markdown_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| MARKDOWN_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// 解析Markdown使用的扩展语法
+fn markdown_options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS
+}
+
+/// 解析Markdown为事件序列，合并相邻的文本事件
+fn parse_markdown_events(markdown: &str) -> Vec<Event<'_>> {
+    TextMergeStream::new(Parser::new_ext(markdown, markdown_options())).collect()
+}
+
+/// 从Markdown中提取可翻译文本
+///
+/// 代码块内的文本被跳过；行内代码和HTML以独立事件出现，不会被提取。
+pub fn collect_markdown_texts(markdown: &str) -> Vec<String> {
+    let mut texts = Vec::new();
+    let mut seen_texts = HashSet::new();
+    let mut code_block_depth = 0usize;
+
+    for event in parse_markdown_events(markdown) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => code_block_depth += 1,
+            Event::End(TagEnd::CodeBlock) => code_block_depth = code_block_depth.saturating_sub(1),
+            Event::Text(text) if code_block_depth == 0 => {
+                let trimmed = text.trim();
+                if is_plain_markdown_text(trimmed) && seen_texts.insert(trimmed.to_string()) {
+                    texts.push(trimmed.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    texts
+}
+
+/// 将翻译结果应用到Markdown并重新渲染
+pub fn apply_markdown_translations(
+    markdown: &str,
+    original_texts: &[String],
+    translations: &[String],
+) -> Result<String> {
+    let translation_map: HashMap<&str, &str> = original_texts
+        .iter()
+        .zip(translations.iter())
+        .filter(|(_, trans)| !trans.is_empty())
+        .map(|(orig, trans)| (orig.as_str(), trans.as_str()))
+        .collect();
+
+    let mut code_block_depth = 0usize;
+    let events: Vec<Event> = parse_markdown_events(markdown)
+        .into_iter()
+        .map(|event| match event {
+            Event::Start(Tag::CodeBlock(_)) => {
+                code_block_depth += 1;
+                event
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                code_block_depth = code_block_depth.saturating_sub(1);
+                event
+            }
+            Event::Text(text) if code_block_depth == 0 => {
+                match translation_map.get(text.trim()) {
+                    Some(translation) => {
+                        let leading = &text[..text.len() - text.trim_start().len()];
+                        let trailing = &text[text.trim_end().len()..];
+                        Event::Text(format!("{}{}{}", leading, translation, trailing).into())
+                    }
+                    None => Event::Text(text),
+                }
+            }
+            other => other,
+        })
+        .collect();
+
+    let mut output = String::with_capacity(markdown.len());
+    pulldown_cmark_to_cmark::cmark(events.iter(), &mut output, markdown_options())
+        .map_err(|e| anyhow::anyhow!("Markdown渲染失败: {}", e))?;
+
+    if markdown.ends_with('\n') && !output.ends_with('\n') {
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// 判断Markdown纯文本是否值得翻译
+fn is_plain_markdown_text(text: &str) -> bool {
+    text.len() > 1
+        && !text
+            .chars()
+            .all(|c| c.is_whitespace() || c.is_ascii_punctuation() || c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKDOWN_SAMPLE: &str = r#"# Getting Started
+
+Install the tool and read the [user guide](https://example.com/guide).
+
+- First item
+- Second item
+
+| Name | Description |
+|------|-------------|
+| cli | Command line tool |
+
+![Logo image](images/logo.png)
+
+```rust
+let message = "Hello code";
+```
+
+<div>Raw html block</div>
+"#;
+
+    #[test]
+    fn test_is_markdown_path() {
+        assert!(is_markdown_path(Path::new("docs/README.md")));
+        assert!(is_markdown_path(Path::new("notes.Markdown")));
+        assert!(!is_markdown_path(Path::new("index.html")));
+    }
+
+    #[test]
+    fn test_collect_markdown_texts() {
+        let texts = collect_markdown_texts(MARKDOWN_SAMPLE);
+        for expected in [
+            "Getting Started",
+            "Install the tool and read the",
+            "user guide",
+            "First item",
+            "Command line tool",
+            "Logo image",
+        ] {
+            assert!(texts.contains(&expected.to_string()), "缺少文本: {}", expected);
+        }
+        assert!(!texts.iter().any(|t| t.contains("Hello code")));
+        assert!(!texts.iter().any(|t| t.contains("Raw html block")));
+        assert!(!texts.iter().any(|t| t.contains("example.com")));
+    }
+
+    #[test]
+    fn test_apply_markdown_translations_preserves_code_and_urls() {
+        let originals = vec![
+            "Getting Started".to_string(),
+            "user guide".to_string(),
+            "Command line tool".to_string(),
+        ];
+        let translations = vec![
+            "快速开始".to_string(),
+            "用户指南".to_string(),
+            "命令行工具".to_string(),
+        ];
+
+        let output = apply_markdown_translations(MARKDOWN_SAMPLE, &originals, &translations).unwrap();
+        assert!(output.contains("# 快速开始"));
+        assert!(output.contains("[用户指南](https://example.com/guide)"));
+        assert!(output.contains("命令行工具"));
+        assert!(output.contains("images/logo.png"));
+        assert!(output.contains(r#"let message = "Hello code";"#));
+        assert!(output.contains("<div>Raw html block</div>"));
+    }
+}
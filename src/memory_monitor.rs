@@ -0,0 +1,87 @@
+//! 内存监控模块
+//!
+//! 读取进程常驻内存（RSS），超过告警阈值时输出警告，
+//! 让用户在处理大批量内容、出现OOM之前得到预警
+
+// 第三方crate导入
+use tracing::{debug, warn};
+
+/// 进程内存监控器
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryMonitor {
+    /// 告警阈值（字节），为0时不告警
+    threshold_bytes: u64,
+}
+
+impl MemoryMonitor {
+    /// 使用指定的告警阈值（字节）创建监控器，阈值为0表示禁用告警
+    pub fn new(threshold_bytes: u64) -> Self {
+        Self { threshold_bytes }
+    }
+
+    /// 告警阈值（字节）
+    pub fn threshold_bytes(&self) -> u64 {
+        self.threshold_bytes
+    }
+
+    /// 检查当前内存占用，超过阈值时告警
+    ///
+    /// `stage`为检查点描述（如"翻译前"），返回当前RSS；平台不支持时返回`None`。
+    pub fn check(&self, stage: &str) -> Option<u64> {
+        let rss = current_rss_bytes()?;
+        debug!("🧠 内存占用（{}）: {:.1} MB", stage, bytes_to_mb(rss));
+
+        if self.threshold_bytes > 0 && rss > self.threshold_bytes {
+            warn!(
+                "⚠️  内存占用（{}）{:.1} MB 超过告警阈值 {:.1} MB，可减小 --batch-size/--concurrent-batches 或分批处理输入",
+                stage,
+                bytes_to_mb(rss),
+                bytes_to_mb(self.threshold_bytes)
+            );
+        }
+        Some(rss)
+    }
+}
+
+/// 当前进程的常驻内存（字节）
+///
+/// 通过`/proc/self/status`的`VmRSS`读取，非Linux平台返回`None`。
+pub fn current_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_rss(&status)
+}
+
+/// 从`/proc/<pid>/status`内容中解析`VmRSS`（单位kB）
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+/// 字节数转换为MB
+fn bytes_to_mb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\ttranslation-cli\nVmPeak:\t  20480 kB\nVmRSS:\t   10240 kB\nThreads:\t4\n";
+        assert_eq!(parse_vm_rss(status), Some(10240 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tfoo\n"), None);
+    }
+
+    #[test]
+    fn test_check_reports_rss_on_linux() {
+        let monitor = MemoryMonitor::new(0);
+        if cfg!(target_os = "linux") {
+            assert!(monitor.check("测试").unwrap() > 0);
+        }
+    }
+}
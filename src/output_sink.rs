@@ -0,0 +1,235 @@
+//! 输出目标模块
+//!
+//! 将翻译结果的写出抽象为[`OutputSink`]，默认写入本地文件，
+//! 也可通过HTTP PUT推送到对象存储（如S3预签名URL）或自定义接口
+
+// 标准库导入
+use std::path::Path;
+
+// 第三方crate导入
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use tracing::info;
+use url::Url;
+
+// 本地模块导入
+use crate::translator::shared_http_client;
+use crate::utils::{backup_existing_file, encode_output, write_output_bytes, write_output_file, UTF8_BOM};
+
+/// 翻译结果输出目标
+pub trait OutputSink: Send + Sync {
+    /// 写出内容
+    ///
+    /// `path_hint`为本地输出路径，不同实现可据此决定实际落点（如上传时的文件名）。
+    fn write<'a>(&'a self, path_hint: &'a Path, content: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    /// 输出目标描述，用于日志
+    fn describe(&self, path_hint: &Path) -> String;
+}
+
+/// 写入本地文件
+#[derive(Debug, Clone, Default)]
+pub struct FileSink {
+    /// 是否在文件开头写入UTF-8 BOM
+    pub bom: bool,
+    /// 覆盖前是否将已有文件重命名为备份
+    pub backup: bool,
+    /// 输出字符集，未设置时为UTF-8
+    pub encoding: Option<&'static encoding_rs::Encoding>,
+}
+
+impl OutputSink for FileSink {
+    fn write<'a>(&'a self, path_hint: &'a Path, content: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            if self.backup {
+                if let Some(backup_path) = backup_existing_file(path_hint)? {
+                    info!("🗂️ 已备份原输出文件: {}", backup_path.display());
+                }
+            }
+            match self.encoding {
+                Some(encoding) => write_output_bytes(path_hint, &encode_output(content, encoding)),
+                None => write_output_file(path_hint, content, self.bom),
+            }
+        })
+    }
+
+    fn describe(&self, path_hint: &Path) -> String {
+        path_hint.display().to_string()
+    }
+}
+
+/// 通过HTTP PUT上传
+///
+/// URL以`/`结尾时视为目录，上传地址为该目录下的输出文件名。
+#[derive(Debug, Clone)]
+pub struct HttpPutSink {
+    /// 上传地址
+    pub url: Url,
+    /// 是否在内容开头写入UTF-8 BOM
+    pub bom: bool,
+    /// 上传内容的字符集，未设置时为UTF-8
+    pub encoding: Option<&'static encoding_rs::Encoding>,
+}
+
+impl HttpPutSink {
+    /// 创建HTTP上传目标
+    pub fn new(url: Url, bom: bool) -> Self {
+        Self { url, bom, encoding: None }
+    }
+
+    /// 设置上传内容的字符集
+    pub fn with_encoding(mut self, encoding: Option<&'static encoding_rs::Encoding>) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// 计算实际上传地址
+    pub fn target_url(&self, path_hint: &Path) -> Result<Url> {
+        if !self.url.path().ends_with('/') {
+            return Ok(self.url.clone());
+        }
+
+        let file_name = path_hint
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow::anyhow!("无法从输出路径确定文件名: {}", path_hint.display()))?;
+        self.url
+            .join(file_name)
+            .with_context(|| format!("拼接上传地址失败: {}{}", self.url, file_name))
+    }
+}
+
+impl OutputSink for HttpPutSink {
+    fn write<'a>(&'a self, path_hint: &'a Path, content: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let target = self.target_url(path_hint)?;
+            let body = match self.encoding {
+                Some(encoding) => encode_output(content, encoding),
+                None if self.bom => format!("{}{}", UTF8_BOM, content).into_bytes(),
+                None => content.as_bytes().to_vec(),
+            };
+            let charset = self.encoding.map_or("utf-8", |encoding| encoding.name());
+
+            let response = shared_http_client()?
+                .put(target.as_str())
+                .header("Content-Type", format!("{}; charset={}", content_type_for(path_hint), charset))
+                .body(body)
+                .send()
+                .await
+                .with_context(|| format!("上传翻译结果失败: {}", target))?;
+
+            if !response.status().is_success() {
+                anyhow::bail!("上传翻译结果失败: {} 返回 {}", target, response.status());
+            }
+
+            info!("☁️ 翻译结果已上传: {}", target);
+            Ok(())
+        })
+    }
+
+    fn describe(&self, path_hint: &Path) -> String {
+        self.target_url(path_hint)
+            .map(|url| url.to_string())
+            .unwrap_or_else(|_| self.url.to_string())
+    }
+}
+
+/// 依次写入多个输出目标，任一失败即返回错误
+#[derive(Default)]
+pub struct MultiSink {
+    sinks: Vec<Box<dyn OutputSink>>,
+}
+
+impl MultiSink {
+    /// 创建空的组合输出目标
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 追加输出目标
+    pub fn with_sink<S: OutputSink + 'static>(mut self, sink: S) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+}
+
+impl OutputSink for MultiSink {
+    fn write<'a>(&'a self, path_hint: &'a Path, content: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            for sink in &self.sinks {
+                sink.write(path_hint, content).await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn describe(&self, path_hint: &Path) -> String {
+        self.sinks
+            .iter()
+            .map(|sink| sink.describe(path_hint))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// 根据输出文件扩展名推断上传时的媒体类型
+fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("md") | Some("markdown") => "text/markdown",
+        Some("xml") | Some("rss") | Some("atom") => "application/xml",
+        _ => "text/html",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_sink_target_url() {
+        let path = Path::new("/tmp/out/page_zh.html");
+
+        let directory = HttpPutSink::new(Url::parse("https://bucket.example.com/site/").unwrap(), false);
+        assert_eq!(
+            directory.target_url(path).unwrap().as_str(),
+            "https://bucket.example.com/site/page_zh.html"
+        );
+
+        let exact = HttpPutSink::new(Url::parse("https://api.example.com/upload?id=1").unwrap(), false);
+        assert_eq!(
+            exact.target_url(path).unwrap().as_str(),
+            "https://api.example.com/upload?id=1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_sink_writes_file_then_fails_on_unreachable_http() {
+        let dir = std::env::temp_dir().join("translation-cli-sink-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.html");
+
+        let sink = MultiSink::new()
+            .with_sink(FileSink::default())
+            .with_sink(HttpPutSink::new(Url::parse("http://127.0.0.1:9/upload/").unwrap(), false));
+
+        assert!(sink.write(&path, "<p>你好</p>").await.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "<p>你好</p>");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_writes_target_encoding() {
+        let dir = std::env::temp_dir().join("translation-cli-sink-encoding-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.html");
+
+        let sink = FileSink { encoding: Some(encoding_rs::SHIFT_JIS), ..FileSink::default() };
+        sink.write(&path, "<p>日本語</p>").await.unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(encoding_rs::SHIFT_JIS.decode(&bytes).0, "<p>日本語</p>");
+        assert!(std::str::from_utf8(&bytes).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+//! 网页爬取缓存模块
+//!
+//! 按URL保存爬取到的原始HTML及其`ETag`/`Last-Modified`校验信息，
+//! 重复爬取时发送条件请求，服务器返回304时直接复用缓存内容
+
+// 标准库导入
+use std::path::{Path, PathBuf};
+
+// 第三方crate导入
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::json;
+use tracing::debug;
+
+// 本地模块导入
+use crate::translation_cache::stable_hash;
+
+/// 缓存的网页
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedPage {
+    /// 原始响应内容
+    pub body: Vec<u8>,
+    /// 响应字符集
+    pub charset: Option<String>,
+    /// 重定向后的最终地址
+    pub final_url: String,
+    /// 响应的`ETag`
+    pub etag: Option<String>,
+    /// 响应的`Last-Modified`
+    pub last_modified: Option<String>,
+}
+
+impl CachedPage {
+    /// 是否带有可用于条件请求的校验信息
+    pub fn has_validators(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+/// 网页爬取缓存
+#[derive(Debug, Clone)]
+pub struct PageCache {
+    dir: PathBuf,
+}
+
+impl PageCache {
+    /// 使用指定目录创建缓存（目录在首次写入时创建）
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// 标准缓存目录，如Linux下的`~/.cache/translation-cli/pages`
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("translation-cli").join("pages"))
+    }
+
+    /// 打开位于标准缓存目录的缓存
+    pub fn open_default() -> Result<Self> {
+        Self::default_dir()
+            .map(Self::new)
+            .ok_or_else(|| anyhow::anyhow!("无法定位系统缓存目录"))
+    }
+
+    /// 缓存目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 查询URL对应的缓存网页
+    pub fn get(&self, url: &str) -> Option<CachedPage> {
+        let content = std::fs::read_to_string(self.entry_path(url)).ok()?;
+        let entry: serde_json::Value = serde_json::from_str(&content).ok()?;
+
+        if entry["url"].as_str() != Some(url) {
+            debug!("网页缓存键冲突，忽略条目: {}", url);
+            return None;
+        }
+
+        let optional = |key: &str| entry[key].as_str().map(str::to_string);
+        Some(CachedPage {
+            body: general_purpose::STANDARD
+                .decode(entry["body"].as_str()?)
+                .ok()?,
+            charset: optional("charset"),
+            final_url: optional("final_url").unwrap_or_else(|| url.to_string()),
+            etag: optional("etag"),
+            last_modified: optional("last_modified"),
+        })
+    }
+
+    /// 写入URL对应的网页
+    pub fn put(&self, url: &str, page: &CachedPage) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("创建缓存目录失败: {}", self.dir.display()))?;
+
+        let entry = json!({
+            "url": url,
+            "final_url": page.final_url,
+            "charset": page.charset,
+            "etag": page.etag,
+            "last_modified": page.last_modified,
+            "body": general_purpose::STANDARD.encode(&page.body),
+        });
+
+        // 先写临时文件再重命名，避免并发读取到半写入的条目
+        let path = self.entry_path(url);
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, entry.to_string())
+            .with_context(|| format!("写入网页缓存失败: {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, &path)
+            .with_context(|| format!("写入网页缓存失败: {}", path.display()))
+    }
+
+    /// 条目文件路径
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", stable_hash(url)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_and_get() {
+        let dir = std::env::temp_dir().join("translation-cli-page-cache-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = PageCache::new(&dir);
+        let url = "https://example.com/page";
+        assert_eq!(cache.get(url), None);
+
+        let page = CachedPage {
+            body: b"<html><body>Hello</body></html>".to_vec(),
+            charset: Some("UTF-8".to_string()),
+            final_url: "https://example.com/page/".to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        };
+        assert!(page.has_validators());
+        cache.put(url, &page).unwrap();
+        assert_eq!(cache.get(url), Some(page));
+        assert_eq!(cache.get("https://example.com/other"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
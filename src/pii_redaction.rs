@@ -0,0 +1,183 @@
+//! 敏感信息脱敏模块
+//!
+//! 页面中混入的邮箱、电话、身份证号、银行卡号等隐私信息不应发给第三方翻译API。
+//! 送翻译前按规则识别并替换为占位符，译文回来后还原原值；API也就无从改坏这些格式。
+//! 内置规则之外可追加自定义正则
+
+// 第三方crate导入
+use regex::Regex;
+
+/// 脱敏规则
+#[derive(Debug, Clone)]
+pub struct RedactionRule {
+    /// 规则名称，用于日志
+    pub name: String,
+    /// 匹配敏感信息的正则
+    pub pattern: Regex,
+}
+
+/// 内置规则：按顺序匹配，先匹配的规则优先（身份证号先于银行卡号，避免被截断）
+const BUILTIN_RULES: &[(&str, &str)] = &[
+    ("email", r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"),
+    ("id_card", r"\d{17}[\dXx]"),
+    ("card", r"\d{4}[ -]?\d{4}[ -]?\d{4}[ -]?\d{1,7}"),
+    ("phone", r"(?:\+\d{1,3}[ -]?)?\(?\d{2,4}\)?[ -]\d{3,4}[ -]\d{4}"),
+    ("mobile", r"1[3-9]\d{9}"),
+];
+
+/// 脱敏后的文本
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedactedText {
+    /// 替换为占位符后的文本
+    pub text: String,
+    /// 被替换的原值，下标即占位符编号
+    values: Vec<String>,
+}
+
+impl RedactedText {
+    /// 未做脱敏的文本
+    pub fn plain(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            values: Vec::new(),
+        }
+    }
+
+    /// 是否有被脱敏的内容
+    pub fn has_redactions(&self) -> bool {
+        !self.values.is_empty()
+    }
+}
+
+/// 敏感信息脱敏器
+#[derive(Debug, Clone)]
+pub struct PiiRedactor {
+    rules: Vec<RedactionRule>,
+}
+
+impl Default for PiiRedactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PiiRedactor {
+    /// 创建带内置规则（邮箱、身份证号、银行卡号、电话、手机号）的脱敏器
+    pub fn new() -> Self {
+        let rules = BUILTIN_RULES
+            .iter()
+            .map(|(name, pattern)| RedactionRule {
+                name: name.to_string(),
+                pattern: Regex::new(pattern).expect("内置脱敏规则无效"),
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// 追加自定义规则，在内置规则之后匹配
+    pub fn with_rule(mut self, name: &str, pattern: Regex) -> Self {
+        self.rules.push(RedactionRule {
+            name: name.to_string(),
+            pattern,
+        });
+        self
+    }
+
+    /// 当前生效的规则
+    pub fn rules(&self) -> &[RedactionRule] {
+        &self.rules
+    }
+
+    /// 将文本中的敏感信息替换为占位符
+    pub fn redact(&self, text: &str) -> RedactedText {
+        let mut values = Vec::new();
+        let mut redacted = text.to_string();
+        for rule in &self.rules {
+            if !rule.pattern.is_match(&redacted) {
+                continue;
+            }
+            redacted = rule
+                .pattern
+                .replace_all(&redacted, |captures: &regex::Captures| {
+                    values.push(captures[0].to_string());
+                    placeholder(values.len() - 1)
+                })
+                .into_owned();
+        }
+        RedactedText { text: redacted, values }
+    }
+}
+
+/// 生成第`index`个占位符
+fn placeholder(index: usize) -> String {
+    format!("{{{{P{}}}}}", index)
+}
+
+/// 将译文中的占位符还原为原值
+///
+/// 占位符允许被API加入空格（如`{{ P0 }}`）；完全丢失的原值追加到译文末尾，确保不丢信息。
+pub fn restore_redactions(translation: &str, redacted: &RedactedText) -> String {
+    if !redacted.has_redactions() || translation.is_empty() {
+        return translation.to_string();
+    }
+
+    let placeholder_regex = Regex::new(r"\{\{\s*P\s*(\d+)\s*\}\}").expect("占位符正则无效");
+    let mut restored_indices = vec![false; redacted.values.len()];
+    let mut restored = placeholder_regex
+        .replace_all(translation, |captures: &regex::Captures| {
+            captures[1]
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| {
+                    redacted.values.get(index).map(|value| {
+                        restored_indices[index] = true;
+                        value.clone()
+                    })
+                })
+                .unwrap_or_default()
+        })
+        .into_owned();
+
+    for (value, restored_flag) in redacted.values.iter().zip(restored_indices) {
+        if !restored_flag {
+            restored.push(' ');
+            restored.push_str(value);
+        }
+    }
+    restored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_and_phone_are_kept_verbatim() {
+        let redactor = PiiRedactor::new();
+        let redacted = redactor.redact("Email alice@example.com or call +1 555-123-4567 today");
+        assert_eq!(redacted.text, "Email {{P0}} or call {{P1}} today");
+        assert!(!redacted.text.contains("alice"));
+
+        let translation = "今天发邮件至 {{P0}} 或致电 {{ P1 }}";
+        assert_eq!(
+            restore_redactions(translation, &redacted),
+            "今天发邮件至 alice@example.com 或致电 +1 555-123-4567"
+        );
+    }
+
+    #[test]
+    fn test_chinese_numbers_and_lost_placeholders() {
+        let redactor = PiiRedactor::new();
+        let redacted = redactor.redact("手机13800138000，身份证11010519491231002X");
+        assert_eq!(redacted.text, "手机{{P1}}，身份证{{P0}}");
+        assert_eq!(restore_redactions("Mobile {{P1}}", &redacted), "Mobile 13800138000 11010519491231002X");
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        let redactor = PiiRedactor::new().with_rule("order", Regex::new(r"ORD-\d+").unwrap());
+        let redacted = redactor.redact("Order ORD-20240101 shipped");
+        assert_eq!(redacted.text, "Order {{P0}} shipped");
+        assert_eq!(redactor.rules().len(), BUILTIN_RULES.len() + 1);
+    }
+}
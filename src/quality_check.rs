@@ -0,0 +1,325 @@
+//! 译文质量自检模块
+//!
+//! 在译文后处理阶段做轻量的翻译完整性检查：统计译文中目标语言文字的占比，过低时
+//! 很可能根本没有翻译；目标为中文时还检查译文里是否残留大段连续的拉丁字母；
+//! 配置了长度约束时还标记超长译文。命中的条目标记为可疑并写入JSON质量报告，供人工复核
+
+// 标准库导入
+use std::path::Path;
+use std::sync::OnceLock;
+
+// 第三方crate导入
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::json;
+
+// 本地模块导入
+use crate::brand_protection::detect_brands;
+use crate::translator::translation_length_ratio;
+
+/// 译文中目标语言文字占比低于该值时标记为可疑
+pub const MIN_TARGET_SCRIPT_RATIO: f64 = 0.2;
+
+/// 译文字母数少于该值时不做占比检查（短词、缩写保留原文很常见）
+const MIN_CHECKED_LETTERS: usize = 8;
+
+/// 中文译文中连续拉丁单词达到该数量时标记为可疑
+const LATIN_RUN_MIN_WORDS: usize = 4;
+
+/// 目标语言使用的文字
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    /// 汉字
+    Han,
+    /// 日文：汉字与假名
+    Japanese,
+    /// 韩文：谚文，兼容汉字
+    Korean,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Thai,
+    Devanagari,
+}
+
+impl Script {
+    /// 按目标语言代码的主语言部分选择文字；拉丁字母语言无法与原文区分，返回None不检查
+    fn for_language(target_lang: &str) -> Option<Self> {
+        let primary = target_lang
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" => Some(Self::Han),
+            "ja" => Some(Self::Japanese),
+            "ko" => Some(Self::Korean),
+            "ru" | "uk" | "be" | "bg" | "sr" | "mk" | "kk" => Some(Self::Cyrillic),
+            "el" => Some(Self::Greek),
+            "ar" | "fa" | "ur" => Some(Self::Arabic),
+            "he" => Some(Self::Hebrew),
+            "th" => Some(Self::Thai),
+            "hi" | "mr" | "ne" => Some(Self::Devanagari),
+            _ => None,
+        }
+    }
+
+    /// 判断字符是否属于该文字
+    fn contains(self, character: char) -> bool {
+        let is_han = matches!(
+            character,
+            '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{f900}'..='\u{faff}' | '\u{20000}'..='\u{2ebef}'
+        );
+        match self {
+            Self::Han => is_han,
+            Self::Japanese => {
+                is_han || matches!(character, '\u{3040}'..='\u{30ff}' | '\u{31f0}'..='\u{31ff}' | '\u{ff66}'..='\u{ff9f}')
+            }
+            Self::Korean => {
+                is_han || matches!(character, '\u{1100}'..='\u{11ff}' | '\u{3130}'..='\u{318f}' | '\u{ac00}'..='\u{d7af}')
+            }
+            Self::Cyrillic => matches!(character, '\u{0400}'..='\u{052f}'),
+            Self::Greek => matches!(character, '\u{0370}'..='\u{03ff}' | '\u{1f00}'..='\u{1fff}'),
+            Self::Arabic => matches!(
+                character,
+                '\u{0600}'..='\u{06ff}' | '\u{0750}'..='\u{077f}' | '\u{fb50}'..='\u{fdff}' | '\u{fe70}'..='\u{feff}'
+            ),
+            Self::Hebrew => matches!(character, '\u{0590}'..='\u{05ff}'),
+            Self::Thai => matches!(character, '\u{0e00}'..='\u{0e7f}'),
+            Self::Devanagari => matches!(character, '\u{0900}'..='\u{097f}'),
+        }
+    }
+}
+
+/// 译文被标记为可疑的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuspicionReason {
+    /// 目标语言文字占比过低，可能根本没有翻译
+    LowTargetRatio { ratio: f64 },
+    /// 中文译文中残留大段连续拉丁字母
+    LatinRun { run: String },
+    /// 译文长度超过原文的`--max-translation-ratio`倍
+    Overlong { ratio: f64, max_ratio: f64 },
+}
+
+impl SuspicionReason {
+    /// 报告中使用的原因代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::LowTargetRatio { .. } => "low_target_ratio",
+            Self::LatinRun { .. } => "latin_run",
+            Self::Overlong { .. } => "overlong",
+        }
+    }
+}
+
+/// 一条可疑译文
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuspiciousTranslation {
+    /// 原文在文档中的出现次序（从0开始）
+    pub index: usize,
+    /// 原文文本
+    pub source: String,
+    /// 译文文本
+    pub translation: String,
+    /// 可疑原因
+    pub reason: SuspicionReason,
+}
+
+/// 匹配以空白或常见连接标点分隔的连续拉丁单词
+fn latin_run_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(&format!(
+            r"[A-Za-z]+(?:[\s,'’-]+[A-Za-z]+){{{},}}",
+            LATIN_RUN_MIN_WORDS - 1
+        ))
+        .expect("拉丁字母正则无效")
+    })
+}
+
+/// 计算译文中目标语言文字占全部字母的比例
+///
+/// 内置品牌词按惯例保留原文，不计入字母总数；字母过少或目标语言不支持检查时返回None。
+pub fn target_script_ratio(translation: &str, target_lang: &str) -> Option<f64> {
+    let script = Script::for_language(target_lang)?;
+    let lowered = translation.to_ascii_lowercase();
+    let brand_letters: usize = detect_brands(translation)
+        .into_iter()
+        .map(|brand| {
+            let brand = brand.to_ascii_lowercase();
+            lowered.matches(&brand).count() * brand.chars().filter(|c| c.is_alphabetic()).count()
+        })
+        .sum();
+
+    let letters = translation.chars().filter(|c| c.is_alphabetic()).count();
+    let letters = letters.saturating_sub(brand_letters);
+    if letters < MIN_CHECKED_LETTERS {
+        return None;
+    }
+    let target_letters = translation.chars().filter(|c| script.contains(*c)).count();
+    Some((target_letters as f64 / letters as f64).min(1.0))
+}
+
+/// 检查单条译文，返回可疑原因；空译文（翻译失败）不在此检查
+pub fn check_translation(translation: &str, target_lang: &str) -> Option<SuspicionReason> {
+    if translation.trim().is_empty() {
+        return None;
+    }
+    if let Some(ratio) = target_script_ratio(translation, target_lang) {
+        if ratio < MIN_TARGET_SCRIPT_RATIO {
+            return Some(SuspicionReason::LowTargetRatio { ratio });
+        }
+    }
+    if Script::for_language(target_lang) == Some(Script::Han) {
+        if let Some(run) = latin_run_regex().find(translation) {
+            return Some(SuspicionReason::LatinRun {
+                run: run.as_str().to_string(),
+            });
+        }
+    }
+    None
+}
+
+/// 按文档顺序检查全部译文，返回可疑条目
+///
+/// `max_length_ratio`为`Some`时，未命中其它检查但长度超过原文该倍数的译文标记为超长。
+pub fn find_suspicious_translations(
+    originals: &[String],
+    translations: &[String],
+    target_lang: &str,
+    max_length_ratio: Option<f64>,
+) -> Vec<SuspiciousTranslation> {
+    originals
+        .iter()
+        .zip(translations)
+        .enumerate()
+        .filter_map(|(index, (source, translation))| {
+            let overlong = || {
+                let max_ratio = max_length_ratio?;
+                let ratio = translation_length_ratio(source, translation).filter(|ratio| *ratio > max_ratio)?;
+                Some(SuspicionReason::Overlong { ratio, max_ratio })
+            };
+            check_translation(translation, target_lang).or_else(overlong).map(|reason| SuspiciousTranslation {
+                index,
+                source: source.clone(),
+                translation: translation.clone(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// 将检查结果渲染为JSON质量报告
+pub fn render_quality_report(target_lang: &str, checked: usize, suspicious: &[SuspiciousTranslation]) -> String {
+    let items: Vec<_> = suspicious
+        .iter()
+        .map(|item| {
+            let mut entry = json!({
+                "index": item.index,
+                "source": item.source,
+                "translation": item.translation,
+                "reason": item.reason.code(),
+            });
+            match &item.reason {
+                SuspicionReason::LowTargetRatio { ratio } => {
+                    entry["target_ratio"] = json!((ratio * 1000.0).round() / 1000.0);
+                }
+                SuspicionReason::LatinRun { run } => entry["latin_run"] = json!(run),
+                SuspicionReason::Overlong { ratio, max_ratio } => {
+                    entry["length_ratio"] = json!((ratio * 1000.0).round() / 1000.0);
+                    entry["max_length_ratio"] = json!(max_ratio);
+                }
+            }
+            entry
+        })
+        .collect();
+    serde_json::to_string_pretty(&json!({
+        "version": 1,
+        "target_lang": target_lang,
+        "checked": checked,
+        "suspicious_count": items.len(),
+        "suspicious": items,
+    }))
+    .unwrap_or_default()
+}
+
+/// 写出质量报告文件
+pub fn write_quality_report(
+    path: &Path,
+    target_lang: &str,
+    checked: usize,
+    suspicious: &[SuspiciousTranslation],
+) -> Result<()> {
+    std::fs::write(path, render_quality_report(target_lang, checked, suspicious))
+        .with_context(|| format!("写入质量报告失败: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_script_ratio() {
+        assert_eq!(target_script_ratio("这是一段正常的中文译文", "zh-CN"), Some(1.0));
+        assert_eq!(target_script_ratio("Welcome to our website", "zh"), Some(0.0));
+        // 字母过少、品牌词以及拉丁字母目标语言不参与检查
+        assert_eq!(target_script_ratio("OK", "zh"), None);
+        assert_eq!(target_script_ratio("在GitHub上", "zh"), None);
+        assert_eq!(target_script_ratio("Bienvenue sur notre site", "fr"), None);
+        assert!(target_script_ratio("ようこそ our site へ", "ja").unwrap() > 0.3);
+    }
+
+    #[test]
+    fn test_check_translation_flags_untranslated_text() {
+        assert_eq!(
+            check_translation("Welcome to our website", "zh"),
+            Some(SuspicionReason::LowTargetRatio { ratio: 0.0 })
+        );
+        assert_eq!(
+            check_translation("欢迎访问我们的网站，请阅读 the terms of service and privacy policy 后继续使用", "zh"),
+            Some(SuspicionReason::LatinRun {
+                run: "the terms of service and privacy policy".to_string()
+            })
+        );
+        assert_eq!(check_translation("使用 GitHub Actions 部署应用", "zh"), None);
+        assert_eq!(check_translation("", "zh"), None);
+        // 只有中文目标检查连续拉丁字母
+        assert_eq!(check_translation("Привет, read the terms of service первым делом сейчас", "ru"), None);
+    }
+
+    #[test]
+    fn test_quality_report_lists_suspicious_items() {
+        let originals = vec!["Hello world".to_string(), "Read the full terms and conditions here".to_string()];
+        let translations = vec!["你好，世界".to_string(), "Read the full terms and conditions here".to_string()];
+        let suspicious = find_suspicious_translations(&originals, &translations, "zh", None);
+        assert_eq!(suspicious.len(), 1);
+        assert_eq!(suspicious[0].index, 1);
+
+        let report: serde_json::Value =
+            serde_json::from_str(&render_quality_report("zh", originals.len(), &suspicious)).unwrap();
+        assert_eq!(report["checked"], 2);
+        assert_eq!(report["suspicious_count"], 1);
+        assert_eq!(report["suspicious"][0]["reason"], "low_target_ratio");
+        assert_eq!(report["suspicious"][0]["target_ratio"], 0.0);
+    }
+
+    #[test]
+    fn test_quality_report_flags_overlong_translations() {
+        let originals = vec!["Save".to_string(), "Cancel".to_string()];
+        let translations = vec!["保存您刚才所做的全部更改".to_string(), "取消".to_string()];
+        assert!(find_suspicious_translations(&originals, &translations, "fr", None).is_empty());
+
+        let suspicious = find_suspicious_translations(&originals, &translations, "fr", Some(1.5));
+        assert_eq!(suspicious.len(), 1);
+        assert_eq!(suspicious[0].index, 0);
+        assert_eq!(suspicious[0].reason, SuspicionReason::Overlong { ratio: 3.0, max_ratio: 1.5 });
+
+        let report: serde_json::Value =
+            serde_json::from_str(&render_quality_report("fr", originals.len(), &suspicious)).unwrap();
+        assert_eq!(report["suspicious"][0]["reason"], "overlong");
+        assert_eq!(report["suspicious"][0]["length_ratio"], 3.0);
+        assert_eq!(report["suspicious"][0]["max_length_ratio"], 1.5);
+    }
+}
@@ -0,0 +1,271 @@
+//! 请求速率限制模块
+//!
+//! 按控制文件中的每秒请求数为翻译API请求分配发送时刻，运行中定期重读控制文件，
+//! 无需重启任务即可在高峰期降速、闲时提速；另提供按主机维度的礼貌爬取延迟
+
+// 标准库导入
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// 第三方crate导入
+use tracing::{info, warn};
+
+/// 重读控制文件的默认间隔
+const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 由控制文件驱动的请求速率限制器
+///
+/// 控制文件内容为每秒请求数（可为小数，如`0.5`表示每两秒一个请求）。
+/// 文件暂时不可读或内容无效时沿用上一次的速率；从未读到有效速率时不限速。
+#[derive(Debug)]
+pub struct RateLimiter {
+    control_file: PathBuf,
+    reload_interval: Duration,
+    state: Mutex<RateState>,
+}
+
+/// 速率限制器的可变状态
+#[derive(Debug)]
+struct RateState {
+    /// 当前每秒请求数，`None`表示不限速
+    rate: Option<f64>,
+    /// 下一个可用的发送时刻
+    next_slot: Instant,
+    /// 上次读取控制文件的时刻
+    last_reload: Option<Instant>,
+    /// 上次读到的文件内容，内容不变时不重复记录日志
+    last_content: Option<String>,
+}
+
+impl RateLimiter {
+    /// 创建读取指定控制文件的限速器
+    pub fn new(control_file: impl Into<PathBuf>) -> Self {
+        Self {
+            control_file: control_file.into(),
+            reload_interval: DEFAULT_RELOAD_INTERVAL,
+            state: Mutex::new(RateState {
+                rate: None,
+                next_slot: Instant::now(),
+                last_reload: None,
+                last_content: None,
+            }),
+        }
+    }
+
+    /// 设置重读控制文件的间隔
+    pub fn with_reload_interval(mut self, interval: Duration) -> Self {
+        self.reload_interval = interval;
+        self
+    }
+
+    /// 控制文件路径
+    pub fn control_file(&self) -> &Path {
+        &self.control_file
+    }
+
+    /// 当前生效的每秒请求数，必要时先重读控制文件
+    pub fn current_rate(&self) -> Option<f64> {
+        let mut state = self.state.lock().expect("速率状态锁被污染");
+        self.reload_if_due(&mut state);
+        state.rate
+    }
+
+    /// 等待到下一个可用的发送时刻
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut state = self.state.lock().expect("速率状态锁被污染");
+            self.reload_if_due(&mut state);
+            let rate = match state.rate {
+                Some(rate) => rate,
+                None => return,
+            };
+
+            let now = Instant::now();
+            let slot = state.next_slot.max(now);
+            state.next_slot = slot + Duration::from_secs_f64(1.0 / rate);
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// 距上次读取超过重读间隔时重新读取控制文件
+    fn reload_if_due(&self, state: &mut RateState) {
+        if state
+            .last_reload
+            .is_some_and(|last| last.elapsed() < self.reload_interval)
+        {
+            return;
+        }
+        state.last_reload = Some(Instant::now());
+
+        let content = match std::fs::read_to_string(&self.control_file) {
+            Ok(content) => content.trim().to_string(),
+            Err(e) => {
+                if state.last_content.take().is_some() || state.rate.is_none() {
+                    warn!("⚠️ 无法读取速率控制文件 {}: {}，沿用当前速率", self.control_file.display(), e);
+                }
+                return;
+            }
+        };
+        if state.last_content.as_deref() == Some(content.as_str()) {
+            return;
+        }
+
+        match parse_rate(&content) {
+            Ok(rate) => {
+                info!("🚦 请求速率调整为每秒 {} 个", rate);
+                state.rate = Some(rate);
+                // 新速率立即生效，不受旧速率排好的时刻约束
+                state.next_slot = state.next_slot.min(Instant::now() + Duration::from_secs_f64(1.0 / rate));
+            }
+            Err(e) => warn!("⚠️ 速率控制文件内容无效: {}，沿用当前速率", e),
+        }
+        state.last_content = Some(content);
+    }
+}
+
+/// 按主机维度的礼貌爬取延迟
+///
+/// 对同一主机的连续请求之间至少间隔`delay`，不同主机互不影响、可并行请求。
+#[derive(Debug)]
+pub struct HostThrottle {
+    delay: Duration,
+    /// 各主机下一个可用的请求时刻
+    next_slots: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostThrottle {
+    /// 创建同主机请求间隔为`delay`的节流器
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            next_slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 同主机请求间隔
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// 等待到`url`所在主机的下一个可用请求时刻；无法解析主机时不等待
+    pub async fn acquire(&self, url: &str) {
+        if self.delay.is_zero() {
+            return;
+        }
+        let host = match url::Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_lowercase)) {
+            Some(host) => host,
+            None => return,
+        };
+
+        let wait = {
+            let mut next_slots = self.next_slots.lock().expect("主机节流状态锁被污染");
+            let now = Instant::now();
+            let slot = next_slots.get(&host).map_or(now, |next| (*next).max(now));
+            next_slots.insert(host, slot + self.delay);
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// 每秒请求数下限（每小时一个），更小的值按此处理，避免请求间隔溢出`Duration`
+pub const MIN_RATE: f64 = 1.0 / 3600.0;
+
+/// 解析控制文件中的每秒请求数，低于[`MIN_RATE`]的正数按下限处理
+pub fn parse_rate(content: &str) -> std::result::Result<f64, String> {
+    let rate: f64 = content
+        .trim()
+        .parse()
+        .map_err(|_| format!("无效的每秒请求数: {:?}", content.trim()))?;
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err(format!("每秒请求数必须大于0: {}", rate));
+    }
+    Ok(rate.max(MIN_RATE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn control_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("translation-cli-rate-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("5\n"), Ok(5.0));
+        assert_eq!(parse_rate(" 0.5 "), Ok(0.5));
+        assert!(parse_rate("0").is_err());
+        assert!(parse_rate("fast").is_err());
+        // 极小的速率按下限处理，计算间隔时不会溢出
+        assert_eq!(parse_rate("1e-300"), Ok(MIN_RATE));
+        assert!(Duration::from_secs_f64(1.0 / parse_rate("1e-300").unwrap()) <= Duration::from_secs(3600));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_spaces_requests() {
+        let path = control_file("spacing");
+        std::fs::write(&path, "20").unwrap();
+        let limiter = RateLimiter::new(&path);
+
+        let start = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire().await;
+        }
+        // 每秒20个：第1个立即发送，其后每个间隔50ms
+        assert!(start.elapsed() >= Duration::from_millis(140));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_rate_reloads_from_control_file() {
+        let path = control_file("reload");
+        std::fs::write(&path, "2").unwrap();
+        let limiter = RateLimiter::new(&path).with_reload_interval(Duration::ZERO);
+        assert_eq!(limiter.current_rate(), Some(2.0));
+
+        std::fs::write(&path, "10").unwrap();
+        assert_eq!(limiter.current_rate(), Some(10.0));
+
+        // 无效内容和文件缺失都沿用上一次的速率
+        std::fs::write(&path, "oops").unwrap();
+        assert_eq!(limiter.current_rate(), Some(10.0));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(limiter.current_rate(), Some(10.0));
+    }
+
+    #[test]
+    fn test_missing_control_file_is_unlimited() {
+        let limiter = RateLimiter::new(control_file("missing"));
+        assert_eq!(limiter.current_rate(), None);
+    }
+
+    #[tokio::test]
+    async fn test_host_throttle_delays_same_host_only() {
+        let throttle = HostThrottle::new(Duration::from_millis(150));
+
+        let start = Instant::now();
+        throttle.acquire("https://example.com/a").await;
+        throttle.acquire("https://other.example.org/b").await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        // 同一主机（不区分大小写）的第二次请求需要等待
+        throttle.acquire("https://EXAMPLE.com/c").await;
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        // 无法解析主机或未设置延迟时不等待
+        let start = Instant::now();
+        throttle.acquire("not a url").await;
+        HostThrottle::new(Duration::ZERO).acquire("https://example.com").await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
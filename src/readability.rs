@@ -0,0 +1,335 @@
+//! 正文提取模块
+//!
+//! 剥离广告、追踪脚本等非正文内容，并以readability风格的打分找出文章主体，
+//! 用于提升新闻、博客类页面的翻译信噪比
+
+// 标准库导入
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// 第三方crate导入
+use anyhow::Result;
+use html5ever::serialize::{serialize, SerializeOpts, TraversalScope};
+use html5ever::tendril::TendrilSink;
+use html5ever::parse_document;
+use markup5ever_rcdom::{Handle, NodeData, RcDom, SerializableHandle};
+use regex::Regex;
+use url::Url;
+
+/// 常见广告和追踪服务域名
+const AD_TRACKER_DOMAINS: &[&str] = &[
+    "doubleclick.net",
+    "googlesyndication.com",
+    "googleadservices.com",
+    "googletagmanager.com",
+    "google-analytics.com",
+    "adservice.google.com",
+    "amazon-adsystem.com",
+    "adnxs.com",
+    "criteo.com",
+    "criteo.net",
+    "taboola.com",
+    "outbrain.com",
+    "scorecardresearch.com",
+    "quantserve.com",
+    "connect.facebook.net",
+    "hotjar.com",
+    "clarity.ms",
+    "hm.baidu.com",
+    "cnzz.com",
+    "umeng.com",
+];
+
+/// 内联追踪脚本的特征片段
+const TRACKER_SCRIPT_SIGNATURES: &[&str] = &[
+    "googletagmanager",
+    "google-analytics",
+    "adsbygoogle",
+    "gtag(",
+    "fbq(",
+    "_hmt",
+    "_paq",
+];
+
+/// 匹配广告容器class/id的模式
+const AD_CLASS_PATTERN: &str =
+    r"(?i)(^|[-_\s])(ads?|advert\w*|sponsor\w*|promo\w*|banner|doubleclick|outbrain|taboola)([-_\s]|$)";
+
+/// 正文中不需要保留的元素
+const NON_CONTENT_TAGS: &[&str] = &[
+    "script", "style", "noscript", "nav", "aside", "footer", "form", "iframe",
+];
+
+/// 计入正文打分的段落最小长度（字符）
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+/// 剥离广告和追踪内容，返回移除的元素数量
+///
+/// 移除来源为广告/追踪域名的`script`、`iframe`、`img`，带追踪特征的内联脚本，
+/// 以及class或id形如广告容器（`ad-banner`、`sponsored`等）的元素。
+pub fn strip_ads_and_trackers(dom: &RcDom) -> usize {
+    let ad_pattern = Regex::new(AD_CLASS_PATTERN).expect("广告模式正则无效");
+    remove_matching(&dom.document, &|node| is_ad_or_tracker(node, &ad_pattern))
+}
+
+/// 提取页面正文，生成只包含标题和正文的精简HTML文档
+///
+/// 先剥离广告和追踪内容，再优先选用`<article>`/`<main>`，否则按段落文本长度和
+/// 标点数量为各容器打分，取得分最高者。找不到正文时返回`None`。
+pub fn extract_readable_html(html: &str) -> Result<Option<String>> {
+    let dom = parse_document(RcDom::default(), Default::default()).one(html);
+    strip_ads_and_trackers(&dom);
+
+    let content = match find_main_content(&dom.document) {
+        Some(content) => content,
+        None => return Ok(None),
+    };
+    remove_matching(&content, &|node| {
+        element_name(node).is_some_and(|name| NON_CONTENT_TAGS.contains(&name))
+    });
+
+    let title = find_first_element(&dom.document, "title")
+        .map(|title| text_content(&title).trim().to_string())
+        .unwrap_or_default();
+
+    let mut buffer = Vec::new();
+    serialize(
+        &mut buffer,
+        &SerializableHandle::from(content),
+        SerializeOpts {
+            traversal_scope: TraversalScope::IncludeNode,
+            ..Default::default()
+        },
+    )
+    .map_err(|e| anyhow::anyhow!("正文序列化失败: {:?}", e))?;
+    let body = String::from_utf8(buffer).map_err(|e| anyhow::anyhow!("UTF-8转换失败: {}", e))?;
+
+    Ok(Some(format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        escape_text(&title),
+        body
+    )))
+}
+
+/// 判断元素是否为广告或追踪内容
+fn is_ad_or_tracker(node: &Handle, ad_pattern: &Regex) -> bool {
+    let (name, attrs) = match &node.data {
+        NodeData::Element { name, attrs, .. } => (name.local.as_ref(), attrs.borrow()),
+        _ => return false,
+    };
+
+    let attr = |key: &str| {
+        attrs
+            .iter()
+            .find(|a| a.name.local.as_ref() == key)
+            .map(|a| a.value.to_string())
+    };
+
+    if matches!(name, "script" | "iframe" | "img") {
+        if let Some(src) = attr("src") {
+            if is_ad_tracker_url(&src) {
+                return true;
+            }
+        }
+    }
+
+    if name == "script" {
+        let code = text_content(node);
+        if TRACKER_SCRIPT_SIGNATURES.iter().any(|sig| code.contains(sig)) {
+            return true;
+        }
+    }
+
+    // body/html不参与模式匹配，避免整页被误删
+    if matches!(name, "html" | "body") {
+        return false;
+    }
+    ["class", "id"]
+        .iter()
+        .filter_map(|key| attr(key))
+        .any(|value| ad_pattern.is_match(&value))
+}
+
+/// URL是否指向广告或追踪域名
+fn is_ad_tracker_url(src: &str) -> bool {
+    let src = if src.starts_with("//") {
+        format!("https:{}", src)
+    } else {
+        src.to_string()
+    };
+    let host = match Url::parse(&src).ok().and_then(|url| url.host_str().map(str::to_string)) {
+        Some(host) => host,
+        None => return false,
+    };
+
+    AD_TRACKER_DOMAINS
+        .iter()
+        .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+}
+
+/// 递归移除满足条件的子孙元素，返回移除数量
+fn remove_matching(node: &Handle, should_remove: &dyn Fn(&Handle) -> bool) -> usize {
+    let mut removed = 0;
+    node.children.borrow_mut().retain(|child| {
+        let remove = should_remove(child);
+        removed += usize::from(remove);
+        !remove
+    });
+    for child in node.children.borrow().iter() {
+        removed += remove_matching(child, should_remove);
+    }
+    removed
+}
+
+/// 找出正文容器
+fn find_main_content(document: &Handle) -> Option<Handle> {
+    for tag in ["article", "main"] {
+        if let Some(element) = find_first_element(document, tag) {
+            if text_content(&element).trim().chars().count() >= MIN_PARAGRAPH_CHARS {
+                return Some(element);
+            }
+        }
+    }
+
+    // readability风格打分：段落得分累加到父元素，并以一半累加到祖父元素
+    let mut scores: HashMap<*const markup5ever_rcdom::Node, (Handle, f64)> = HashMap::new();
+    for paragraph in find_all_elements(document, "p") {
+        let text = text_content(&paragraph);
+        let length = text.trim().chars().count();
+        if length < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+        let commas = text.matches([',', '，', '、']).count();
+        let score = 1.0 + commas as f64 + (length as f64 / 100.0).min(3.0);
+
+        let parent = parent_of(&paragraph);
+        let grandparent = parent.as_ref().and_then(parent_of);
+        for (ancestor, weight) in [(parent, 1.0), (grandparent, 0.5)] {
+            if let Some(ancestor) = ancestor {
+                scores
+                    .entry(Rc::as_ptr(&ancestor))
+                    .or_insert_with(|| (ancestor.clone(), 0.0))
+                    .1 += score * weight;
+            }
+        }
+    }
+
+    scores
+        .into_values()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(handle, _)| handle)
+}
+
+/// 元素标签名，非元素节点返回`None`
+fn element_name(node: &Handle) -> Option<&str> {
+    match &node.data {
+        NodeData::Element { name, .. } => Some(name.local.as_ref()),
+        _ => None,
+    }
+}
+
+/// 父节点
+fn parent_of(node: &Handle) -> Option<Handle> {
+    let weak = node.parent.take();
+    let parent = weak.as_ref().and_then(|weak| weak.upgrade());
+    node.parent.set(weak);
+    parent
+}
+
+/// 按文档顺序查找第一个指定标签的元素
+fn find_first_element(node: &Handle, tag: &str) -> Option<Handle> {
+    if element_name(node) == Some(tag) {
+        return Some(node.clone());
+    }
+    node.children
+        .borrow()
+        .iter()
+        .find_map(|child| find_first_element(child, tag))
+}
+
+/// 按文档顺序查找所有指定标签的元素
+fn find_all_elements(node: &Handle, tag: &str) -> Vec<Handle> {
+    let mut found = Vec::new();
+    if element_name(node) == Some(tag) {
+        found.push(node.clone());
+    }
+    for child in node.children.borrow().iter() {
+        found.extend(find_all_elements(child, tag));
+    }
+    found
+}
+
+/// 子树中的全部文本
+fn text_content(node: &Handle) -> String {
+    let mut text = String::new();
+    if let NodeData::Text { contents } = &node.data {
+        text.push_str(&contents.borrow());
+    }
+    for child in node.children.borrow().iter() {
+        text.push_str(&text_content(child));
+    }
+    text
+}
+
+/// 转义HTML文本中的特殊字符
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE_PAGE: &str = r#"<html><head><title>Daily News</title>
+        <script src="https://www.googletagmanager.com/gtag/js?id=G-1"></script>
+        <script>window.dataLayer = []; function gtag(){dataLayer.push(arguments);}</script>
+        </head><body>
+        <nav><a href="/">Home</a><a href="/world">World</a></nav>
+        <div class="ad-banner">Buy cheap watches now</div>
+        <div id="content">
+            <h1>City opens new library</h1>
+            <p>The city opened its largest public library on Monday, with over a million books, study rooms, and a rooftop garden.</p>
+            <p>Officials said the project, funded by local taxes, took five years to complete and employs two hundred people.</p>
+            <img src="https://ad.doubleclick.net/pixel.gif">
+        </div>
+        <footer>Copyright 2024 Daily News</footer>
+        </body></html>"#;
+
+    #[test]
+    fn test_strip_ads_and_trackers() {
+        let dom = parse_document(RcDom::default(), Default::default()).one(ARTICLE_PAGE);
+        // 外链追踪脚本、内联gtag、广告容器、追踪像素
+        assert_eq!(strip_ads_and_trackers(&dom), 4);
+        let remaining = text_content(&dom.document);
+        assert!(!remaining.contains("Buy cheap watches"));
+        assert!(!remaining.contains("gtag"));
+        assert!(remaining.contains("largest public library"));
+    }
+
+    #[test]
+    fn test_extract_readable_html() {
+        let html = extract_readable_html(ARTICLE_PAGE).unwrap().unwrap();
+        assert!(html.contains("<title>Daily News</title>"));
+        assert!(html.contains("City opens new library"));
+        assert!(html.contains("rooftop garden"));
+        assert!(!html.contains("Buy cheap watches"));
+        assert!(!html.contains("World</a>"));
+        assert!(!html.contains("Copyright"));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn test_extract_readable_html_without_content() {
+        assert_eq!(extract_readable_html("<html><body><p>Hi</p></body></html>").unwrap(), None);
+    }
+
+    #[test]
+    fn test_is_ad_tracker_url() {
+        assert!(is_ad_tracker_url("https://pagead2.googlesyndication.com/pagead/js/adsbygoogle.js"));
+        assert!(is_ad_tracker_url("//hm.baidu.com/hm.js?abc"));
+        assert!(!is_ad_tracker_url("https://example.com/app.js"));
+        assert!(!is_ad_tracker_url("/static/app.js"));
+    }
+}
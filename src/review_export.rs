@@ -0,0 +1,277 @@
+//! 校对文件导出模块
+//!
+//! 将「原文→译文」对按文档出现顺序导出为两列对照表，便于批量人工校对；
+//! 也可导出带元信息的CSV校对表，审阅者在表格工具中修正译文后再导回作为覆盖译文
+
+// 标准库导入
+use std::path::Path;
+
+// 第三方crate导入
+use anyhow::{bail, Context, Result};
+
+// 本地模块导入
+use crate::brand_protection::detect_brands;
+use crate::dictionary::TranslationDictionary;
+use crate::translator::translation_length_ratio;
+use crate::utils::UTF8_BOM;
+
+/// CSV校对表的表头，导回时按「原文」「译文」列名定位
+const REVIEW_CSV_HEADER: [&str; 6] = ["序号", "父标签", "原文", "译文", "字符数比", "是否命中术语表"];
+
+/// 校对文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewFormat {
+    /// Markdown表格
+    Markdown,
+    /// 制表符分隔
+    Tsv,
+}
+
+impl ReviewFormat {
+    /// 按扩展名选择格式：`.tsv`/`.txt`为TSV，其余为Markdown表格
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("tsv" | "txt") => Self::Tsv,
+            _ => Self::Markdown,
+        }
+    }
+}
+
+/// 渲染原文/译文对照表，翻译失败（译文为空）的条目保留空译文以便发现
+pub fn render_review(originals: &[String], translations: &[String], format: ReviewFormat) -> String {
+    let mut output = String::new();
+    match format {
+        ReviewFormat::Markdown => {
+            output.push_str("| # | 原文 | 译文 |\n|---|------|------|\n");
+            for (index, (original, translation)) in originals.iter().zip(translations).enumerate() {
+                output.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    index + 1,
+                    escape_markdown_cell(original),
+                    escape_markdown_cell(translation)
+                ));
+            }
+        }
+        ReviewFormat::Tsv => {
+            output.push_str("原文\t译文\n");
+            for (original, translation) in originals.iter().zip(translations) {
+                output.push_str(&format!("{}\t{}\n", escape_tsv_field(original), escape_tsv_field(translation)));
+            }
+        }
+    }
+    output
+}
+
+/// 写出校对文件，返回条目数量
+pub fn write_review_file(path: &Path, originals: &[String], translations: &[String]) -> Result<usize> {
+    let content = render_review(originals, translations, ReviewFormat::from_path(path));
+    std::fs::write(path, content).with_context(|| format!("写入校对文件失败: {}", path.display()))?;
+    Ok(originals.len().min(translations.len()))
+}
+
+/// 渲染CSV校对表，以BOM开头以便表格工具按UTF-8打开
+///
+/// 「是否命中术语表」表示原文含内置品牌词；父标签与原文一一对应，缺失时留空。
+pub fn render_review_csv(originals: &[String], translations: &[String], parent_tags: &[String]) -> String {
+    let mut output = String::new();
+    output.push(UTF8_BOM);
+    output.push_str(&REVIEW_CSV_HEADER.join(","));
+    output.push_str("\r\n");
+    for (index, (original, translation)) in originals.iter().zip(translations).enumerate() {
+        let ratio = translation_length_ratio(original, translation)
+            .map(|ratio| format!("{:.2}", ratio))
+            .unwrap_or_default();
+        let glossary_hit = if detect_brands(original).is_empty() { "否" } else { "是" };
+        let fields = [
+            (index + 1).to_string(),
+            parent_tags.get(index).cloned().unwrap_or_default(),
+            original.trim().to_string(),
+            translation.trim().to_string(),
+            ratio,
+            glossary_hit.to_string(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| escape_csv_field(field)).collect();
+        output.push_str(&row.join(","));
+        output.push_str("\r\n");
+    }
+    output
+}
+
+/// 写出CSV校对表，返回条目数量
+pub fn write_review_csv(
+    path: &Path,
+    originals: &[String],
+    translations: &[String],
+    parent_tags: &[String],
+) -> Result<usize> {
+    let content = render_review_csv(originals, translations, parent_tags);
+    std::fs::write(path, content).with_context(|| format!("写入CSV校对表失败: {}", path.display()))?;
+    Ok(originals.len().min(translations.len()))
+}
+
+/// 从审阅后的CSV校对表读取「原文→译文」覆盖映射
+///
+/// 按表头中的「原文」「译文」列定位，其余列和列顺序不影响导入；空译文的行被忽略。
+pub fn load_review_overrides(path: &Path) -> Result<TranslationDictionary> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("读取CSV校对表失败: {}", path.display()))?;
+    let rows = parse_csv(content.trim_start_matches(UTF8_BOM));
+    let Some((header, records)) = rows.split_first() else {
+        bail!("CSV校对表为空: {}", path.display());
+    };
+    let column = |name: &str| header.iter().position(|field| field.trim() == name);
+    let (Some(original_column), Some(translation_column)) = (column("原文"), column("译文")) else {
+        bail!("CSV校对表缺少「原文」或「译文」列: {}", path.display());
+    };
+
+    let (originals, translations): (Vec<String>, Vec<String>) = records
+        .iter()
+        .filter_map(|record| Some((record.get(original_column)?.clone(), record.get(translation_column)?.clone())))
+        .unzip();
+    let mut overrides = TranslationDictionary::new();
+    overrides.extend_from_pairs(&originals, &translations);
+    Ok(overrides)
+}
+
+/// 命令行参数解析：加载`--import-overrides`指定的CSV校对表
+pub fn parse_overrides_arg(spec: &str) -> std::result::Result<TranslationDictionary, String> {
+    load_review_overrides(Path::new(spec)).map_err(|e| format!("{:#}", e))
+}
+
+/// 转义CSV字段：含逗号、引号或换行时加引号，内部引号写成两个
+fn escape_csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// 解析CSV（RFC 4180）：支持引号包裹的字段、字段内的逗号、换行和转义引号，忽略空行
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|field| !field.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                }
+                row.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+    row.push(field);
+    if row.iter().any(|field| !field.is_empty()) {
+        rows.push(row);
+    }
+    rows
+}
+
+/// 转义Markdown表格单元格，避免文本中的特殊字符破坏表格或被渲染为格式
+fn escape_markdown_cell(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.trim().chars() {
+        match c {
+            '\\' | '|' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("<br>"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 转义TSV字段中的制表符和换行
+fn escape_tsv_field(text: &str) -> String {
+    text.trim()
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\r', "")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_markdown_review() {
+        let originals = vec!["Hello | World".to_string(), "**Bold** text".to_string(), "Line\nbreak".to_string()];
+        let translations = vec!["你好 | 世界".to_string(), String::new(), "换\n行".to_string()];
+        let review = render_review(&originals, &translations, ReviewFormat::Markdown);
+        let lines: Vec<&str> = review.lines().collect();
+        assert_eq!(lines[0], "| # | 原文 | 译文 |");
+        assert_eq!(lines[2], "| 1 | Hello \\| World | 你好 \\| 世界 |");
+        assert_eq!(lines[3], "| 2 | \\*\\*Bold\\*\\* text |  |");
+        assert_eq!(lines[4], "| 3 | Line<br>break | 换<br>行 |");
+    }
+
+    #[test]
+    fn test_render_tsv_review() {
+        let originals = vec!["Tab\there".to_string()];
+        let translations = vec!["制表\t符".to_string()];
+        assert_eq!(
+            render_review(&originals, &translations, ReviewFormat::Tsv),
+            "原文\t译文\nTab\\there\t制表\\t符\n"
+        );
+        assert_eq!(ReviewFormat::from_path(Path::new("review.TSV")), ReviewFormat::Tsv);
+        assert_eq!(ReviewFormat::from_path(Path::new("review.md")), ReviewFormat::Markdown);
+    }
+
+    #[test]
+    fn test_review_csv_round_trip() {
+        let originals = vec!["Buy an iPhone".to_string(), "Hello, \"world\"".to_string(), "Failed".to_string()];
+        let translations = vec!["买一部 iPhone".to_string(), "你好，\"世界\"".to_string(), String::new()];
+        let parent_tags = vec!["h1".to_string(), "p".to_string()];
+        let csv = render_review_csv(&originals, &translations, &parent_tags);
+        let lines: Vec<&str> = csv.trim_start_matches(UTF8_BOM).lines().collect();
+        assert_eq!(lines[0], "序号,父标签,原文,译文,字符数比,是否命中术语表");
+        assert_eq!(lines[1], "1,h1,Buy an iPhone,买一部 iPhone,0.77,是");
+        assert_eq!(lines[2], "2,p,\"Hello, \"\"world\"\"\",\"你好，\"\"世界\"\"\",0.50,否");
+        assert_eq!(lines[3], "3,,Failed,,,否");
+
+        // 审阅者修改译文后导回
+        let path = std::env::temp_dir().join(format!("review_csv_round_trip_{}.csv", std::process::id()));
+        let edited = csv.replace("买一部 iPhone", "\"购买 iPhone\n手机\"");
+        std::fs::write(&path, edited).unwrap();
+        let overrides = load_review_overrides(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(overrides.get("Buy an iPhone"), Some("购买 iPhone\n手机"));
+        assert_eq!(overrides.get("Hello, \"world\""), Some("你好，\"世界\""));
+        assert_eq!(overrides.get("Failed"), None);
+        assert_eq!(overrides.len(), 2);
+    }
+
+    #[test]
+    fn test_load_review_overrides_requires_columns() {
+        let path = std::env::temp_dir().join(format!("review_csv_bad_{}.csv", std::process::id()));
+        std::fs::write(&path, "source,target\nHello,你好\n").unwrap();
+        let error = load_review_overrides(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(error.to_string().contains("缺少「原文」或「译文」列"));
+    }
+}
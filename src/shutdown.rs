@@ -0,0 +1,106 @@
+//! 中断信号处理模块
+//!
+//! 长任务运行中收到SIGINT/SIGTERM时不立即终止，而是置位停止标志：翻译流程不再发起新批次、
+//! 新文件，在途请求照常完成，已完成的译文写出并写回翻译缓存（重新运行时从缓存续跑），
+//! 临时文件随正常返回清理后再退出。再次收到信号时立即退出
+
+// 标准库导入
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// 第三方crate导入
+use tracing::warn;
+
+// 本地模块导入
+use crate::error::TranslationError;
+
+/// 被信号中断时的进程退出码（128 + SIGINT）
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// 可在各配置副本、各任务间共享的停止标志
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownSignal {
+    requested: Arc<AtomicBool>,
+}
+
+impl ShutdownSignal {
+    /// 创建未置位的停止标志
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建停止标志并安装SIGINT/SIGTERM处理，需在tokio运行时内调用
+    ///
+    /// 第一次收到信号时置位标志；第二次收到信号时立即以[`INTERRUPTED_EXIT_CODE`]退出。
+    pub fn install() -> Self {
+        let signal = Self::new();
+        let handler = signal.clone();
+        tokio::spawn(async move {
+            wait_for_signal().await;
+            warn!("🛑 收到中断信号：不再发起新的批次和文件，完成手头任务并写出结果后退出（再次中断立即退出）");
+            handler.request();
+
+            wait_for_signal().await;
+            warn!("🛑 再次收到中断信号，立即退出");
+            std::process::exit(INTERRUPTED_EXIT_CODE);
+        });
+        signal
+    }
+
+    /// 请求停止
+    pub fn request(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+    }
+
+    /// 是否已请求停止
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    /// 发起新请求前检查：已请求停止时返回错误
+    pub fn check(&self) -> Result<(), TranslationError> {
+        if self.is_requested() {
+            return Err(TranslationError::Interrupted);
+        }
+        Ok(())
+    }
+}
+
+/// 等待SIGINT（Ctrl-C）或SIGTERM
+async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_is_shared_between_clones() {
+        let signal = ShutdownSignal::new();
+        let clone = signal.clone();
+        assert!(clone.check().is_ok());
+
+        signal.request();
+        assert!(clone.is_requested());
+        assert!(matches!(clone.check(), Err(TranslationError::Interrupted)));
+    }
+}
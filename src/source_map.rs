@@ -0,0 +1,157 @@
+//! 译文定位映射（source map）模块
+//!
+//! 记录每条译文与原文的对应关系及其在输入、输出文件中的大致行号，便于QA时定位问题。
+//! 由于输出经过DOM序列化，位置通过按文档顺序查找文本得到，只保证近似。
+
+// 标准库导入
+use std::collections::HashMap;
+use std::path::Path;
+
+// 第三方crate导入
+use anyhow::{Context, Result};
+use serde_json::json;
+
+/// 一条译文的定位信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMapEntry {
+    /// 原文在文档中的出现次序（从0开始）
+    pub index: usize,
+    /// 原文文本
+    pub source: String,
+    /// 译文文本，翻译失败时为空
+    pub translation: String,
+    /// 相同原文第几次出现（从1开始）
+    pub occurrence: usize,
+    /// 原文在输入中的行号（从1开始），未找到时为None
+    pub source_line: Option<usize>,
+    /// 译文在输出中的行号（从1开始），未找到时为None
+    pub output_line: Option<usize>,
+}
+
+/// 按文档顺序逐条查找文本所在行号
+///
+/// 每次从上一次命中的位置之后开始查找，使重复文本依次对应到后续出现处；
+/// 找不到（如文本在源码中以实体形式出现）时不移动查找位置。
+struct LineLocator<'a> {
+    content: &'a str,
+    cursor: usize,
+}
+
+impl<'a> LineLocator<'a> {
+    fn new(content: &'a str) -> Self {
+        Self { content, cursor: 0 }
+    }
+
+    fn locate(&mut self, text: &str) -> Option<usize> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let offset = self.cursor + self.content[self.cursor..].find(text)?;
+        self.cursor = offset + text.len();
+        Some(self.content[..offset].matches('\n').count() + 1)
+    }
+}
+
+/// 根据输入、输出内容和按文档顺序排列的原文/译文构建映射
+pub fn build_source_map(
+    input: &str,
+    output: &str,
+    originals: &[String],
+    translations: &[String],
+) -> Vec<SourceMapEntry> {
+    let mut input_locator = LineLocator::new(input);
+    let mut output_locator = LineLocator::new(output);
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+
+    originals
+        .iter()
+        .zip(translations)
+        .enumerate()
+        .map(|(index, (source, translation))| {
+            let occurrence = occurrences.entry(source.as_str()).or_default();
+            *occurrence += 1;
+            SourceMapEntry {
+                index,
+                source: source.clone(),
+                translation: translation.clone(),
+                occurrence: *occurrence,
+                source_line: input_locator.locate(source),
+                output_line: output_locator.locate(translation),
+            }
+        })
+        .collect()
+}
+
+/// 将映射渲染为JSON文档
+pub fn render_source_map(entries: &[SourceMapEntry]) -> String {
+    let mappings: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "index": entry.index,
+                "source": entry.source,
+                "translation": entry.translation,
+                "occurrence": entry.occurrence,
+                "source_line": entry.source_line,
+                "output_line": entry.output_line,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&json!({ "version": 1, "mappings": mappings }))
+        .unwrap_or_default()
+}
+
+/// 写出source map文件，返回条目数量
+pub fn write_source_map(
+    path: &Path,
+    input: &str,
+    output: &str,
+    originals: &[String],
+    translations: &[String],
+) -> Result<usize> {
+    let entries = build_source_map(input, output, originals, translations);
+    std::fs::write(path, render_source_map(&entries))
+        .with_context(|| format!("写入source map失败: {}", path.display()))?;
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_source_map() {
+        let input = "<html>\n<body>\n<p>Hello</p>\n<p>World</p>\n<p>Hello</p>\n</body>\n</html>";
+        let output = "<html><body>\n<p>你好</p><p>世界</p>\n<p>你好</p></body></html>";
+        let originals = vec!["Hello".to_string(), "World".to_string(), "Hello".to_string()];
+        let translations = vec!["你好".to_string(), "世界".to_string(), "你好".to_string()];
+
+        let entries = build_source_map(input, output, &originals, &translations);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].source_line, Some(3));
+        assert_eq!(entries[1].source_line, Some(4));
+        // 重复原文依次对应到后续出现处
+        assert_eq!(entries[2].source_line, Some(5));
+        assert_eq!(entries[2].occurrence, 2);
+        assert_eq!(entries[0].output_line, Some(2));
+        assert_eq!(entries[2].output_line, Some(3));
+    }
+
+    #[test]
+    fn test_missing_text_keeps_position() {
+        let input = "<p>Tom &amp; Jerry</p>\n<p>Next</p>";
+        let originals = vec!["Tom & Jerry".to_string(), "Next".to_string()];
+        let translations = vec![String::new(), "下一个".to_string()];
+
+        let entries = build_source_map(input, "<p>下一个</p>", &originals, &translations);
+        assert_eq!(entries[0].source_line, None);
+        assert_eq!(entries[0].output_line, None);
+        assert_eq!(entries[1].source_line, Some(2));
+        assert_eq!(entries[1].output_line, Some(1));
+
+        let rendered: serde_json::Value = serde_json::from_str(&render_source_map(&entries)).unwrap();
+        assert_eq!(rendered["mappings"][1]["translation"], "下一个");
+        assert!(rendered["mappings"][0]["source_line"].is_null());
+    }
+}
@@ -0,0 +1,162 @@
+//! 符号保护模块
+//!
+//! 翻译API可能吞掉或改写emoji、箭头、数学符号等字符。翻译前将连续的符号
+//! 替换为占位符，翻译后按占位符还原；占位符丢失时按原位置（句首/句尾）补回
+
+// 第三方crate导入
+use regex::Regex;
+
+/// 经过符号保护的文本
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtectedText {
+    /// 替换符号后的文本
+    pub text: String,
+    /// 被保护的符号片段及其是否位于原文开头
+    symbols: Vec<(String, bool)>,
+}
+
+impl ProtectedText {
+    /// 是否有被保护的符号
+    pub fn has_symbols(&self) -> bool {
+        !self.symbols.is_empty()
+    }
+}
+
+/// 生成第`index`个占位符
+fn placeholder(index: usize) -> String {
+    format!("{{{{S{}}}}}", index)
+}
+
+/// 判断字符是否属于需要保护的emoji或特殊符号
+pub fn is_protected_symbol(c: char) -> bool {
+    matches!(c as u32,
+        0x2190..=0x21FF     // 箭头
+        | 0x2200..=0x22FF   // 数学运算符
+        | 0x2300..=0x23FF   // 杂项技术符号
+        | 0x25A0..=0x25FF   // 几何图形
+        | 0x2600..=0x27BF   // 杂项符号和装饰符号
+        | 0x2900..=0x297F   // 补充箭头
+        | 0x2B00..=0x2BFF   // 杂项符号和箭头
+        | 0x1F000..=0x1FAFF // emoji及图形符号（含肤色修饰、区域指示符）
+    )
+}
+
+/// 是否为连接emoji序列的辅助字符（零宽连接符、变体选择符、组合键帽）
+fn is_symbol_joiner(c: char) -> bool {
+    matches!(c, '\u{200D}' | '\u{FE0E}' | '\u{FE0F}' | '\u{20E3}')
+}
+
+/// 将文本中的连续符号替换为占位符
+pub fn protect_symbols(text: &str) -> ProtectedText {
+    let mut protected = String::with_capacity(text.len());
+    let mut symbols: Vec<(String, bool)> = Vec::new();
+    let mut current = String::new();
+    let mut current_at_start = false;
+
+    for c in text.chars() {
+        if is_protected_symbol(c) || (!current.is_empty() && is_symbol_joiner(c)) {
+            if current.is_empty() {
+                current_at_start = protected.trim().is_empty();
+            }
+            current.push(c);
+            continue;
+        }
+
+        if !current.is_empty() {
+            protected.push_str(&placeholder(symbols.len()));
+            symbols.push((std::mem::take(&mut current), current_at_start));
+        }
+        protected.push(c);
+    }
+
+    if !current.is_empty() {
+        protected.push_str(&placeholder(symbols.len()));
+        symbols.push((current, current_at_start));
+    }
+
+    ProtectedText {
+        text: protected,
+        symbols,
+    }
+}
+
+/// 将译文中的占位符还原为原符号
+///
+/// 占位符允许被API加入空格（如`{{ S0 }}`）；完全丢失的符号按其在原文中的位置
+/// 补回到句首或句尾。
+pub fn restore_symbols(translation: &str, protected: &ProtectedText) -> String {
+    if !protected.has_symbols() || translation.is_empty() {
+        return translation.to_string();
+    }
+
+    let placeholder_regex = match Regex::new(r"\{\{\s*S\s*(\d+)\s*\}\}") {
+        Ok(regex) => regex,
+        Err(_) => return translation.to_string(),
+    };
+
+    let mut restored_indices = vec![false; protected.symbols.len()];
+    let restored = placeholder_regex.replace_all(translation, |captures: &regex::Captures| {
+        captures[1]
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| {
+                protected.symbols.get(index).map(|(symbol, _)| {
+                    restored_indices[index] = true;
+                    symbol.clone()
+                })
+            })
+            .unwrap_or_default()
+    });
+
+    let mut prefix = String::new();
+    let mut suffix = String::new();
+    for ((symbol, at_start), restored) in protected.symbols.iter().zip(restored_indices) {
+        if restored {
+            continue;
+        }
+        if *at_start {
+            prefix.push_str(symbol);
+            prefix.push(' ');
+        } else {
+            suffix.push(' ');
+            suffix.push_str(symbol);
+        }
+    }
+
+    format!("{}{}{}", prefix, restored, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protect_and_restore_emoji() {
+        let protected = protect_symbols("🎉 Congratulations → next step ✔️");
+        assert_eq!(protected.text, "{{S0}} Congratulations {{S1}} next step {{S2}}");
+
+        let translation = "{{S0}} 恭喜 {{ S1 }} 下一步 {{S2}}";
+        assert_eq!(restore_symbols(translation, &protected), "🎉 恭喜 → 下一步 ✔️");
+    }
+
+    #[test]
+    fn test_restore_lost_placeholders_by_position() {
+        let protected = protect_symbols("🎉 Congratulations 🚀");
+        assert_eq!(restore_symbols("恭喜", &protected), "🎉 恭喜 🚀");
+    }
+
+    #[test]
+    fn test_zwj_sequence_is_single_symbol() {
+        let protected = protect_symbols("Family 👨‍👩‍👧 photo");
+        assert_eq!(protected.text, "Family {{S0}} photo");
+        assert_eq!(restore_symbols("家庭 {{S0}} 照片", &protected), "家庭 👨‍👩‍👧 照片");
+    }
+
+    #[test]
+    fn test_text_without_symbols_is_unchanged() {
+        let protected = protect_symbols("Plain text, 中文。");
+        assert!(!protected.has_symbols());
+        assert_eq!(protected.text, "Plain text, 中文。");
+        assert_eq!(restore_symbols("纯文本", &protected), "纯文本");
+    }
+}
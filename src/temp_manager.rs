@@ -8,10 +8,12 @@
 
 // 标准库导入
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 // 第三方crate导入
 use anyhow::{Context, Result};
+use tokio::io::AsyncWriteExt;
 use tracing::{debug, info, warn};
 
 /// 临时文件管理器配置
@@ -38,6 +40,31 @@ impl Default for TempManagerConfig {
     }
 }
 
+/// 单个临时路径的清理失败记录
+#[derive(Debug)]
+pub struct CleanupFailure {
+    /// 清理失败的路径
+    pub path: PathBuf,
+    /// 底层IO错误
+    pub error: std::io::Error,
+}
+
+/// 临时文件清理结果报告
+#[derive(Debug, Default)]
+pub struct CleanupReport {
+    /// 成功删除的文件和目录
+    pub removed: Vec<PathBuf>,
+    /// 清理失败的文件和目录
+    pub failed: Vec<CleanupFailure>,
+}
+
+impl CleanupReport {
+    /// 是否全部清理成功
+    pub fn is_clean(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
 /// 临时文件管理器主结构体
 pub struct TempManager {
     config: TempManagerConfig,
@@ -63,30 +90,59 @@ impl TempManager {
     }
 
     /// 使用默认配置创建临时文件管理器
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Result<Self> {
         Self::new(TempManagerConfig::default())
     }
 
     /// 创建临时文件
     pub fn create_temp_file(&mut self, suffix: &str) -> Result<PathBuf> {
-        self.check_file_limit()?;
+        self.create_temp_file_handle(suffix).map(|(_, temp_path)| temp_path)
+    }
 
-        let file_name = format!("{}_{}.{}", 
-            self.config.file_prefix,
-            self.generate_unique_id(),
-            suffix
-        );
+    /// 创建临时文件并返回已打开的可写句柄及其路径
+    ///
+    /// 以独占方式新建文件（路径已存在时失败，不跟随已有的符号链接），Unix下权限为0600。
+    /// 调用方直接通过句柄写入，避免创建后按路径重新打开带来的TOCTOU问题。
+    pub fn create_temp_file_handle(&mut self, suffix: &str) -> Result<(fs::File, PathBuf)> {
+        let temp_path = self.next_temp_path(suffix)?;
 
-        let temp_path = self.config.temp_dir.join(file_name);
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let file = options
+            .open(&temp_path)
+            .with_context(|| format!("创建临时文件失败: {}", temp_path.display()))?;
+
+        self.tracked_files.push(temp_path.clone());
+        debug!("创建临时文件: {}", temp_path.display());
+
+        Ok((file, temp_path))
+    }
 
-        // 创建空文件
-        fs::File::create(&temp_path)
+    /// 异步创建临时文件并返回已打开的可写句柄及其路径
+    ///
+    /// 与[`Self::create_temp_file_handle`]语义相同，IO通过`tokio::fs`完成，不阻塞异步运行时。
+    pub async fn create_temp_file_handle_async(&mut self, suffix: &str) -> Result<(tokio::fs::File, PathBuf)> {
+        let temp_path = self.next_temp_path(suffix)?;
+
+        let mut options = tokio::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let file = options
+            .open(&temp_path)
+            .await
             .with_context(|| format!("创建临时文件失败: {}", temp_path.display()))?;
 
         self.tracked_files.push(temp_path.clone());
         debug!("创建临时文件: {}", temp_path.display());
 
-        Ok(temp_path)
+        Ok((file, temp_path))
     }
 
     /// 创建临时目录
@@ -109,9 +165,25 @@ impl TempManager {
 
     /// 写入内容到临时文件
     pub fn write_temp_file(&mut self, content: &str, suffix: &str) -> Result<PathBuf> {
-        let temp_path = self.create_temp_file(suffix)?;
+        let (mut file, temp_path) = self.create_temp_file_handle(suffix)?;
 
-        fs::write(&temp_path, content)
+        file.write_all(content.as_bytes())
+            .with_context(|| format!("写入临时文件失败: {}", temp_path.display()))?;
+
+        debug!("写入临时文件完成: {} ({} 字节)", temp_path.display(), content.len());
+
+        Ok(temp_path)
+    }
+
+    /// 异步写入内容到临时文件
+    pub async fn write_temp_file_async(&mut self, content: &str, suffix: &str) -> Result<PathBuf> {
+        let (mut file, temp_path) = self.create_temp_file_handle_async(suffix).await?;
+
+        file.write_all(content.as_bytes())
+            .await
+            .with_context(|| format!("写入临时文件失败: {}", temp_path.display()))?;
+        file.flush()
+            .await
             .with_context(|| format!("写入临时文件失败: {}", temp_path.display()))?;
 
         debug!("写入临时文件完成: {} ({} 字节)", temp_path.display(), content.len());
@@ -139,6 +211,27 @@ impl TempManager {
         Ok(temp_path)
     }
 
+    /// 异步复制文件到临时位置
+    pub async fn copy_to_temp_async<P: AsRef<Path>>(&mut self, source_path: P, suffix: &str) -> Result<PathBuf> {
+        let (_, temp_path) = self.create_temp_file_handle_async(suffix).await?;
+
+        tokio::fs::copy(source_path.as_ref(), &temp_path)
+            .await
+            .with_context(|| {
+                format!("复制文件到临时位置失败: {} -> {}", 
+                    source_path.as_ref().display(),
+                    temp_path.display()
+                )
+            })?;
+
+        debug!("复制到临时文件: {} -> {}", 
+            source_path.as_ref().display(), 
+            temp_path.display()
+        );
+
+        Ok(temp_path)
+    }
+
     /// 移动文件到临时位置
     pub fn move_to_temp<P: AsRef<Path>>(&mut self, source_path: P, suffix: &str) -> Result<PathBuf> {
         let temp_path = self.create_temp_file(suffix)?;
@@ -169,19 +262,33 @@ impl TempManager {
 
     /// 从爬取的内容创建HTML临时文件
     pub fn create_temp_html_from_crawl(&mut self, html_content: &str, url: &str) -> Result<PathBuf> {
-        // 添加元数据注释
+        let full_content = Self::crawl_html_with_metadata(html_content, url);
+        
+        let temp_path = self.create_temp_html(&full_content)?;
+        info!("📁 HTML临时文件已创建: {}", temp_path.display());
+        
+        Ok(temp_path)
+    }
+
+    /// 异步从爬取的内容创建HTML临时文件，供异步翻译流程使用
+    pub async fn create_temp_html_from_crawl_async(&mut self, html_content: &str, url: &str) -> Result<PathBuf> {
+        let full_content = Self::crawl_html_with_metadata(html_content, url);
+
+        let temp_path = self.write_temp_file_async(&full_content, "html").await?;
+        info!("📁 HTML临时文件已创建: {}", temp_path.display());
+
+        Ok(temp_path)
+    }
+
+    /// 在爬取内容前添加来源元数据注释
+    fn crawl_html_with_metadata(html_content: &str, url: &str) -> String {
         let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
         let metadata_comment = format!(
             "<!-- 由translation-cli生成 -->\n<!-- 源URL: {} -->\n<!-- 生成时间: {} -->\n",
             url, timestamp
         );
         
-        let full_content = format!("{}\n{}", metadata_comment, html_content);
-        
-        let temp_path = self.create_temp_html(&full_content)?;
-        info!("📁 HTML临时文件已创建: {}", temp_path.display());
-        
-        Ok(temp_path)
+        format!("{}\n{}", metadata_comment, html_content)
     }
 
     /// 获取临时工作目录
@@ -233,44 +340,55 @@ impl TempManager {
     }
 
     /// 清理所有临时文件和目录
-    pub fn cleanup_all(&mut self) -> Result<()> {
+    ///
+    /// 返回结构化的清理报告。清理失败的路径会保留在跟踪列表中，
+    /// 调用方可以据此决定重试或上报。
+    pub fn cleanup_all(&mut self) -> Result<CleanupReport> {
         info!("开始清理所有临时文件...");
 
-        let mut errors = Vec::new();
+        let mut report = CleanupReport::default();
 
         // 清理文件
-        for file_path in &self.tracked_files {
-            if let Err(e) = fs::remove_file(file_path) {
-                if file_path.exists() {
-                    errors.push(format!("清理文件失败 {}: {}", file_path.display(), e));
+        let mut remaining_files = Vec::new();
+        for file_path in self.tracked_files.drain(..) {
+            match fs::remove_file(&file_path) {
+                Ok(()) => {
+                    debug!("已清理临时文件: {}", file_path.display());
+                    report.removed.push(file_path);
+                }
+                Err(_) if !file_path.exists() => {}
+                Err(e) => {
+                    remaining_files.push(file_path.clone());
+                    report.failed.push(CleanupFailure { path: file_path, error: e });
                 }
-            } else {
-                debug!("已清理临时文件: {}", file_path.display());
             }
         }
+        self.tracked_files = remaining_files;
 
         // 清理目录
-        for dir_path in &self.tracked_dirs {
-            if let Err(e) = fs::remove_dir_all(dir_path) {
-                if dir_path.exists() {
-                    errors.push(format!("清理目录失败 {}: {}", dir_path.display(), e));
+        let mut remaining_dirs = Vec::new();
+        for dir_path in self.tracked_dirs.drain(..) {
+            match fs::remove_dir_all(&dir_path) {
+                Ok(()) => {
+                    debug!("已清理临时目录: {}", dir_path.display());
+                    report.removed.push(dir_path);
+                }
+                Err(_) if !dir_path.exists() => {}
+                Err(e) => {
+                    remaining_dirs.push(dir_path.clone());
+                    report.failed.push(CleanupFailure { path: dir_path, error: e });
                 }
-            } else {
-                debug!("已清理临时目录: {}", dir_path.display());
             }
         }
+        self.tracked_dirs = remaining_dirs;
 
-        // 清空跟踪列表
-        self.tracked_files.clear();
-        self.tracked_dirs.clear();
-
-        if !errors.is_empty() {
-            warn!("清理过程中遇到错误: {:?}", errors);
+        if !report.is_clean() {
+            warn!("清理过程中遇到错误: {:?}", report.failed);
         } else {
             info!("临时文件清理完成");
         }
 
-        Ok(())
+        Ok(report)
     }
 
     /// 确保临时目录存在
@@ -285,6 +403,19 @@ impl TempManager {
         Ok(())
     }
 
+    /// 检查数量限制并生成下一个临时文件路径
+    fn next_temp_path(&self, suffix: &str) -> Result<PathBuf> {
+        self.check_file_limit()?;
+
+        let file_name = format!("{}_{}.{}", 
+            self.config.file_prefix,
+            self.generate_unique_id(),
+            suffix
+        );
+
+        Ok(self.config.temp_dir.join(file_name))
+    }
+
     /// 检查文件数量限制
     fn check_file_limit(&self) -> Result<()> {
         if self.tracked_files.len() >= self.config.max_temp_files {
@@ -314,8 +445,12 @@ impl TempManager {
 impl Drop for TempManager {
     fn drop(&mut self) {
         if self.config.auto_cleanup {
-            if let Err(e) = self.cleanup_all() {
-                warn!("自动清理临时文件时出错: {}", e);
+            match self.cleanup_all() {
+                Ok(report) if !report.is_clean() => {
+                    warn!("自动清理临时文件时有 {} 项失败", report.failed.len());
+                }
+                Ok(_) => {}
+                Err(e) => warn!("自动清理临时文件时出错: {}", e),
             }
         }
     }
@@ -334,6 +469,7 @@ pub fn create_temp_work_dir() -> Result<PathBuf> {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use std::fs;
@@ -380,6 +516,26 @@ mod tests {
         assert_eq!(manager.list_temp_files().len(), 0);
     }
 
+    #[test]
+    fn test_temp_file_handle_creation() {
+        let mut manager = TempManager::default().unwrap();
+
+        let (mut file, temp_path) = manager.create_temp_file_handle("txt").unwrap();
+        file.write_all("句柄写入".as_bytes()).unwrap();
+        drop(file);
+        assert_eq!(fs::read_to_string(&temp_path).unwrap(), "句柄写入");
+        assert_eq!(manager.list_temp_files(), vec![temp_path.clone()]);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&temp_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        manager.cleanup_file(&temp_path).unwrap();
+    }
+
     #[test]
     fn test_temp_dir_creation_and_cleanup() {
         let mut manager = TempManager::default().unwrap();
@@ -522,11 +678,26 @@ mod tests {
         assert_eq!(manager.list_temp_dirs().len(), 2);
         
         // 清理所有
-        manager.cleanup_all().unwrap();
+        let report = manager.cleanup_all().unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.removed.len(), 4);
         assert_eq!(manager.list_temp_files().len(), 0);
         assert_eq!(manager.list_temp_dirs().len(), 0);
     }
 
+    #[test]
+    fn test_cleanup_all_skips_already_removed() {
+        let mut manager = TempManager::default().unwrap();
+
+        let file = manager.create_temp_file("txt").unwrap();
+        fs::remove_file(&file).unwrap();
+
+        let report = manager.cleanup_all().unwrap();
+        assert!(report.is_clean());
+        assert!(report.removed.is_empty());
+        assert_eq!(manager.list_temp_files().len(), 0);
+    }
+
     #[test]
     fn test_get_work_dir() {
         let mut manager = TempManager::default().unwrap();
@@ -597,4 +768,34 @@ mod tests {
             assert!(part.chars().all(|c| c.is_ascii_hexdigit()), "ID部分应该是十六进制: {}", part);
         }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_async_temp_file_operations() {
+        let temp_base = std::env::temp_dir().join("translation-cli-test-async");
+        let config = TempManagerConfig {
+            temp_dir: temp_base.clone(),
+            ..Default::default()
+        };
+        let mut manager = TempManager::new(config).unwrap();
+
+        let written = manager.write_temp_file_async("<p>async</p>", "html").await.unwrap();
+        assert_eq!(fs::read_to_string(&written).unwrap(), "<p>async</p>");
+
+        let copied = manager.copy_to_temp_async(&written, "html").await.unwrap();
+        assert_eq!(fs::read_to_string(&copied).unwrap(), "<p>async</p>");
+
+        let crawled = manager
+            .create_temp_html_from_crawl_async("<p>page</p>", "https://example.com")
+            .await
+            .unwrap();
+        let content = fs::read_to_string(&crawled).unwrap();
+        assert!(content.contains("源URL: https://example.com"));
+        assert!(content.ends_with("<p>page</p>"));
+
+        assert_eq!(manager.list_temp_files().len(), 3);
+        manager.cleanup_all().unwrap();
+        assert!(!written.exists() && !copied.exists() && !crawled.exists());
+
+        let _ = fs::remove_dir_all(&temp_base);
+    }
+}
@@ -0,0 +1,655 @@
+//! 翻译结果磁盘缓存模块
+//!
+//! 以「作用域 + 目标语言 + 原文」为键将译文持久化到标准缓存目录。同一作用域、同一目标语言
+//! 的条目存放在同一个分片文件中：翻译开始时一次读入整个分片，结束时一次写回，
+//! 不会随缓存规模产生大量随机小IO。作用域标识翻译来源（API端点、协议、模型），
+//! 切换来源后不会复用其它来源的译文
+
+// 标准库导入
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// 第三方crate导入
+use anyhow::{Context, Result};
+use futures::channel::oneshot;
+use futures::future::{FutureExt, Shared};
+use serde_json::json;
+use tracing::debug;
+
+/// 缓存分片文件扩展名
+const CACHE_ENTRY_EXTENSION: &str = "json";
+
+/// 缓存占用统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheUsage {
+    /// 条目数量
+    pub entries: usize,
+    /// 占用字节数
+    pub bytes: u64,
+}
+
+/// 分片中的一条译文
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ShardEntry {
+    translation: String,
+    /// 写入时间（Unix秒），按时间清理时逐条判断
+    updated: u64,
+}
+
+/// 分片内容：原文→译文
+type ShardEntries = HashMap<String, ShardEntry>;
+
+/// 已读入内存的分片，文件未变化时直接复用，批量任务中各页面不必重复解析
+struct LoadedShard {
+    /// 读入时文件的修改时间和大小
+    stamp: (SystemTime, u64),
+    entries: Arc<ShardEntries>,
+}
+
+/// 进程内已读入的分片
+fn loaded_shards() -> &'static Mutex<HashMap<PathBuf, LoadedShard>> {
+    static SHARDS: OnceLock<Mutex<HashMap<PathBuf, LoadedShard>>> = OnceLock::new();
+    SHARDS.get_or_init(Default::default)
+}
+
+/// 串行化进程内对分片的「读取-合并-写回」，避免并发翻译的文件互相覆盖新条目
+fn shard_write_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(Default::default)
+}
+
+/// 当前Unix秒
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// 文件的修改时间和大小，文件不存在时返回None
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// 翻译结果磁盘缓存
+#[derive(Debug, Clone)]
+pub struct TranslationCache {
+    dir: PathBuf,
+    /// 翻译来源标识的哈希，空字符串表示不区分来源
+    scope: String,
+}
+
+impl TranslationCache {
+    /// 使用指定目录创建缓存（目录在首次写入时创建）
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            scope: String::new(),
+        }
+    }
+
+    /// 设置翻译来源标识（如API端点、协议、模型的组合），只命中同一来源写入的条目
+    ///
+    /// 分片中只保存标识的哈希，端点中的token等不会以明文写入缓存目录。
+    pub fn with_scope(mut self, scope: &str) -> Self {
+        self.scope = format!("{:016x}", stable_hash(scope));
+        self
+    }
+
+    /// 标准缓存目录，如Linux下的`~/.cache/translation-cli/translations`
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("translation-cli").join("translations"))
+    }
+
+    /// 打开位于标准缓存目录的缓存
+    pub fn open_default() -> Result<Self> {
+        Self::default_dir()
+            .map(Self::new)
+            .ok_or_else(|| anyhow::anyhow!("无法定位系统缓存目录"))
+    }
+
+    /// 缓存目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 查询缓存的译文
+    pub fn get(&self, text: &str, target_lang: &str) -> Option<String> {
+        self.load_shard(target_lang)
+            .get(text)
+            .map(|entry| entry.translation.clone())
+    }
+
+    /// 按本次所有文本一次性预取缓存条目到内存
+    ///
+    /// 只读取本作用域、本目标语言的一个分片文件（文件未变化时复用进程内已读入的内容），
+    /// 与缓存中其它来源、其它语言的条目数量无关。返回「原文→译文」映射，翻译过程直接查内存。
+    pub fn prefetch(&self, texts: &[String], target_lang: &str) -> HashMap<String, String> {
+        let shard = self.load_shard(target_lang);
+        texts
+            .iter()
+            .filter_map(|text| {
+                shard
+                    .get(text)
+                    .map(|entry| (text.clone(), entry.translation.clone()))
+            })
+            .collect()
+    }
+
+    /// 批量写回新增译文：合并到分片后整体写出一次，返回写入的条目数
+    pub fn put_many(&self, entries: &[(String, String)], target_lang: &str) -> Result<usize> {
+        if entries.is_empty() {
+            return Ok(0);
+        }
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("创建缓存目录失败: {}", self.dir.display()))?;
+
+        let _guard = shard_write_lock().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut shard = (*self.load_shard(target_lang)).clone();
+        let updated = unix_now();
+        for (text, translation) in entries {
+            shard.insert(
+                text.clone(),
+                ShardEntry {
+                    translation: translation.clone(),
+                    updated,
+                },
+            );
+        }
+        self.write_shard(&self.shard_path(target_lang), target_lang, shard)?;
+        Ok(entries.len())
+    }
+
+    /// 写入译文
+    pub fn put(&self, text: &str, target_lang: &str, translation: &str) -> Result<()> {
+        self.put_many(&[(text.to_string(), translation.to_string())], target_lang)
+            .map(|_| ())
+    }
+
+    /// 读取本作用域、指定目标语言的分片，文件未变化时复用进程内已读入的内容
+    fn load_shard(&self, target_lang: &str) -> Arc<ShardEntries> {
+        let path = self.shard_path(target_lang);
+        let mut loaded = loaded_shards().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(stamp) = file_stamp(&path) else {
+            loaded.remove(&path);
+            return Arc::default();
+        };
+        if let Some(shard) = loaded.get(&path).filter(|shard| shard.stamp == stamp) {
+            return shard.entries.clone();
+        }
+
+        let entries = Arc::new(
+            std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .and_then(|value| self.parse_shard(&value, target_lang))
+                .unwrap_or_default(),
+        );
+        loaded.insert(path, LoadedShard { stamp, entries: entries.clone() });
+        entries
+    }
+
+    /// 解析分片内容，作用域或目标语言不符（哈希冲突）时返回None
+    fn parse_shard(&self, value: &serde_json::Value, target_lang: &str) -> Option<ShardEntries> {
+        if value["target_lang"].as_str() != Some(target_lang)
+            || value["scope"].as_str().unwrap_or_default() != self.scope
+        {
+            debug!("缓存分片键冲突，忽略: {}", target_lang);
+            return None;
+        }
+        Some(parse_shard_entries(value))
+    }
+
+    /// 整体写出分片并更新进程内副本；先写临时文件再重命名，避免并发读取到半写入的分片
+    fn write_shard(&self, path: &Path, target_lang: &str, entries: ShardEntries) -> Result<()> {
+        let mut value = json!({
+            "target_lang": target_lang,
+            "entries": render_shard_entries(&entries),
+        });
+        if !self.scope.is_empty() {
+            value["scope"] = json!(self.scope);
+        }
+        write_file_atomically(path, &value.to_string())?;
+
+        if let Some(stamp) = file_stamp(path) {
+            loaded_shards()
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .insert(path.to_path_buf(), LoadedShard { stamp, entries: Arc::new(entries) });
+        }
+        Ok(())
+    }
+
+    /// 统计缓存占用
+    pub fn usage(&self) -> Result<CacheUsage> {
+        let mut usage = CacheUsage::default();
+        for (path, metadata) in self.entries()? {
+            usage.entries += count_shard_entries(&path);
+            usage.bytes += metadata.len();
+        }
+        Ok(usage)
+    }
+
+    /// 清空缓存，返回删除的条目数
+    pub fn clear(&self) -> Result<usize> {
+        let mut removed = 0;
+        for (path, _) in self.entries()? {
+            removed += count_shard_entries(&path);
+            std::fs::remove_file(&path)
+                .with_context(|| format!("删除缓存分片失败: {}", path.display()))?;
+        }
+        Ok(removed)
+    }
+
+    /// 删除写入时间早于`max_age`之前的条目，返回删除的条目数
+    ///
+    /// 分片内逐条判断，清理后为空的分片文件直接删除。
+    pub fn prune_older_than(&self, max_age: Duration) -> Result<usize> {
+        let _guard = shard_write_lock().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let cutoff = unix_now().saturating_sub(max_age.as_secs());
+        let mut removed = 0;
+        for (path, _) in self.entries()? {
+            let Some(value) = read_shard_file(&path) else {
+                continue;
+            };
+            // 旧版本每条译文一个文件，不会再被读取，直接清理
+            if !value["entries"].is_object() {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("删除缓存分片失败: {}", path.display()))?;
+                removed += 1;
+                continue;
+            }
+            let mut entries = parse_shard_entries(&value);
+            let before = entries.len();
+            entries.retain(|_, entry| entry.updated >= cutoff);
+            if entries.len() == before {
+                continue;
+            }
+            removed += before - entries.len();
+
+            if entries.is_empty() {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("删除缓存分片失败: {}", path.display()))?;
+                continue;
+            }
+            let mut value = value;
+            value["entries"] = render_shard_entries(&entries);
+            write_file_atomically(&path, &value.to_string())?;
+        }
+        Ok(removed)
+    }
+
+    /// 列出所有缓存分片文件及其元数据
+    fn entries(&self) -> Result<Vec<(PathBuf, std::fs::Metadata)>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)
+            .with_context(|| format!("读取缓存目录失败: {}", self.dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(CACHE_ENTRY_EXTENSION) {
+                continue;
+            }
+            let metadata = std::fs::metadata(&path)
+                .with_context(|| format!("读取缓存分片失败: {}", path.display()))?;
+            if metadata.is_file() {
+                entries.push((path, metadata));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// 本作用域、指定目标语言的分片文件路径
+    fn shard_path(&self, target_lang: &str) -> PathBuf {
+        let key = format!("{}\u{0}{}", self.scope, target_lang);
+        self.dir
+            .join(format!("{:016x}.{}", stable_hash(&key), CACHE_ENTRY_EXTENSION))
+    }
+}
+
+/// 读取分片文件为JSON
+fn read_shard_file(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// 分片文件中的条目数；无法解析或旧版本的单条目文件计为1条
+fn count_shard_entries(path: &Path) -> usize {
+    read_shard_file(path)
+        .and_then(|value| value["entries"].as_object().map(serde_json::Map::len))
+        .unwrap_or(1)
+}
+
+/// 将分片条目渲染为JSON对象
+fn render_shard_entries(entries: &ShardEntries) -> serde_json::Value {
+    entries
+        .iter()
+        .map(|(text, entry)| {
+            (text.clone(), json!({ "translation": entry.translation, "updated": entry.updated }))
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// 取出分片中的条目，格式不符的条目忽略
+fn parse_shard_entries(value: &serde_json::Value) -> ShardEntries {
+    let Some(entries) = value["entries"].as_object() else {
+        return ShardEntries::new();
+    };
+    entries
+        .iter()
+        .filter_map(|(text, entry)| {
+            Some((
+                text.clone(),
+                ShardEntry {
+                    translation: entry["translation"].as_str()?.to_string(),
+                    updated: entry["updated"].as_u64().unwrap_or_default(),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// 先写临时文件再重命名；临时文件名带进程号，多个进程同时写同一分片时互不干扰
+fn write_file_atomically(path: &Path, content: &str) -> Result<()> {
+    let temp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&temp_path, content)
+        .with_context(|| format!("写入缓存分片失败: {}", temp_path.display()))?;
+    std::fs::rename(&temp_path, path)
+        .with_context(|| format!("写入缓存分片失败: {}", path.display()))
+}
+
+/// 同一次运行内跨页面共享的内存译文字典
+///
+/// 批量翻译一个站点时导航、页脚等文本在各页面大量重复，一个页面翻过的文本在后续页面
+/// 直接复用。与磁盘缓存正交：即使关闭持久缓存，同批任务内也会去重。
+#[derive(Debug, Default)]
+pub struct SharedTranslations {
+    /// 键为（目标语言, 原文）
+    entries: Mutex<HashMap<(String, String), String>>,
+}
+
+impl SharedTranslations {
+    /// 创建空字典
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 查询一组文本中已有译文的条目，返回「原文→译文」映射
+    pub fn lookup(&self, texts: &[String], target_lang: &str) -> HashMap<String, String> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        texts
+            .iter()
+            .filter_map(|text| {
+                entries
+                    .get(&(target_lang.to_string(), text.clone()))
+                    .map(|translation| (text.clone(), translation.clone()))
+            })
+            .collect()
+    }
+
+    /// 记录一组新译文
+    pub fn insert_many(&self, translations: &[(String, String)], target_lang: &str) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        for (text, translation) in translations {
+            entries.insert((target_lang.to_string(), text.clone()), translation.clone());
+        }
+    }
+
+    /// 已记录的条目数
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 等待中的译文，由负责请求的任务完成后分发；该任务失败或放弃时为`None`
+pub type PendingTranslation = Shared<oneshot::Receiver<Option<String>>>;
+
+/// 在飞翻译请求的登记键：（翻译来源作用域, 目标语言, 原文）
+type InFlightKey = (String, String, String);
+
+/// 进程内在飞翻译请求的去重登记表
+///
+/// 批量模式并发翻译多个文件、库用户并发调用或快速重跑时，同一文本可能同时被多次请求。
+/// 先登记的任务负责请求API，其余任务等待其结果，相同文本+语言的并发请求只打一次API。
+#[derive(Debug, Default)]
+pub struct InFlightTranslations {
+    pending: Mutex<HashMap<InFlightKey, PendingTranslation>>,
+}
+
+impl InFlightTranslations {
+    /// 创建空登记表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 进程级共享的登记表
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<InFlightTranslations> = OnceLock::new();
+        GLOBAL.get_or_init(Self::new)
+    }
+
+    /// 登记一组待翻译文本：已在飞的文本返回其等待句柄，其余由调用方负责翻译
+    pub fn claim(&self, scope: &str, target_lang: &str, texts: &[String]) -> InFlightClaim<'_> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let mut claim = InFlightClaim {
+            registry: self,
+            owned: Vec::new(),
+            waiting: Vec::new(),
+        };
+        for (index, text) in texts.iter().enumerate() {
+            let key = (scope.to_string(), target_lang.to_string(), text.clone());
+            match pending.get(&key) {
+                Some(translation) => claim.waiting.push((index, translation.clone())),
+                None => {
+                    let (sender, receiver) = oneshot::channel();
+                    pending.insert(key.clone(), receiver.shared());
+                    claim.owned.push((index, key, sender));
+                }
+            }
+        }
+        claim
+    }
+
+    /// 在飞的文本数
+    pub fn len(&self) -> usize {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// 是否没有在飞的文本
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 一次登记的结果
+///
+/// 调用方翻译完[`owned_indices`](Self::owned_indices)中的文本后调用[`complete`](Self::complete)
+/// 分发译文，再等待[`waiting`](Self::waiting)中的句柄；先分发再等待，避免任务间互相等待。
+/// 未调用`complete`就被丢弃时（如翻译出错提前返回），等待者得到`None`。
+pub struct InFlightClaim<'a> {
+    registry: &'a InFlightTranslations,
+    /// 由本任务负责翻译的文本：下标、登记键和分发端
+    owned: Vec<(usize, InFlightKey, oneshot::Sender<Option<String>>)>,
+    /// 正由其它任务翻译的文本下标及等待句柄
+    pub waiting: Vec<(usize, PendingTranslation)>,
+}
+
+impl InFlightClaim<'_> {
+    /// 由本任务负责翻译的文本下标，按登记顺序
+    pub fn owned_indices(&self) -> Vec<usize> {
+        self.owned.iter().map(|(index, _, _)| *index).collect()
+    }
+
+    /// 分发译文并注销登记，`translations`与[`owned_indices`](Self::owned_indices)一一对应；空译文视为失败
+    pub fn complete(mut self, translations: &[String]) {
+        let owned = std::mem::take(&mut self.owned);
+        let mut pending = self.registry.pending.lock().unwrap_or_else(|e| e.into_inner());
+        for (position, (_, key, sender)) in owned.into_iter().enumerate() {
+            pending.remove(&key);
+            let translation = translations.get(position).filter(|translation| !translation.is_empty());
+            let _ = sender.send(translation.cloned());
+        }
+    }
+}
+
+impl Drop for InFlightClaim<'_> {
+    fn drop(&mut self) {
+        if self.owned.is_empty() {
+            return;
+        }
+        let mut pending = self.registry.pending.lock().unwrap_or_else(|e| e.into_inner());
+        for (_, key, _) in self.owned.drain(..) {
+            pending.remove(&key);
+        }
+    }
+}
+
+/// 跨版本稳定的FNV-1a 64位哈希，用于生成持久化的缓存键
+pub(crate) fn stable_hash(content: &str) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    content.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache(name: &str) -> TranslationCache {
+        let dir = std::env::temp_dir().join(format!("translation-cli-cache-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        TranslationCache::new(dir)
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let cache = temp_cache("put-get");
+        assert_eq!(cache.get("Hello", "zh"), None);
+
+        cache.put("Hello", "zh", "你好").unwrap();
+        assert_eq!(cache.get("Hello", "zh"), Some("你好".to_string()));
+        assert_eq!(cache.get("Hello", "ja"), None);
+
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_usage_clear_and_prune() {
+        let cache = temp_cache("manage");
+        assert_eq!(cache.usage().unwrap(), CacheUsage::default());
+
+        cache.put("One", "zh", "一").unwrap();
+        cache.put("Two", "zh", "二").unwrap();
+        let usage = cache.usage().unwrap();
+        assert_eq!(usage.entries, 2);
+        assert!(usage.bytes > 0);
+
+        // 新条目不应被按时间清理
+        assert_eq!(cache.prune_older_than(Duration::from_secs(3600)).unwrap(), 0);
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert_eq!(cache.usage().unwrap().entries, 0);
+
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_stable_hash_is_deterministic() {
+        assert_eq!(stable_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash("zh\u{0}Hello"), stable_hash("zh\u{0}Hello"));
+        assert_ne!(stable_hash("zh\u{0}Hello"), stable_hash("ja\u{0}Hello"));
+    }
+
+    #[test]
+    fn test_scoped_entries_do_not_mix() {
+        let cache = temp_cache("scope");
+        let api_a = cache.clone().with_scope("https://a.example.com/translate");
+        let api_b = cache.clone().with_scope("https://b.example.com/translate");
+
+        api_a.put("Hello", "zh", "你好").unwrap();
+        assert_eq!(api_a.get("Hello", "zh"), Some("你好".to_string()));
+        assert_eq!(api_b.get("Hello", "zh"), None);
+        assert_eq!(cache.get("Hello", "zh"), None);
+
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_prefetch_and_put_many() {
+        let cache = temp_cache("prefetch");
+        let texts = vec!["One".to_string(), "Two".to_string(), "One".to_string(), "Three".to_string()];
+        assert!(cache.prefetch(&texts, "zh").is_empty());
+
+        let written = cache
+            .put_many(&[("One".to_string(), "一".to_string()), ("Two".to_string(), "二".to_string())], "zh")
+            .unwrap();
+        assert_eq!(written, 2);
+
+        let prefetched = cache.prefetch(&texts, "zh");
+        assert_eq!(prefetched.len(), 2);
+        assert_eq!(prefetched.get("One").map(String::as_str), Some("一"));
+        assert_eq!(prefetched.get("Three"), None);
+        assert!(cache.prefetch(&texts, "ja").is_empty());
+        assert_eq!(cache.get("Two", "zh"), Some("二".to_string()));
+
+        // 同一来源同一语言的条目写入同一个分片文件
+        cache.put_many(&[("Three".to_string(), "三".to_string())], "zh").unwrap();
+        assert_eq!(cache.entries().unwrap().len(), 1);
+        assert_eq!(cache.prefetch(&texts, "zh").len(), 3);
+
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_shared_translations_lookup() {
+        let shared = SharedTranslations::new();
+        assert!(shared.is_empty());
+        shared.insert_many(&[("Home".to_string(), "首页".to_string())], "zh");
+
+        let texts = vec!["Home".to_string(), "About".to_string()];
+        let found = shared.lookup(&texts, "zh");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found.get("Home").map(String::as_str), Some("首页"));
+        assert!(shared.lookup(&texts, "ja").is_empty());
+        assert_eq!(shared.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_claim_shares_result() {
+        let registry = InFlightTranslations::new();
+        let texts = vec!["Home".to_string(), "About".to_string()];
+        let first = registry.claim("api", "zh", &texts);
+        assert_eq!(first.owned_indices(), vec![0, 1]);
+
+        // 同一作用域和语言的文本等待首个任务；其它语言不受影响
+        let second = registry.claim("api", "zh", &texts[..1]);
+        assert!(second.owned_indices().is_empty());
+        assert_eq!(second.waiting.len(), 1);
+        assert_eq!(registry.claim("api", "ja", &texts[..1]).owned_indices(), vec![0]);
+
+        first.complete(&["首页".to_string(), String::new()]);
+        let pending = second.waiting[0].1.clone();
+        assert_eq!(pending.await.unwrap(), Some("首页".to_string()));
+        assert!(registry.is_empty());
+
+        // 未完成就放弃时等待者得到None
+        let abandoned = registry.claim("api", "zh", &texts[..1]);
+        let waiter = registry.claim("api", "zh", &texts[..1]);
+        drop(abandoned);
+        let pending = waiter.waiting[0].1.clone();
+        assert!(pending.await.ok().flatten().is_none());
+        assert!(registry.is_empty());
+    }
+}
@@ -3,21 +3,89 @@
 //! 实现索引模式的高性能翻译功能，支持并发批处理
 
 // 标准库导入
-// (无直接标准库导入)
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::Duration;
 
 // 第三方crate导入
 use anyhow::{Context, Result};
-use futures::future::join_all;
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::future::{join_all, try_join_all};
+use futures::stream::{self, Stream, StreamExt};
 use html5ever::parse_document;
 use html5ever::tendril::TendrilSink;
 use markup5ever_rcdom::RcDom;
 use regex::Regex;
 use reqwest::Client;
 use serde_json::json;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 // 本地模块导入
-use crate::html_processor::{extract_translatable_texts, apply_translations_to_dom, serialize_dom_to_html};
+use crate::api_constants::{api_config, performance_config, service_config};
+use crate::circuit_breaker::CircuitBreaker;
+use crate::shutdown::ShutdownSignal;
+use crate::config::{ApiProtocol, LocalTranslationConfig};
+use crate::feed_processor::{apply_feed_translations, collect_feed_texts};
+use crate::markdown_processor::{apply_markdown_translations, collect_markdown_texts};
+use crate::symbol_protection::{protect_symbols, restore_symbols, ProtectedText};
+use crate::pii_redaction::{restore_redactions, RedactedText};
+use crate::html_entities::{decode_html_entities, reencode_html_entities};
+use crate::brand_protection::restore_brand_casing;
+use crate::translation_cache::{InFlightTranslations, TranslationCache};
+use crate::dictionary::export_translation_pairs;
+use crate::review_export::{write_review_csv, write_review_file};
+use crate::source_map::write_source_map;
+use crate::utils::write_output_bytes;
+use crate::coverage_report::write_coverage_report;
+use crate::quality_check::{find_suspicious_translations, write_quality_report};
+use crate::error::{suggestion_for, TranslationError, EMPTY_TRANSLATIONS_SUGGESTION};
+use crate::html_processor::{
+    apply_translations_to_dom_with_options, apply_translations_to_source, ensure_utf8_charset,
+    ensure_utf8_charset_in_source, extract_prioritized_texts, extract_texts_with_context, is_xhtml_document, to_xhtml_void_elements,
+    extract_translatable_texts_with_options, parse_html_fragment, serialize_dom_snapshot, serialize_fragment_snapshot,
+    serialize_dom_to_html, serialize_fragment_to_html, split_html_into_chunks, TextPriority,
+    CHUNK_PLACEHOLDER,
+};
+
+/// 进程级共享的HTTP客户端，按请求超时和TCP keep-alive间隔区分
+static HTTP_CLIENTS: OnceLock<Mutex<HashMap<(Duration, Duration), Client>>> = OnceLock::new();
+
+/// 获取使用默认超时和keep-alive设置的可复用HTTP客户端
+///
+/// 首次调用时创建客户端，之后的调用返回同一连接池的句柄（`Client`内部为`Arc`，
+/// clone开销很小），从而在反复翻译时复用TCP连接和TLS会话。
+pub fn shared_http_client() -> Result<Client> {
+    shared_http_client_with(
+        Duration::from_secs(service_config::REQUEST_TIMEOUT_SECONDS),
+        Duration::from_secs(service_config::TCP_KEEPALIVE_SECONDS),
+    )
+}
+
+/// 获取使用配置中请求超时和keep-alive设置的可复用HTTP客户端
+///
+/// 设置相同的配置共用同一个客户端和连接池。
+pub fn shared_http_client_for(config: &LocalTranslationConfig) -> Result<Client> {
+    shared_http_client_with(config.request_timeout(), config.tcp_keepalive())
+}
+
+fn shared_http_client_with(timeout: Duration, tcp_keepalive: Duration) -> Result<Client> {
+    let mut clients = HTTP_CLIENTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(client) = clients.get(&(timeout, tcp_keepalive)) {
+        return Ok(client.clone());
+    }
+
+    let client = Client::builder()
+        .timeout(timeout)
+        .tcp_keepalive(tcp_keepalive)
+        .pool_idle_timeout(Duration::from_secs(service_config::POOL_IDLE_TIMEOUT_SECONDS))
+        .build()
+        .context("创建HTTP客户端失败")?;
+    clients.insert((timeout, tcp_keepalive), client.clone());
+    Ok(client)
+}
 
 /// 使用索引模式进行高性能翻译
 /// 
@@ -43,7 +111,8 @@ use crate::html_processor::{extract_translatable_texts, apply_translations_to_do
 /// 
 /// # Returns
 /// 
-/// * `Result<String>` - 成功时返回翻译后的HTML内容
+/// * `Result<TranslationOutcome>` - 成功时返回翻译后的HTML内容及翻译条数、失败项等元信息；
+///   只需要HTML时可使用[`translate_with_indexed_mode_to_string`]
 /// 
 /// # Errors
 /// 
@@ -53,19 +122,23 @@ use crate::html_processor::{extract_translatable_texts, apply_translations_to_do
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```rust,no_run
 /// use translation_cli::translator::translate_with_indexed_mode;
 /// 
+/// # async fn run() -> anyhow::Result<()> {
 /// let html = "<html><body><h1>Hello World</h1><p>Welcome to our website</p></body></html>";
-/// let result = translate_with_indexed_mode(
+/// let outcome = translate_with_indexed_mode(
 ///     html, 
 ///     "http://localhost:1188/translate", 
 ///     5, 
 ///     false
 /// ).await?;
 /// 
-/// assert!(result.contains("你好"));
-/// assert!(result.contains("欢迎"));
+/// assert!(outcome.html.contains("你好"));
+/// assert!(outcome.html.contains("欢迎"));
+/// assert!(outcome.failed_texts.is_empty());
+/// # Ok(())
+/// # }
 /// ```
 /// 
 /// # Performance
@@ -79,200 +152,3125 @@ pub async fn translate_with_indexed_mode(
     api_url: &str,
     concurrent_batches: usize,
     verbose: bool,
+) -> Result<TranslationOutcome> {
+    let config = LocalTranslationConfig::new()
+        .with_api_url(api_url)
+        .with_concurrent_batches(concurrent_batches);
+    translate_with_outcome(html_content, &config, verbose).await
+}
+
+/// 与[`translate_with_indexed_mode`]相同，只返回翻译后的HTML字符串
+pub async fn translate_with_indexed_mode_to_string(
+    html_content: &str,
+    api_url: &str,
+    concurrent_batches: usize,
+    verbose: bool,
+) -> Result<String> {
+    translate_with_indexed_mode(html_content, api_url, concurrent_batches, verbose)
+        .await
+        .map(|outcome| outcome.html)
+}
+
+/// 一次HTML翻译的结果及元信息
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TranslationOutcome {
+    /// 翻译后的HTML
+    pub html: String,
+    /// 提取到的待翻译文本数
+    pub total: usize,
+    /// 得到非空译文的文本数
+    pub succeeded: usize,
+    /// 未得到译文的原文（按文档出现顺序）
+    pub failed_texts: Vec<String>,
+}
+
+impl TranslationOutcome {
+    /// 成功率（0.0~1.0），没有待翻译文本时为1.0
+    pub fn success_rate(&self) -> f64 {
+        self.report().success_rate()
+    }
+
+    /// 转换为成功率统计
+    pub fn report(&self) -> TranslationReport {
+        TranslationReport {
+            total: self.total,
+            translated: self.succeeded,
+        }
+    }
+}
+
+/// 使用完整配置进行索引模式翻译
+///
+/// 与[`translate_with_indexed_mode`]流程相同，额外支持配置中的后处理选项
+/// （如术语一致性检查）。
+pub async fn translate_with_config(
+    html_content: &str,
+    config: &LocalTranslationConfig,
+    verbose: bool,
 ) -> Result<String> {
+    translate_with_report(html_content, config, verbose)
+        .await
+        .map(|(html, _)| html)
+}
+
+/// 使用完整配置翻译HTML，同时返回翻译成功率统计
+pub async fn translate_with_report(
+    html_content: &str,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+) -> Result<(String, TranslationReport)> {
+    let outcome = translate_with_outcome(html_content, config, verbose).await?;
+    let report = outcome.report();
+    Ok((outcome.html, report))
+}
+
+/// 使用完整配置翻译HTML，返回包含失败项的结构化结果
+pub async fn translate_with_outcome(
+    html_content: &str,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+) -> Result<TranslationOutcome> {
+    if config.is_stream_large() && html_content.len() > performance_config::STREAM_LARGE_THRESHOLD_BYTES {
+        // 分块翻译内部会以关闭分块的配置回调本函数，需装箱打断递归的future类型
+        let chunked = translate_large_html(html_content, config, performance_config::STREAM_CHUNK_SIZE_BYTES, verbose);
+        return Box::pin(chunked).await;
+    }
+
     // 1. 解析HTML
-    let dom = parse_document(RcDom::default(), Default::default())
-        .from_utf8()
-        .read_from(&mut html_content.as_bytes())
-        .map_err(|e| anyhow::anyhow!("HTML解析失败: {:?}", e))?;
+    let dom = parse_input_html(config, html_content)?;
 
     // 2. 提取所有可翻译文本
-    let texts = extract_translatable_texts(&dom);
+    let options = config.html_processing_options();
+    let texts = extract_translatable_texts_with_options(&dom, &options);
 
     if verbose {
         info!("📝 提取到 {} 个可翻译文本", texts.len());
     }
 
     if texts.is_empty() {
-        return Ok(html_content.to_string());
+        return Ok(TranslationOutcome {
+            html: html_content.to_string(),
+            ..TranslationOutcome::default()
+        });
+    }
+
+    // 3. 按优先级划分翻译阶段：启用时先翻译可见区域文本
+    let phases: Vec<Vec<String>> = if config.is_priority_first() {
+        let (high, normal): (Vec<_>, Vec<_>) = extract_prioritized_texts(&dom, &options)
+            .into_iter()
+            .partition(|t| t.priority == TextPriority::High);
+        if verbose {
+            info!("⭐ 高优先级文本 {} 个，其余文本 {} 个", high.len(), normal.len());
+        }
+        [high, normal]
+            .into_iter()
+            .map(|group| group.into_iter().map(|t| t.text).collect::<Vec<_>>())
+            .filter(|group| !group.is_empty())
+            .collect()
+    } else {
+        vec![texts.clone()]
+    };
+
+    let phase_count = phases.len();
+    let mut dom = dom;
+    let mut report = TranslationReport::default();
+    let mut applied_texts = Vec::new();
+    let mut applied_translations = Vec::new();
+    let mut adopted = HashMap::new();
+    for (phase_index, phase_texts) in phases.into_iter().enumerate() {
+        // 4. 使用索引标记批量翻译
+        let (mut translations, phase_report) =
+            indexed_batch_translation_with_report(phase_texts.clone(), config, verbose).await?;
+        report = report.merge(phase_report);
+
+        // 术语一致性归一化，后续阶段沿用前面阶段已写入DOM的译文
+        apply_consistency(config, &mut adopted, &phase_texts, &mut translations, verbose);
+
+        export_dictionary(config, &phase_texts, &translations, verbose)?;
+
+        // 5. 应用翻译结果到DOM
+        dom = apply_translations_to_dom_with_options(dom, &phase_texts, &translations, &options)?;
+        applied_texts.extend(phase_texts);
+        applied_translations.extend(translations);
+
+        // 高优先级阶段完成后先写出预览
+        if phase_index + 1 < phase_count {
+            if let Some(preview_path) = config.preview_output() {
+                let preview = if config.is_fragment() {
+                    serialize_fragment_snapshot(&dom)?
+                } else {
+                    serialize_dom_snapshot(&dom)?
+                };
+                write_output_bytes(preview_path, preview.as_bytes())
+                    .with_context(|| format!("写入预览文件失败: {}", preview_path.display()))?;
+                info!("👀 可见区域翻译完成，已写出预览: {}", preview_path.display());
+            }
+        }
     }
 
-    // 3. 使用索引标记批量翻译
-    let text_strings: Vec<String> = texts.iter().map(|t| t.clone()).collect();
-    let translations =
-        indexed_batch_translation(text_strings, api_url, concurrent_batches, verbose).await?;
+    // 校对文件和source map按文档出现顺序输出，不受优先级分阶段影响
+    let ordered: Vec<String> = {
+        let translated: HashMap<&str, &str> = applied_texts
+            .iter()
+            .zip(&applied_translations)
+            .map(|(text, translation)| (text.as_str(), translation.as_str()))
+            .collect();
+        texts
+            .iter()
+            .map(|text| translated.get(text.as_str()).copied().unwrap_or_default().to_string())
+            .collect()
+    };
+    export_review(config, &texts, &ordered, verbose)?;
+    export_review_csv(config, html_content, &texts, &ordered, verbose)?;
+    export_coverage_report(config, html_content, &texts, &ordered, verbose)?;
+    export_quality_report(config, &texts, &ordered, verbose)?;
 
-    // 4. 应用翻译结果到DOM
-    let translated_dom = apply_translations_to_dom(dom, &texts, &translations)?;
+    // 6. 序列化为HTML；保留格式时直接在原始源码上回写译文
+    // 输出总是UTF-8，同时修正字符集声明
+    let html = if config.is_preserve_formatting() {
+        let html = apply_translations_to_source(html_content, &applied_texts, &applied_translations, &options);
+        finish_source_output(config, html)
+    } else {
+        serialize_output_html(config, html_content, dom)?
+    };
+    export_source_map(config, html_content, &html, &texts, &ordered, verbose)?;
 
-    // 5. 序列化为HTML
-    serialize_dom_to_html(translated_dom)
+    let failed_texts = texts
+        .iter()
+        .zip(&ordered)
+        .filter(|(_, translation)| translation.trim().is_empty())
+        .map(|(text, _)| text.clone())
+        .collect();
+    Ok(TranslationOutcome {
+        html,
+        total: report.total,
+        succeeded: report.translated,
+        failed_texts,
+    })
 }
 
-/// 高性能索引标记翻译
-pub async fn indexed_batch_translation(
-    texts: Vec<String>,
-    api_url: &str,
-    concurrent_batches: usize,
+/// 分块翻译超大HTML文档
+///
+/// 按[`split_html_into_chunks`]将`<body>`内容切成约`max_chunk_bytes`的片段，逐块解析、
+/// 翻译、序列化后释放DOM，骨架（`<head>`等其余部分）单独翻译，最后拼接。
+/// 无法安全切分时退回整体翻译。分块模式下不生成校对文件、source map和覆盖率报告。
+pub async fn translate_large_html(
+    html_content: &str,
+    config: &LocalTranslationConfig,
+    max_chunk_bytes: usize,
     verbose: bool,
-) -> Result<Vec<String>> {
-    if texts.is_empty() {
-        return Ok(vec![]);
+) -> Result<TranslationOutcome> {
+    let whole_document = config.clone().stream_large(false);
+    let chunks = match split_html_into_chunks(html_content, max_chunk_bytes) {
+        Some(chunks) => chunks,
+        None => {
+            warn!("⚠️ 文档无法按顶层节点安全切分，改为整体翻译");
+            return translate_with_outcome(html_content, &whole_document, verbose).await;
+        }
+    };
+    if config.review_output().is_some()
+        || config.review_csv().is_some()
+        || config.source_map_output().is_some()
+        || config.coverage_report().is_some()
+        || config.quality_report().is_some()
+    {
+        warn!("⚠️ 分块翻译模式下不生成校对文件、source map、覆盖率报告和质量报告");
     }
+    info!("🧩 大文档分块翻译: {} 字节切分为 {} 块", html_content.len(), chunks.chunks.len());
 
-    // 创建HTTP客户端
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .context("创建HTTP客户端失败")?;
+    let chunk_config = whole_document
+        .with_review_output(None)
+        .with_review_csv(None)
+        .with_source_map_output(None)
+        .with_coverage_report(None)
+        .with_quality_report(None);
+    let mut outcome = translate_with_outcome(&chunks.skeleton, &chunk_config, verbose).await?;
+    let skeleton_html = std::mem::take(&mut outcome.html);
 
-    // 将文本分成批次，每个批次包含多个文本项
-    let batch_size = std::cmp::max(5, texts.len() / concurrent_batches.max(1));
-    let batches: Vec<_> = texts
-        .chunks(batch_size)
-        .enumerate()
-        .map(|(batch_idx, chunk)| {
-            // 为每个批次创建索引标记的文本
-            let indexed_text = chunk
-                .iter()
-                .enumerate()
-                .map(|(i, text)| format!("[{}] {}", batch_idx * batch_size + i, text.trim()))
-                .collect::<Vec<_>>()
-                .join("\n");
-            (batch_idx, indexed_text, chunk.len())
-        })
-        .collect();
+    let mut translated_chunks = String::with_capacity(html_content.len());
+    let mut adopted = HashMap::new();
+    for (index, chunk) in chunks.chunks.iter().enumerate() {
+        let dom = parse_html_fragment(chunk);
+        let options = chunk_config.html_processing_options();
+        let texts = extract_translatable_texts_with_options(&dom, &options);
+        if verbose {
+            info!("🧩 第 {}/{} 块: {} 个可翻译文本", index + 1, chunks.chunks.len(), texts.len());
+        }
+        if texts.is_empty() {
+            translated_chunks.push_str(chunk);
+            continue;
+        }
 
-    if verbose {
-        info!(
-            "🚀 索引翻译: {} 个文本项分成 {} 个批次",
-            texts.len(),
-            batches.len()
+        let (mut translations, report) =
+            indexed_batch_translation_with_report(texts.clone(), &chunk_config, verbose).await?;
+        apply_consistency(config, &mut adopted, &texts, &mut translations, verbose);
+        export_dictionary(&chunk_config, &texts, &translations, verbose)?;
+        let dom = apply_translations_to_dom_with_options(dom, &texts, &translations, &options)?;
+        translated_chunks.push_str(&apply_void_element_style(config, html_content, serialize_fragment_to_html(dom)?));
+
+        outcome.total += report.total;
+        outcome.succeeded += report.translated;
+        outcome.failed_texts.extend(
+            texts
+                .into_iter()
+                .zip(&translations)
+                .filter(|(_, translation)| translation.trim().is_empty())
+                .map(|(text, _)| text),
         );
     }
 
-    // 并发处理所有批次
-    let tasks = batches.into_iter().map(|(batch_idx, indexed_text, count)| {
-        let client = client.clone();
-        let api_url = api_url.to_string();
-        let verbose = verbose;
+    outcome.html = skeleton_html.replacen(CHUNK_PLACEHOLDER, &translated_chunks, 1);
+    Ok(outcome)
+}
 
-        async move {
-            if verbose {
-                info!("处理批次 {}: {} 个文本项", batch_idx + 1, count);
-            }
+/// 配置了词典导出路径时，将本次翻译映射合并写入词典
+fn export_dictionary(
+    config: &LocalTranslationConfig,
+    texts: &[String],
+    translations: &[String],
+    verbose: bool,
+) -> Result<()> {
+    if let Some(path) = config.dictionary_output() {
+        let entries = export_translation_pairs(path, texts, translations)?;
+        if verbose {
+            info!("📖 已导出翻译词典: {} ({} 个词条)", path.display(), entries);
+        }
+    }
+    Ok(())
+}
 
-            let result = translate_indexed_batch(&client, &api_url, &indexed_text).await;
+/// 配置了校对文件路径时，写出原文/译文对照表
+fn export_review(
+    config: &LocalTranslationConfig,
+    texts: &[String],
+    translations: &[String],
+    verbose: bool,
+) -> Result<()> {
+    if let Some(path) = config.review_output() {
+        let entries = write_review_file(path, texts, translations)?;
+        if verbose {
+            info!("📋 已导出校对文件: {} ({} 条)", path.display(), entries);
+        }
+    }
+    Ok(())
+}
 
-            match &result {
-                Ok(translations) => {
-                    if verbose {
-                        info!(
-                            "✅ 批次 {} 完成: {} 个翻译",
-                            batch_idx + 1,
-                            translations.len()
-                        );
-                    }
-                }
-                Err(e) => {
-                    warn!("❌ 批次 {} 失败: {}", batch_idx + 1, e);
-                }
-            }
+/// 配置了CSV校对表路径时，写出带父标签、字符数比等元信息的校对表
+fn export_review_csv(
+    config: &LocalTranslationConfig,
+    html_content: &str,
+    texts: &[String],
+    translations: &[String],
+    verbose: bool,
+) -> Result<()> {
+    if let Some(path) = config.review_csv() {
+        let parent_tags: HashMap<String, String> =
+            extract_texts_with_context(&parse_input_html(config, html_content)?, &config.html_processing_options())
+                .into_iter()
+                .map(|extracted| (extracted.text, extracted.parent_tag))
+                .collect();
+        let parent_tags: Vec<String> = texts
+            .iter()
+            .map(|text| parent_tags.get(text).cloned().unwrap_or_default())
+            .collect();
+        let entries = write_review_csv(path, texts, translations, &parent_tags)?;
+        if verbose {
+            info!("📋 已导出CSV校对表: {} ({} 条)", path.display(), entries);
+        }
+    }
+    Ok(())
+}
 
-            result
+/// 译文后处理阶段的质量自检：发现可疑译文时输出警告，配置了报告路径时写出JSON质量报告
+fn export_quality_report(
+    config: &LocalTranslationConfig,
+    texts: &[String],
+    translations: &[String],
+    verbose: bool,
+) -> Result<()> {
+    let suspicious =
+        find_suspicious_translations(texts, translations, config.target_lang(), config.max_translation_ratio());
+    if !suspicious.is_empty() {
+        warn!("⚠️ 发现 {} 条可疑译文（目标语言文字占比过低、残留大段原文或超长）", suspicious.len());
+        for item in &suspicious {
+            debug!("可疑译文 #{} [{}]: {}", item.index, item.reason.code(), item.translation);
+        }
+    }
+    if let Some(path) = config.quality_report() {
+        write_quality_report(path, config.target_lang(), texts.len(), &suspicious)?;
+        if verbose {
+            info!("🔍 已导出质量报告: {} (可疑 {} 条)", path.display(), suspicious.len());
         }
-    });
+    }
+    Ok(())
+}
 
-    // 等待所有批次完成
-    let results = join_all(tasks).await;
+/// 配置了source map路径时，写出译文与原文的定位映射
+fn export_source_map(
+    config: &LocalTranslationConfig,
+    input: &str,
+    output: &str,
+    texts: &[String],
+    translations: &[String],
+    verbose: bool,
+) -> Result<()> {
+    if let Some(path) = config.source_map_output() {
+        let entries = write_source_map(path, input, output, texts, translations)?;
+        if verbose {
+            info!("🗺️ 已导出source map: {} ({} 条)", path.display(), entries);
+        }
+    }
+    Ok(())
+}
 
-    // 收集翻译结果
-    let mut final_translations = vec![String::new(); texts.len()];
-    let mut success_count = 0;
+/// 配置了覆盖率报告路径时，在原页面上标出已翻译、未翻译和被跳过的文本
+fn export_coverage_report(
+    config: &LocalTranslationConfig,
+    html_content: &str,
+    texts: &[String],
+    translations: &[String],
+    verbose: bool,
+) -> Result<()> {
+    if let Some(path) = config.coverage_report() {
+        let summary = write_coverage_report(path, html_content, texts, translations)?;
+        if verbose {
+            info!(
+                "🟩 已导出覆盖率报告: {} (已翻译 {}，未翻译 {}，已跳过 {})",
+                path.display(),
+                summary.translated,
+                summary.untranslated,
+                summary.skipped
+            );
+        }
+    }
+    Ok(())
+}
 
-    for result in results {
-        match result {
-            Ok(batch_translations) => {
-                for (global_index, translation) in batch_translations {
-                    if global_index < final_translations.len() {
-                        final_translations[global_index] = translation;
-                        success_count += 1;
-                    }
+/// 解析完整HTML文档为DOM
+fn parse_html_document(html_content: &str) -> Result<RcDom> {
+    parse_document(RcDom::default(), Default::default())
+        .from_utf8()
+        .read_from(&mut html_content.as_bytes())
+        .map_err(|e| anyhow::anyhow!("HTML解析失败: {:?}", e))
+}
+
+/// 流式翻译事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranslationEvent {
+    /// 文本提取完成
+    Extracted {
+        /// 可翻译文本总数
+        total: usize,
+    },
+    /// 某条译文就绪
+    Translated {
+        /// 文本下标
+        index: usize,
+        /// 原文
+        original: String,
+        /// 译文
+        translation: String,
+    },
+    /// 翻译完成
+    Completed {
+        /// 翻译后的完整HTML
+        html: String,
+    },
+    /// 翻译失败
+    Failed {
+        /// 错误描述
+        message: String,
+    },
+}
+
+/// 流式翻译HTML
+///
+/// 依次产出「提取完成」、每条「译文就绪」（按批次完成顺序）以及最终的「完成」或「失败」事件，
+/// 适合通过WebSocket/SSE实时推送给前端。翻译由返回的流驱动，丢弃流即取消翻译。
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use futures::StreamExt;
+/// use translation_cli::translator::{translate_stream, TranslationEvent};
+/// use translation_cli::LocalTranslationConfig;
+///
+/// # async fn run() {
+/// let config = LocalTranslationConfig::new();
+/// let mut events = Box::pin(translate_stream("<p>Hello World</p>", &config));
+/// while let Some(event) = events.next().await {
+///     if let TranslationEvent::Translated { index, translation, .. } = event {
+///         println!("[{}] {}", index, translation);
+///     }
+/// }
+/// # }
+/// ```
+pub fn translate_stream(
+    html_content: &str,
+    config: &LocalTranslationConfig,
+) -> impl Stream<Item = TranslationEvent> + Send + 'static {
+    let (sender, receiver) = unbounded();
+    let html_content = html_content.to_string();
+    let config = config.clone();
+
+    let work = async move {
+        let final_event = match run_translation_stream(&html_content, &config, &sender).await {
+            Ok(html) => TranslationEvent::Completed { html },
+            Err(e) => TranslationEvent::Failed {
+                message: format!("{:#}", e),
+            },
+        };
+        let _ = sender.unbounded_send(final_event);
+    };
+
+    // 接收端与驱动翻译的future一起轮询，翻译结束后发送端被丢弃，流随之结束
+    stream::select(
+        receiver,
+        stream::once(work).filter_map(|_| futures::future::ready(None)),
+    )
+}
+
+/// 执行流式翻译，DOM不跨越await持有以保证future可在线程间移动
+async fn run_translation_stream(
+    html_content: &str,
+    config: &LocalTranslationConfig,
+    sender: &UnboundedSender<TranslationEvent>,
+) -> Result<String> {
+    let options = config.html_processing_options();
+    let texts = extract_translatable_texts_with_options(&parse_input_html(config, html_content)?, &options);
+    let _ = sender.unbounded_send(TranslationEvent::Extracted { total: texts.len() });
+
+    if texts.is_empty() {
+        return Ok(html_content.to_string());
+    }
+
+    let client = shared_http_client_for(config)?;
+    let on_translated = |index: usize, translation: &str| {
+        let _ = sender.unbounded_send(TranslationEvent::Translated {
+            index,
+            original: texts[index].clone(),
+            translation: translation.to_string(),
+        });
+    };
+    let mut translations =
+        indexed_batch_translation_with_progress(&client, texts.clone(), config, false, &on_translated)
+            .await?;
+
+    apply_consistency(config, &mut HashMap::new(), &texts, &mut translations, false);
+    export_dictionary(config, &texts, &translations, false)?;
+    export_review(config, &texts, &translations, false)?;
+    export_review_csv(config, html_content, &texts, &translations, false)?;
+    export_coverage_report(config, html_content, &texts, &translations, false)?;
+    export_quality_report(config, &texts, &translations, false)?;
+
+    let html = if config.is_preserve_formatting() {
+        let html = apply_translations_to_source(html_content, &texts, &translations, &options);
+        finish_source_output(config, html)
+    } else {
+        let dom = apply_translations_to_dom_with_options(
+            parse_input_html(config, html_content)?,
+            &texts,
+            &translations,
+            &options,
+        )?;
+        serialize_output_html(config, html_content, dom)?
+    };
+    export_source_map(config, html_content, &html, &texts, &translations, false)?;
+    Ok(html)
+}
+
+/// 按配置将输入解析为完整文档或HTML片段
+fn parse_input_html(config: &LocalTranslationConfig, html_content: &str) -> Result<RcDom> {
+    if config.is_fragment() {
+        Ok(parse_html_fragment(html_content))
+    } else {
+        parse_html_document(html_content)
+    }
+}
+
+/// 序列化翻译后的DOM：完整文档修正字符集声明为UTF-8，片段原样按片段输出
+fn serialize_output_html(config: &LocalTranslationConfig, html_content: &str, dom: RcDom) -> Result<String> {
+    let html = if config.is_fragment() {
+        serialize_fragment_to_html(dom)?
+    } else {
+        ensure_utf8_charset(&dom);
+        serialize_dom_to_html(dom)?
+    };
+    Ok(apply_void_element_style(config, html_content, html))
+}
+
+/// 保留格式输出的收尾：完整文档修正字符集声明，片段不注入`<meta charset>`
+fn finish_source_output(config: &LocalTranslationConfig, html: String) -> String {
+    if config.is_fragment() {
+        html
+    } else {
+        ensure_utf8_charset_in_source(&html)
+    }
+}
+
+/// 按配置决定void元素的输出形式：显式指定时照办，否则输入为XHTML文档时改写为自闭合形式
+fn apply_void_element_style(config: &LocalTranslationConfig, html_content: &str, html: String) -> String {
+    if config.xhtml_output().unwrap_or_else(|| is_xhtml_document(html_content)) {
+        to_xhtml_void_elements(&html)
+    } else {
+        html
+    }
+}
+
+/// 术语译文冲突记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermConflict {
+    /// 归一化后的原文
+    pub original: String,
+    /// 各译文及其出现次数（按首次出现顺序）
+    pub variants: Vec<(String, usize)>,
+    /// 最终统一采用的译文
+    pub chosen: String,
+}
+
+/// 按`--enforce-consistency`统一一组译文并告警冲突
+///
+/// 先在本组内按[`enforce_translation_consistency`]归一，再用[`reuse_adopted_translations`]
+/// 沿用之前阶段或分块已采用的译文；`adopted`在同一文档的各组之间共享。
+fn apply_consistency(
+    config: &LocalTranslationConfig,
+    adopted: &mut HashMap<String, String>,
+    texts: &[String],
+    translations: &mut [String],
+    verbose: bool,
+) {
+    if !config.is_consistency_enforced() {
+        return;
+    }
+    let mut conflicts = enforce_translation_consistency(texts, translations);
+    conflicts.extend(reuse_adopted_translations(adopted, texts, translations));
+    for conflict in &conflicts {
+        warn!(
+            "⚠️ 术语译文不一致: '{}' -> {:?}，统一为 '{}'",
+            conflict.original, conflict.variants, conflict.chosen
+        );
+    }
+    if verbose {
+        info!("🔁 术语一致性检查完成: {} 处冲突", conflicts.len());
+    }
+}
+
+/// 按空白归一化原文，作为术语一致性的分组键
+fn consistency_key(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 沿用之前阶段或分块已采用的译文
+///
+/// 分阶段或分块翻译时，前面的译文已写入输出，无法再按多数改写；同一原文（按空白归一化）
+/// 在后面再次出现且译文不同时统一为已采用的译文。本组新出现的原文记入`adopted`。
+pub fn reuse_adopted_translations(
+    adopted: &mut HashMap<String, String>,
+    original_texts: &[String],
+    translations: &mut [String],
+) -> Vec<TermConflict> {
+    let mut conflicts: Vec<TermConflict> = Vec::new();
+    for (original, translation) in original_texts.iter().zip(translations.iter_mut()) {
+        if translation.is_empty() {
+            continue;
+        }
+        let key = consistency_key(original);
+        match adopted.get(&key) {
+            Some(chosen) if chosen != translation => {
+                match conflicts.iter_mut().find(|conflict| conflict.original == key) {
+                    Some(conflict) => match conflict.variants.iter_mut().find(|(text, _)| text == translation) {
+                        Some((_, count)) => *count += 1,
+                        None => conflict.variants.push((translation.clone(), 1)),
+                    },
+                    None => conflicts.push(TermConflict {
+                        original: key,
+                        variants: vec![(chosen.clone(), 1), (translation.clone(), 1)],
+                        chosen: chosen.clone(),
+                    }),
                 }
+                *translation = chosen.clone();
             }
-            Err(e) => {
-                warn!("批次翻译失败: {}", e);
+            Some(_) => {}
+            None => {
+                adopted.insert(key, translation.clone());
             }
         }
     }
+    conflicts
+}
 
-    if verbose {
-        let success_rate = success_count as f32 / texts.len() as f32 * 100.0;
-        info!(
-            "📊 索引翻译完成: 成功率 {:.1}% ({}/{})",
-            success_rate,
-            success_count,
-            texts.len()
-        );
+/// 对翻译结果做术语一致性归一化
+///
+/// 提取阶段已按去除首尾空白后的文本去重，属性与文本节点中的相同文本只保留一条；
+/// 仍会作为不同原文保留的是内部空白不同的写法（如文本节点中换行缩进的`Sign\n  in`与`Sign in`）。
+/// 这里将原文按空白归一化后分组，同一原文出现多个不同译文时，统一为出现次数最多的译文
+/// （次数相同时取最先出现的），并返回所有冲突记录。空译文（翻译失败）不参与统计。
+pub fn enforce_translation_consistency(
+    original_texts: &[String],
+    translations: &mut [String],
+) -> Vec<TermConflict> {
+    // 原文 -> (出现下标, 译文计数)
+    type TermGroup = (Vec<usize>, Vec<(String, usize)>);
+    let mut groups: HashMap<String, TermGroup> = HashMap::new();
+    let mut group_order = Vec::new();
+
+    for (index, (original, translation)) in original_texts.iter().zip(translations.iter()).enumerate() {
+        if translation.is_empty() {
+            continue;
+        }
+        let key = consistency_key(original);
+        let (indices, variants) = groups.entry(key.clone()).or_insert_with(|| {
+            group_order.push(key);
+            (Vec::new(), Vec::new())
+        });
+        indices.push(index);
+        match variants.iter_mut().find(|(text, _)| text == translation) {
+            Some((_, count)) => *count += 1,
+            None => variants.push((translation.clone(), 1)),
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    for key in group_order {
+        let (indices, variants) = &groups[&key];
+        if variants.len() < 2 {
+            continue;
+        }
+
+        let mut chosen = &variants[0];
+        for variant in variants.iter().skip(1) {
+            if variant.1 > chosen.1 {
+                chosen = variant;
+            }
+        }
+        let chosen = chosen.0.clone();
+
+        for &index in indices {
+            translations[index] = chosen.clone();
+        }
+
+        conflicts.push(TermConflict {
+            original: key,
+            variants: variants.clone(),
+            chosen,
+        });
     }
 
-    Ok(final_translations)
+    conflicts
 }
 
-/// 翻译单个索引批次
-pub async fn translate_indexed_batch(
-    client: &reqwest::Client,
-    api_url: &str,
-    indexed_text: &str,
-) -> Result<Vec<(usize, String)>> {
-    // 发送翻译请求
-    let response = client
-        .post(api_url)
-        .json(&json!({
-            "text": indexed_text,
-            "source_lang": "auto",
-            "target_lang": "zh"
-        }))
-        .send()
+/// 翻译RSS/Atom feed
+///
+/// 提取feed中的标题、摘要和正文后复用索引标记批量翻译，
+/// 再重新组装feed XML，其它元素保持不变。
+pub async fn translate_feed(
+    feed_content: &str,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+) -> Result<String> {
+    translate_feed_with_report(feed_content, config, verbose)
         .await
-        .context("发送翻译请求失败")?;
+        .map(|(feed, _)| feed)
+}
 
-    if !response.status().is_success() {
-        anyhow::bail!("翻译API返回错误状态: {}", response.status());
+/// 翻译RSS/Atom feed，同时返回翻译成功率统计
+pub async fn translate_feed_with_report(
+    feed_content: &str,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+) -> Result<(String, TranslationReport)> {
+    let texts = collect_feed_texts(feed_content)?;
+
+    if verbose {
+        info!("📰 从feed中提取到 {} 个可翻译文本", texts.len());
     }
 
-    let response_text = response.text().await.context("读取响应失败")?;
+    if texts.is_empty() {
+        return Ok((feed_content.to_string(), TranslationReport::default()));
+    }
 
-    // 尝试解析JSON响应
-    let translated_text =
-        if let Ok(json_val) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            json_val
-                .get("data")
-                .or_else(|| json_val.get("text"))
-                .or_else(|| json_val.get("result"))
-                .and_then(|v| v.as_str())
-                .unwrap_or(&response_text)
-                .to_string()
-        } else {
-            response_text
-        };
+    let (translations, report) =
+        indexed_batch_translation_with_report(texts.clone(), config, verbose).await?;
+    export_dictionary(config, &texts, &translations, verbose)?;
+    export_review(config, &texts, &translations, verbose)?;
 
-    // 解析索引标记的翻译结果
-    let index_regex = Regex::new(r"^\[(\d+)\]\s*(.*)$").context("编译正则表达式失败")?;
-    let mut translations = Vec::new();
+    let translated = apply_feed_translations(feed_content, &texts, &translations)?;
+    export_source_map(config, feed_content, &translated, &texts, &translations, verbose)?;
+    Ok((translated, report))
+}
 
-    for line in translated_text.lines() {
-        if let Some(captures) = index_regex.captures(line.trim()) {
-            if let (Some(index_str), Some(text)) = (captures.get(1), captures.get(2)) {
-                if let Ok(index) = index_str.as_str().parse::<usize>() {
-                    let translated = text.as_str().trim();
-                    if !translated.is_empty() {
-                        translations.push((index, translated.to_string()));
-                    }
-                }
-            }
-        }
+/// 翻译Markdown文档
+///
+/// 仅翻译文本节点，代码块、链接URL、图片路径和HTML块保持不变。
+pub async fn translate_markdown(
+    markdown_content: &str,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+) -> Result<String> {
+    translate_markdown_with_report(markdown_content, config, verbose)
+        .await
+        .map(|(markdown, _)| markdown)
+}
+
+/// 翻译Markdown文档，同时返回翻译成功率统计
+pub async fn translate_markdown_with_report(
+    markdown_content: &str,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+) -> Result<(String, TranslationReport)> {
+    let texts = collect_markdown_texts(markdown_content);
+
+    if verbose {
+        info!("📝 从Markdown中提取到 {} 个可翻译文本", texts.len());
     }
 
-    Ok(translations)
-}
\ No newline at end of file
+    if texts.is_empty() {
+        return Ok((markdown_content.to_string(), TranslationReport::default()));
+    }
+
+    let (translations, report) =
+        indexed_batch_translation_with_report(texts.clone(), config, verbose).await?;
+    export_dictionary(config, &texts, &translations, verbose)?;
+    export_review(config, &texts, &translations, verbose)?;
+
+    let translated = apply_markdown_translations(markdown_content, &texts, &translations)?;
+    export_source_map(config, markdown_content, &translated, &texts, &translations, verbose)?;
+    Ok((translated, report))
+}
+
+/// 高性能索引标记翻译
+///
+/// 使用进程级共享的HTTP客户端，见[`shared_http_client_for`]。
+pub async fn indexed_batch_translation(
+    texts: Vec<String>,
+    api_url: &str,
+    concurrent_batches: usize,
+    verbose: bool,
+) -> Result<Vec<String>> {
+    let config = LocalTranslationConfig::new()
+        .with_api_url(api_url)
+        .with_concurrent_batches(concurrent_batches);
+    indexed_batch_translation_with_config(texts, &config, verbose).await
+}
+
+/// 按完整配置进行索引标记翻译
+///
+/// 支持配置中的批次级选项（如`fail_fast`），使用按配置中超时设置共享的HTTP客户端。
+pub async fn indexed_batch_translation_with_config(
+    texts: Vec<String>,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+) -> Result<Vec<String>> {
+    let client = shared_http_client_for(config)?;
+    indexed_batch_translation_with_client(&client, texts, config, verbose).await
+}
+
+/// 按完整配置进行索引标记翻译，同时返回成功率统计
+///
+/// 译文与[`indexed_batch_translation_with_config`]相同；失败批次对应的译文为空，
+/// 统计中只计入命中缓存或由翻译API实际返回了译文的文本。
+pub async fn indexed_batch_translation_with_report(
+    texts: Vec<String>,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+) -> Result<(Vec<String>, TranslationReport)> {
+    let client = shared_http_client_for(config)?;
+    translate_with_cache(&client, texts, config, verbose, &|_, _| {}).await
+}
+
+/// 翻译成功率统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TranslationReport {
+    /// 待翻译文本数
+    pub total: usize,
+    /// 得到非空译文的文本数
+    pub translated: usize,
+}
+
+impl TranslationReport {
+    /// 根据译文列表统计，空译文视为失败
+    pub fn from_translations(translations: &[String]) -> Self {
+        Self {
+            total: translations.len(),
+            translated: translations.iter().filter(|t| !t.trim().is_empty()).count(),
+        }
+    }
+
+    /// 合并多次翻译的统计
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            total: self.total + other.total,
+            translated: self.translated + other.translated,
+        }
+    }
+
+    /// 成功率（0.0~1.0），没有待翻译文本时为1.0
+    pub fn success_rate(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.translated as f64 / self.total as f64
+        }
+    }
+}
+
+/// 使用调用方提供的HTTP客户端进行索引标记翻译
+///
+/// 适用于长驻服务自行管理连接池的场景。
+///
+/// 启用缓存时先查询磁盘缓存，只翻译未命中的文本，成功的译文写回缓存。
+///
+/// 默认尽力翻译所有批次，失败批次对应的译文留空；启用`fail_fast`时，
+/// 首个失败批次会取消其余未完成的批次并整体返回错误。
+pub async fn indexed_batch_translation_with_client(
+    client: &Client,
+    texts: Vec<String>,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+) -> Result<Vec<String>> {
+    indexed_batch_translation_with_progress(client, texts, config, verbose, &|_, _| {}).await
+}
+
+/// 每条译文就绪时的回调，参数为文本下标和译文
+pub type TranslationProgress<'a> = &'a (dyn Fn(usize, &str) + Sync);
+
+/// 带进度回调的索引标记翻译
+///
+/// 与[`indexed_batch_translation_with_client`]相同，额外在缓存命中或每个批次完成时
+/// 对其中每条非空译文调用`on_translated`，用于实时推送译文。
+pub async fn indexed_batch_translation_with_progress(
+    client: &Client,
+    texts: Vec<String>,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+    on_translated: TranslationProgress<'_>,
+) -> Result<Vec<String>> {
+    translate_with_cache(client, texts, config, verbose, on_translated)
+        .await
+        .map(|(translations, _)| translations)
+}
+
+/// 先查覆盖译文、共享字典和磁盘缓存，只翻译未命中的文本，返回译文和成功率统计
+async fn translate_with_cache(
+    client: &Client,
+    texts: Vec<String>,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+    on_translated: TranslationProgress<'_>,
+) -> Result<(Vec<String>, TranslationReport)> {
+    // 缓存以原文为键、不区分hook：预处理hook改变了实际发送的文本，此时不读写缓存；
+    // 后处理hook在查完缓存后对译文执行，缓存和共享字典只保存API返回的译文
+    let cacheable = config.pre_process().is_none();
+    let cache = open_translation_cache(config).filter(|_| cacheable);
+    let shared = config.shared_translations().filter(|_| cacheable);
+    let overrides = config.translation_overrides();
+    let post_process = |translation: &str| match config.post_process() {
+        Some(hook) if !translation.is_empty() => hook.apply(translation),
+        _ => translation.to_string(),
+    };
+    let on_missed_translated = |index: usize, translation: &str| on_translated(index, &post_process(translation));
+
+    // 人工校对的覆盖译文优先；再查同批运行共享的内存字典，其余文本一次性预取磁盘缓存条目，
+    // 翻译过程查内存而非逐条读盘。覆盖译文已在cached中，不会被写回缓存
+    let target_lang = config.target_lang();
+    let mut cached: HashMap<String, String> = overrides
+        .map(|overrides| {
+            texts
+                .iter()
+                .filter_map(|text| Some((text.clone(), overrides.get(text.trim())?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(shared) = shared {
+        let unresolved: Vec<String> = texts.iter().filter(|text| !cached.contains_key(*text)).cloned().collect();
+        cached.extend(shared.lookup(&unresolved, target_lang));
+    }
+    if let Some(cache) = &cache {
+        let uncached: Vec<String> = texts.iter().filter(|text| !cached.contains_key(*text)).cloned().collect();
+        let from_disk = cache.prefetch(&uncached, target_lang);
+        if let Some(shared) = shared {
+            let entries: Vec<(String, String)> = from_disk.clone().into_iter().collect();
+            shared.insert_many(&entries, target_lang);
+        }
+        cached.extend(from_disk);
+    }
+    let mut final_translations = vec![String::new(); texts.len()];
+    let mut succeeded = vec![false; texts.len()];
+    // 未命中的文本去重，同一文本只请求一次；positions记录每个去重文本在输入中的所有下标
+    let mut missed_texts: Vec<String> = Vec::new();
+    let mut positions: Vec<Vec<usize>> = Vec::new();
+    let mut missed_slots: HashMap<String, usize> = HashMap::new();
+
+    for (index, text) in texts.into_iter().enumerate() {
+        match cached.get(&text) {
+            Some(translation) => {
+                // 人工校对的覆盖译文原样使用
+                let translation = if overrides.is_some_and(|overrides| overrides.get(text.trim()).is_some()) {
+                    translation.clone()
+                } else {
+                    post_process(translation)
+                };
+                on_translated(index, &translation);
+                final_translations[index] = translation;
+                succeeded[index] = true;
+            }
+            None => {
+                let slot = *missed_slots.entry(text.clone()).or_insert_with(|| {
+                    missed_texts.push(text);
+                    positions.push(Vec::new());
+                    missed_texts.len() - 1
+                });
+                positions[slot].push(index);
+            }
+        }
+    }
+
+    if verbose {
+        let missed_count: usize = positions.iter().map(Vec::len).sum();
+        info!(
+            "💾 缓存命中 {} 个，需翻译 {} 个",
+            final_translations.len() - missed_count,
+            missed_count
+        );
+    }
+
+    // 其它任务正在翻译的文本不重复请求，等待其结果；本任务负责的文本翻译完先分发再等待
+    let mut claim = InFlightTranslations::global().claim(&translation_cache_scope(config), target_lang, &missed_texts);
+    let owned = claim.owned_indices();
+    if verbose && !claim.waiting.is_empty() {
+        info!("⏳ {} 个文本正由其它任务翻译，等待其结果", claim.waiting.len());
+    }
+    let owned_texts: Vec<String> = owned.iter().map(|&slot| missed_texts[slot].clone()).collect();
+    let on_owned_translated = |owned_index: usize, translation: &str| {
+        for &index in &positions[owned[owned_index]] {
+            on_missed_translated(index, translation);
+        }
+    };
+    let (owned_translations, owned_succeeded) =
+        translate_batches(client, owned_texts, config, verbose, &on_owned_translated).await?;
+    let waiting = std::mem::take(&mut claim.waiting);
+    claim.complete(&owned_translations);
+
+    let mut missed_translations = vec![String::new(); missed_texts.len()];
+    let mut missed_succeeded = vec![false; missed_texts.len()];
+    let mut translated_here = vec![false; missed_texts.len()];
+    for ((&slot, translation), received) in owned.iter().zip(owned_translations).zip(owned_succeeded) {
+        missed_translations[slot] = translation;
+        missed_succeeded[slot] = received;
+        translated_here[slot] = true;
+    }
+
+    // 等待其它任务的结果；对方失败时由本任务补译
+    let mut unresolved = Vec::new();
+    for (slot, translation) in join_all(waiting.into_iter().map(|(slot, pending)| async move {
+        (slot, pending.await.ok().flatten())
+    }))
+    .await
+    {
+        match translation {
+            Some(translation) => {
+                for &index in &positions[slot] {
+                    on_missed_translated(index, &translation);
+                }
+                missed_translations[slot] = translation;
+                missed_succeeded[slot] = true;
+            }
+            None => unresolved.push(slot),
+        }
+    }
+    if !unresolved.is_empty() {
+        let retry_texts: Vec<String> = unresolved.iter().map(|&slot| missed_texts[slot].clone()).collect();
+        let on_retry_translated = |retry_index: usize, translation: &str| {
+            for &index in &positions[unresolved[retry_index]] {
+                on_missed_translated(index, translation);
+            }
+        };
+        let (retried, retried_succeeded) =
+            translate_batches(client, retry_texts, config, verbose, &on_retry_translated).await?;
+        for ((&slot, translation), received) in unresolved.iter().zip(retried).zip(retried_succeeded) {
+            missed_translations[slot] = translation;
+            missed_succeeded[slot] = received;
+            translated_here[slot] = true;
+        }
+    }
+
+    // 本任务新增的译文在结束时批量写回，重复文本只写一次
+    let mut new_entries = Vec::new();
+    for (slot, (text, translation)) in missed_texts.into_iter().zip(missed_translations).enumerate() {
+        if translated_here[slot] && !translation.is_empty() && !cached.contains_key(&text) {
+            cached.insert(text.clone(), translation.clone());
+            new_entries.push((text, translation.clone()));
+        }
+        let translation = post_process(&translation);
+        for &index in &positions[slot] {
+            final_translations[index] = translation.clone();
+            succeeded[index] = missed_succeeded[slot];
+        }
+    }
+    if let Some(shared) = shared {
+        shared.insert_many(&new_entries, target_lang);
+    }
+    if let Some(Err(e)) = cache.map(|cache| cache.put_many(&new_entries, target_lang)) {
+        warn!("⚠️ 写入翻译缓存失败: {:#}", e);
+    }
+
+    let report = TranslationReport {
+        total: final_translations.len(),
+        translated: succeeded.iter().filter(|&&received| received).count(),
+    };
+    Ok((final_translations, report))
+}
+
+/// 按配置打开翻译缓存，未启用或无法定位缓存目录时返回`None`
+fn open_translation_cache(config: &LocalTranslationConfig) -> Option<TranslationCache> {
+    if !config.is_cache_enabled() {
+        return None;
+    }
+
+    let cache = match config.cache_dir() {
+        Some(dir) => TranslationCache::new(dir),
+        None => match TranslationCache::open_default() {
+            Ok(cache) => cache,
+            Err(e) => {
+                warn!("⚠️ 翻译缓存不可用: {}", e);
+                return None;
+            }
+        },
+    };
+    Some(cache.with_scope(&translation_cache_scope(config)))
+}
+
+/// 翻译来源标识：API端点、协议、请求模板（模型名通常写在端点参数或模板中）和领域上下文
+///
+/// 同一文本经不同来源或在不同上下文下翻译的结果可能不同，切换其中任何一项都会使缓存不命中。
+fn translation_cache_scope(config: &LocalTranslationConfig) -> String {
+    format!(
+        "{}\u{0}{:?}\u{0}{}\u{0}{}\u{0}{}",
+        config.api_url(),
+        config.api_protocol(),
+        config.request_template().unwrap_or_default(),
+        config.response_path().unwrap_or_default(),
+        config.context().unwrap_or_default()
+    )
+}
+
+/// 将文本分批并发送到翻译API（不经过缓存），返回译文以及每条是否由API实际返回
+async fn translate_batches(
+    client: &Client,
+    texts: Vec<String>,
+    config: &LocalTranslationConfig,
+    verbose: bool,
+    on_translated: TranslationProgress<'_>,
+) -> Result<(Vec<String>, Vec<bool>)> {
+    if texts.is_empty() {
+        return Ok((vec![], vec![]));
+    }
+
+    let concurrent_batches = config.concurrent_batches();
+
+    // 用占位符保护emoji和特殊符号，避免被翻译API吞掉或改写
+    // 库用户的预处理hook最先执行，随后解码残留的HTML实体、将敏感信息替换为占位符，最后做符号保护
+    let redactions: Vec<RedactedText> = texts
+        .iter()
+        .map(|text| {
+            let prepared = match config.pre_process() {
+                Some(hook) => hook.apply(text.trim()),
+                None => text.trim().to_string(),
+            };
+            let prepared = decode_html_entities(&prepared);
+            match config.pii_redactor() {
+                Some(redactor) => redactor.redact(prepared.trim()),
+                None => RedactedText::plain(prepared.trim()),
+            }
+        })
+        .collect();
+    if verbose {
+        let redacted_count = redactions.iter().filter(|redacted| redacted.has_redactions()).count();
+        if redacted_count > 0 {
+            info!("🕶️ {} 个文本项中的敏感信息已替换为占位符", redacted_count);
+        }
+    }
+    let protected_texts: Vec<ProtectedText> = redactions
+        .iter()
+        .map(|redacted| protect_symbols(&redacted.text))
+        .collect();
+
+    // 将文本分成批次，每个批次包含多个文本项；索引标记使用全局下标，分批方式不影响结果回写
+    let batch_size = std::cmp::max(5, texts.len() / concurrent_batches.max(1));
+    let lengths: Vec<usize> = protected_texts
+        .iter()
+        .map(|protected| protected.text.chars().count())
+        .collect();
+    let mut planned = plan_batches(&lengths, batch_size, config.is_balance_batches());
+    if let Some(max_chars) = config.max_batch_chars() {
+        planned = split_batches_by_chars(planned, &lengths, max_chars);
+        let oversized = (0..lengths.len())
+            .filter(|&index| indexed_line_chars(index, lengths[index]) > max_chars)
+            .count();
+        if oversized > 0 {
+            warn!("⚠️ {} 个文本项本身超过单批字符上限 {}，将各自单独成批发送", oversized, max_chars);
+        }
+    }
+    let batches: Vec<_> = planned
+        .into_iter()
+        .enumerate()
+        .map(|(batch_idx, indices)| {
+            // 为每个批次创建索引标记的文本
+            let indexed_text = indices
+                .iter()
+                .map(|&index| format!("[{}] {}", index, protected_texts[index].text))
+                .collect::<Vec<_>>()
+                .join("\n");
+            (batch_idx, indexed_text, indices)
+        })
+        .collect();
+
+    if verbose {
+        info!(
+            "🚀 索引翻译: {} 个文本项分成 {} 个批次",
+            texts.len(),
+            batches.len()
+        );
+    }
+
+    // 并发处理所有批次；首批检测出的源语言由后续批次共享
+    let protected_ref = &protected_texts;
+    let redactions_ref = &redactions;
+    let texts_ref = &texts;
+    let detected_source_lang = OnceLock::new();
+    let detected_ref = &detected_source_lang;
+    let mut tasks: Vec<_> = batches.into_iter().map(|(batch_idx, indexed_text, indices)| {
+        let client = client.clone();
+        let config = config.clone();
+
+        async move {
+            if verbose {
+                info!("处理批次 {}: {} 个文本项", batch_idx + 1, indices.len());
+            }
+
+            let mut result =
+                translate_indexed_batch_with_retry(&client, &config, &indexed_text, detected_ref).await;
+
+            // 整批重试耗尽后逐条单独请求，尽量挽回能翻译的条目
+            if config.is_single_retry_fallback() {
+                if let Err(e) = &result {
+                    warn!("⚠️ 批次 {} 整体失败，逐条降级重译 {} 个文本项: {:#}", batch_idx + 1, indices.len(), e);
+                    let recovered =
+                        translate_texts_individually(&client, &config, &indices, protected_ref, detected_ref)
+                            .await;
+                    if !recovered.is_empty() {
+                        info!("🩹 批次 {} 逐条降级挽回 {}/{} 个文本项", batch_idx + 1, recovered.len(), indices.len());
+                        result = Ok(recovered);
+                    }
+                }
+            }
+
+            match &result {
+                Ok(translations) => {
+                    if verbose {
+                        info!(
+                            "✅ 批次 {} 完成: {} 个翻译",
+                            batch_idx + 1,
+                            translations.len()
+                        );
+                    }
+                    for (index, translation) in translations {
+                        if let Some(protected) = protected_ref.get(*index) {
+                            on_translated(
+                                *index,
+                                &restore_translation(
+                                    &config,
+                                    &texts_ref[*index],
+                                    translation,
+                                    protected,
+                                    &redactions_ref[*index],
+                                ),
+                            );
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!("❌ 批次 {} 失败: {}", batch_idx + 1, e);
+                }
+            }
+
+            result
+        }
+    }).collect();
+
+    // 先单独完成第一批，使其检测出的源语言能用于其余批次，避免混排文档各批次检测结果不一致
+    let mut results: Vec<Result<Vec<(usize, String)>>> = Vec::with_capacity(tasks.len());
+    if tasks.len() > 1 {
+        let first = tasks.remove(0).await;
+        if config.is_fail_fast() {
+            results.push(Ok(first.context("批次翻译失败，已按 --fail-fast 中止其余批次")?));
+        } else {
+            results.push(first);
+        }
+        if verbose {
+            if let Some(lang) = detected_source_lang.get() {
+                info!("🔎 检测到源语言 {}，后续批次沿用", lang);
+            }
+        }
+    }
+
+    // 等待其余批次完成；fail-fast模式下首个失败即中止，未完成的请求随future一起被丢弃
+    if config.is_fail_fast() {
+        results.extend(
+            try_join_all(tasks)
+                .await
+                .context("批次翻译失败，已按 --fail-fast 中止其余批次")?
+                .into_iter()
+                .map(Ok),
+        );
+    } else {
+        results.extend(join_all(tasks).await);
+    }
+
+    // 收集翻译结果
+    let mut final_translations = vec![String::new(); texts.len()];
+    let mut succeeded = vec![false; texts.len()];
+    let mut success_count = 0;
+    let mut failure_suggestion = None;
+
+    for result in results {
+        match result {
+            Ok(batch_translations) => {
+                for (global_index, translation) in batch_translations {
+                    if global_index < final_translations.len() {
+                        final_translations[global_index] = restore_translation(
+                            config,
+                            &texts[global_index],
+                            &translation,
+                            &protected_texts[global_index],
+                            &redactions[global_index],
+                        );
+                        succeeded[global_index] = true;
+                        success_count += 1;
+                    }
+                }
+            }
+            Err(e) => {
+                warn!("批次翻译失败: {}", e);
+                failure_suggestion = failure_suggestion.or_else(|| suggestion_for(&e));
+            }
+        }
+    }
+
+    enforce_translation_length(
+        client,
+        config,
+        &texts,
+        &protected_texts,
+        &redactions,
+        &mut final_translations,
+        &detected_source_lang,
+    )
+    .await;
+
+    // 全部失败时给出可操作的排查建议；请求都成功却没有译文通常是协议不匹配
+    if success_count == 0 {
+        let suggestion = failure_suggestion.unwrap_or(EMPTY_TRANSLATIONS_SUGGESTION);
+        warn!("💡 没有任何文本得到译文，建议: {}", suggestion);
+    }
+
+    if verbose {
+        let success_rate = success_count as f32 / texts.len() as f32 * 100.0;
+        info!(
+            "📊 索引翻译完成: 成功率 {:.1}% ({}/{})",
+            success_rate,
+            success_count,
+            texts.len()
+        );
+    }
+
+    Ok((final_translations, succeeded))
+}
+
+/// 计算译文与原文的字符数倍数，任一方为空时返回None
+pub fn translation_length_ratio(original: &str, translation: &str) -> Option<f64> {
+    let original_len = original.trim().chars().count();
+    let translation_len = translation.trim().chars().count();
+    if original_len == 0 || translation_len == 0 {
+        return None;
+    }
+    Some(translation_len as f64 / original_len as f64)
+}
+
+/// 找出译文长度超过原文`max_ratio`倍的条目下标
+fn find_overlong_translations(texts: &[String], translations: &[String], max_ratio: f64) -> Vec<usize> {
+    texts
+        .iter()
+        .zip(translations)
+        .enumerate()
+        .filter(|(_, (original, translation))| {
+            translation_length_ratio(original, translation).is_some_and(|ratio| ratio > max_ratio)
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// 按`--max-translation-ratio`约束译文长度
+///
+/// 配置了带`{context}`占位符的请求模板（LLM后端）时，在领域上下文中追加长度要求并逐条重译超长条目，
+/// 仅在重译结果更短时采用；仍超长的条目逐条告警，并由质量报告标记，便于人工调整界面文案。
+async fn enforce_translation_length(
+    client: &Client,
+    config: &LocalTranslationConfig,
+    texts: &[String],
+    protected_texts: &[ProtectedText],
+    redactions: &[RedactedText],
+    translations: &mut [String],
+    detected_source_lang: &OnceLock<String>,
+) {
+    let Some(max_ratio) = config.max_translation_ratio() else {
+        return;
+    };
+    let overlong = find_overlong_translations(texts, translations, max_ratio);
+    if overlong.is_empty() {
+        return;
+    }
+
+    let template = config.request_template();
+    if template.is_some_and(|template| !template.contains("{context}")) {
+        warn!("⚠️ 请求模板中没有 {{context}} 占位符，无法传递长度要求，跳过超长译文的重译");
+    } else if template.is_some() {
+        let hint = format!("译文长度不得超过原文的{}倍，请给出更简短的译法", max_ratio);
+        let context = match config.context() {
+            Some(context) => format!("{}\n{}", context, hint),
+            None => hint,
+        };
+        let shorten_config = config.clone().with_context(Some(context));
+        info!("✂️ {} 条译文超过原文的 {} 倍，请求更简短的重译", overlong.len(), max_ratio);
+        let retranslated =
+            translate_texts_individually(client, &shorten_config, &overlong, protected_texts, detected_source_lang).await;
+        for (index, translation) in retranslated {
+            let restored = restore_translation(
+                config,
+                &texts[index],
+                &translation,
+                &protected_texts[index],
+                &redactions[index],
+            );
+            if restored.chars().count() < translations[index].chars().count() {
+                translations[index] = restored;
+            }
+        }
+    }
+
+    let remaining = find_overlong_translations(texts, translations, max_ratio);
+    for &index in &remaining {
+        warn!(
+            "📏 译文超长 ({:.1}倍 > {}倍): {} → {}",
+            translation_length_ratio(&texts[index], &translations[index]).unwrap_or_default(),
+            max_ratio,
+            texts[index].trim(),
+            translations[index]
+        );
+    }
+    if !remaining.is_empty() {
+        warn!("📏 共 {} 条译文超过原文长度的 {} 倍", remaining.len(), max_ratio);
+    }
+}
+
+/// 还原译文中被保护的符号和脱敏的敏感信息，按配置还原内置品牌词的大小写
+///
+/// 库用户的后处理hook不在这里执行，而是在[`translate_with_cache`]查完缓存之后执行，
+/// 缓存中保存的始终是未经hook处理的译文。
+fn restore_translation(
+    config: &LocalTranslationConfig,
+    original: &str,
+    translation: &str,
+    protected: &ProtectedText,
+    redacted: &RedactedText,
+) -> String {
+    let mut restored = reencode_html_entities(
+        original,
+        &restore_redactions(&restore_symbols(translation, protected), redacted),
+    );
+    if config.is_builtin_brands_enabled() {
+        restored = restore_brand_casing(original, &restored);
+    }
+    restored
+}
+
+/// 逐条翻译文本（不带索引标记），每条只请求一次，返回成功的条目
+///
+/// 用于整批失败后的降级：畸形响应通常只由个别文本引起，单独请求能挽回其余条目。
+async fn translate_texts_individually(
+    client: &reqwest::Client,
+    config: &LocalTranslationConfig,
+    indices: &[usize],
+    protected_texts: &[ProtectedText],
+    detected_source_lang: &OnceLock<String>,
+) -> Vec<(usize, String)> {
+    let source_lang = detected_source_lang.get().map_or("auto", String::as_str);
+    let mut translations = Vec::new();
+    for &index in indices {
+        if let Some(Err(e)) = config.circuit_breaker().map(CircuitBreaker::check) {
+            warn!("逐条重译中止: {}", e);
+            break;
+        }
+        if let Some(limiter) = config.rate_limiter() {
+            limiter.acquire().await;
+        }
+        if let Some(Err(e)) = config.shutdown_signal().map(ShutdownSignal::check) {
+            warn!("逐条重译中止: {}", e);
+            break;
+        }
+        let result = request_translation(client, config, &protected_texts[index].text, source_lang).await;
+        if let Some(breaker) = config.circuit_breaker() {
+            match &result {
+                Ok(_) => breaker.record_success(),
+                Err(_) => breaker.record_failure(),
+            }
+        }
+        match result {
+            Ok((translation, _)) if !translation.trim().is_empty() => {
+                translations.push((index, translation.trim().to_string()));
+            }
+            Ok(_) => warn!("逐条重译第 {} 项返回空译文", index),
+            Err(e) => warn!("逐条重译第 {} 项失败: {:#}", index, e),
+        }
+    }
+    translations
+}
+
+/// 划分批次，返回每个批次包含的文本下标
+///
+/// 默认按出现顺序每`batch_size`个切分；均衡模式下批次数不变，按长度从长到短依次
+/// 分配给当前总字符数最少的批次，使各批次负载大致相等。批次内下标保持升序。
+fn plan_batches(lengths: &[usize], batch_size: usize, balance: bool) -> Vec<Vec<usize>> {
+    let batch_size = batch_size.max(1);
+    let indices: Vec<usize> = (0..lengths.len()).collect();
+    if !balance {
+        return indices.chunks(batch_size).map(<[usize]>::to_vec).collect();
+    }
+
+    let batch_count = lengths.len().div_ceil(batch_size);
+    let mut batches = vec![Vec::new(); batch_count];
+    let mut loads = vec![0usize; batch_count];
+
+    let mut by_length = indices;
+    by_length.sort_by_key(|&index| std::cmp::Reverse(lengths[index]));
+    for index in by_length {
+        let lightest = (0..batch_count)
+            .min_by_key(|&batch| (loads[batch], batches[batch].len()))
+            .expect("批次数量至少为1");
+        loads[lightest] += lengths[index];
+        batches[lightest].push(index);
+    }
+
+    for batch in &mut batches {
+        batch.sort_unstable();
+    }
+    batches.retain(|batch| !batch.is_empty());
+    batches
+}
+
+/// 按字符预算拆分批次，保证每个批次的请求文本（含`[n] `索引标记和换行）不超过`max_chars`
+///
+/// 批内顺序不变，累加超出预算时开始新批次；单条文本本身超出预算时独占一个批次。
+fn split_batches_by_chars(batches: Vec<Vec<usize>>, lengths: &[usize], max_chars: usize) -> Vec<Vec<usize>> {
+    let mut split = Vec::new();
+    for batch in batches {
+        let mut current: Vec<usize> = Vec::new();
+        let mut current_chars = 0;
+        for index in batch {
+            let line_chars = indexed_line_chars(index, lengths[index]);
+            // 非首行还需计入换行符
+            if !current.is_empty() && current_chars + 1 + line_chars > max_chars {
+                split.push(std::mem::take(&mut current));
+            }
+            current_chars = if current.is_empty() { line_chars } else { current_chars + 1 + line_chars };
+            current.push(index);
+        }
+        if !current.is_empty() {
+            split.push(current);
+        }
+    }
+    split
+}
+
+/// 索引批次中一行（`[n] 文本`）的字符数
+fn indexed_line_chars(index: usize, length: usize) -> usize {
+    format!("[{}] ", index).len() + length
+}
+
+/// 健康检查使用的探测文本
+pub const HEALTH_CHECK_TEXT: &str = "hello";
+
+/// 探测翻译API是否可用，返回探测文本的译文
+///
+/// 以与正式批次相同的请求格式翻译[`HEALTH_CHECK_TEXT`]，不重试、不使用缓存，
+/// 端点不可达、协议不匹配或译文为空时立即返回错误。
+pub async fn check_translation_api(config: &LocalTranslationConfig) -> Result<String> {
+    let client = shared_http_client_for(config)?;
+    let indexed_text = format!("[0] {}", HEALTH_CHECK_TEXT);
+
+    let translations = translate_indexed_batch(&client, config, &indexed_text)
+        .await
+        .context("翻译API健康检查失败")?;
+    let translation = translations
+        .into_iter()
+        .find(|(index, _)| *index == 0)
+        .map(|(_, text)| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("翻译API健康检查失败: 响应中没有探测文本的译文"))?;
+
+    if translation.eq_ignore_ascii_case(HEALTH_CHECK_TEXT) && !config.target_lang().starts_with("en") {
+        warn!("⚠️ 健康检查译文与原文相同，翻译API可能未生效");
+    }
+    Ok(translation)
+}
+
+/// 翻译单个索引批次，失败时按错误类别重试
+///
+/// 最多重试`max_retries`次：超时使用更长的退避，连接失败和不可重试的错误立即返回。
+/// `detected_source_lang`已有值时以其作为源语言，否则请求`auto`检测并记录API返回的结果。
+async fn translate_indexed_batch_with_retry(
+    client: &reqwest::Client,
+    config: &LocalTranslationConfig,
+    indexed_text: &str,
+    detected_source_lang: &OnceLock<String>,
+) -> Result<Vec<(usize, String)>> {
+    let base_delay = Duration::from_millis(service_config::RETRY_DELAY_BASE_MS);
+    let mut attempt: u32 = 0;
+
+    loop {
+        // 限速对重试同样生效，避免重试请求突破配额
+        if let Some(limiter) = config.rate_limiter() {
+            limiter.acquire().await;
+        }
+
+        // 收到中断信号后不再发起新请求（含重试），在途请求不受影响
+        if let Some(signal) = config.shutdown_signal() {
+            signal.check()?;
+        }
+
+        // 熔断打开时立即失败，不再发请求也不重试
+        if let Some(breaker) = config.circuit_breaker() {
+            breaker.check()?;
+        }
+
+        let source_lang = detected_source_lang.get().map_or("auto", String::as_str);
+        let error = match request_indexed_batch(client, config, indexed_text, source_lang).await {
+            Ok(response) => {
+                if let Some(breaker) = config.circuit_breaker() {
+                    breaker.record_success();
+                }
+                if let Some(lang) = response.detected_source_lang {
+                    let _ = detected_source_lang.set(lang);
+                }
+                return Ok(response.translations);
+            }
+            Err(e) => e,
+        };
+        if let Some(breaker) = config.circuit_breaker() {
+            breaker.record_failure();
+        }
+
+        attempt += 1;
+        let delay = error
+            .downcast_ref::<TranslationError>()
+            .filter(|_| attempt as usize <= config.max_retries())
+            .and_then(|e| e.retry_delay(attempt, base_delay));
+        match delay {
+            Some(delay) => {
+                warn!(
+                    "⏳ 翻译请求失败，{:?} 后重试 ({}/{}): {:#}",
+                    delay,
+                    attempt,
+                    config.max_retries(),
+                    error
+                );
+                tokio::time::sleep(delay).await;
+            }
+            None => return Err(error),
+        }
+    }
+}
+
+/// 翻译单个索引批次
+///
+/// 请求体默认为`{"text", "source_lang", "target_lang"}`，配置了请求模板时按模板渲染；
+/// 配置了响应路径时从该路径读取译文，否则依次尝试`data`、`text`、`result`字段。
+pub async fn translate_indexed_batch(
+    client: &reqwest::Client,
+    config: &LocalTranslationConfig,
+    indexed_text: &str,
+) -> Result<Vec<(usize, String)>> {
+    Ok(request_indexed_batch(client, config, indexed_text, "auto").await?.translations)
+}
+
+/// 单个索引批次的翻译结果
+struct IndexedBatchResponse {
+    /// 按全局下标标记的译文
+    translations: Vec<(usize, String)>,
+    /// API返回的检测到的源语言（`detected_source_language`字段）
+    detected_source_lang: Option<String>,
+}
+
+/// 以指定源语言翻译单个索引批次，同时解析API检测到的源语言
+async fn request_indexed_batch(
+    client: &reqwest::Client,
+    config: &LocalTranslationConfig,
+    indexed_text: &str,
+    source_lang: &str,
+) -> Result<IndexedBatchResponse> {
+    let items = parse_indexed_translations(indexed_text)?;
+
+    // Azure按数组逐条翻译，拆开索引标记后按顺序对应回下标
+    if config.api_protocol() == ApiProtocol::Azure {
+        let texts: Vec<&str> = items.iter().map(|(_, text)| text.as_str()).collect();
+        let (translated, detected_source_lang) =
+            request_azure_translation(client, config, &texts, source_lang).await?;
+        let translations = items
+            .iter()
+            .map(|(index, _)| *index)
+            .zip(translated)
+            .filter(|(_, translation)| !translation.trim().is_empty())
+            .collect();
+        return Ok(IndexedBatchResponse {
+            translations: strip_batch_residual_markers(&items, translations),
+            detected_source_lang,
+        });
+    }
+
+    let (translated_text, detected_source_lang) =
+        request_translation(client, config, indexed_text, source_lang).await?;
+
+    // 解析索引标记的翻译结果
+    Ok(IndexedBatchResponse {
+        translations: strip_batch_residual_markers(&items, parse_indexed_translations(&translated_text)?),
+        detected_source_lang,
+    })
+}
+
+/// 对批次中的每条译文清理残留的索引标记，清理后为空的条目视为未翻译
+fn strip_batch_residual_markers(
+    originals: &[(usize, String)],
+    translations: Vec<(usize, String)>,
+) -> Vec<(usize, String)> {
+    let originals: HashMap<usize, &str> = originals.iter().map(|(index, text)| (*index, text.as_str())).collect();
+    translations
+        .into_iter()
+        .map(|(index, translation)| {
+            let original = originals.get(&index).copied().unwrap_or_default();
+            (index, strip_residual_markers(original, &translation))
+        })
+        .filter(|(_, translation)| !translation.is_empty())
+        .collect()
+}
+
+/// 移除译文中残留的索引标记碎片（`[n]`、`§n§`、`【n】`），开头和结尾多余的标记一并裁掉
+///
+/// 原文中本来就有的同形标记（如脚注`[1]`）予以保留；标记后紧跟的空格随标记一起移除。
+pub fn strip_residual_markers(original: &str, translation: &str) -> String {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    let marker_regex = MARKER
+        .get_or_init(|| Regex::new(r"(\[\s*\d+\s*\]|§\s*\d+\s*§|【\s*\d+\s*】)[ \t]*").expect("索引标记正则无效"));
+    if !marker_regex.is_match(translation) {
+        return translation.to_string();
+    }
+
+    let original_markers: Vec<&str> = marker_regex
+        .captures_iter(original)
+        .filter_map(|captures| captures.get(1).map(|marker| marker.as_str()))
+        .collect();
+    marker_regex
+        .replace_all(translation, |captures: &regex::Captures| {
+            if original_markers.contains(&&captures[1]) {
+                captures[0].to_string()
+            } else {
+                String::new()
+            }
+        })
+        .trim()
+        .to_string()
+}
+
+/// 发送一次翻译请求，返回译文文本和API检测到的源语言
+async fn request_translation(
+    client: &reqwest::Client,
+    config: &LocalTranslationConfig,
+    text: &str,
+    source_lang: &str,
+) -> Result<(String, Option<String>)> {
+    if config.api_protocol() == ApiProtocol::Azure {
+        let (mut translated, detected_source_lang) =
+            request_azure_translation(client, config, &[text], source_lang).await?;
+        return Ok((translated.pop().unwrap_or_default(), detected_source_lang));
+    }
+
+    let request_body = match config.request_template() {
+        Some(template) => render_request_template_with_context(
+            template,
+            text,
+            config.target_lang(),
+            source_lang,
+            config.context().unwrap_or_default(),
+        )?,
+        None => {
+            let mut body = json!({
+                "text": text,
+                "source_lang": source_lang,
+                "target_lang": config.target_lang()
+            });
+            // 支持context字段的API直接传入领域上下文
+            if let Some(context) = config.context() {
+                body["context"] = json!(context);
+            }
+            body
+        }
+    };
+
+    // 发送翻译请求，网络错误按类别（超时、连接失败等）转换为TranslationError供重试策略区分
+    let response = client
+        .post(config.api_url())
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(TranslationError::from)
+        .context("发送翻译请求失败")?;
+
+    if !response.status().is_success() {
+        return Err(TranslationError::TranslationApi {
+            status_code: response.status().as_u16(),
+            message: "翻译API返回错误状态".to_string(),
+            // 去掉查询参数，避免在错误信息中泄露token
+            api_url: config.api_url().split('?').next().unwrap_or_default().to_string(),
+        }
+        .into());
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let response_text = response
+        .text()
+        .await
+        .map_err(TranslationError::from)
+        .context("读取响应失败")?;
+
+    // 故障端点可能以200返回HTML错误页，不能当作译文使用
+    if is_html_page_response(&content_type, &response_text) {
+        anyhow::bail!(
+            "翻译API返回了HTML页面而非译文 (Content-Type: {})",
+            if content_type.is_empty() { "未声明" } else { &content_type }
+        );
+    }
+
+    // 声明为JSON或配置了响应路径时严格按JSON解析，否则尝试JSON后退回纯文本
+    let mut detected_source_lang = None;
+    let translated_text = if let Some(response_path) = config.response_path() {
+        let json_val = serde_json::from_str::<serde_json::Value>(&response_text)
+            .context("翻译API响应不是有效JSON，无法按 --response-path 提取译文")?;
+        detected_source_lang = extract_detected_source_lang(&json_val);
+        extract_json_path(&json_val, response_path)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("翻译API响应中不存在字符串字段: {}", response_path))?
+            .to_string()
+    } else {
+        let parsed = serde_json::from_str::<serde_json::Value>(&response_text);
+        if content_type.contains("json") && parsed.is_err() {
+            anyhow::bail!("翻译API响应声明为JSON但无法解析 (Content-Type: {})", content_type);
+        }
+        match parsed {
+            Ok(json_val) => {
+                detected_source_lang = extract_detected_source_lang(&json_val);
+                json_val
+                    .get("data")
+                    .or_else(|| json_val.get("text"))
+                    .or_else(|| json_val.get("result"))
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("翻译API的JSON响应中没有data、text或result字符串字段，可用 --response-path 指定")
+                    })?
+                    .to_string()
+            }
+            Err(_) => response_text,
+        }
+    };
+
+    Ok((translated_text, detected_source_lang))
+}
+
+/// 调用Azure Translator v3翻译一组文本，返回与输入顺序一致的译文和检测到的源语言
+///
+/// 请求体为`[{"Text": ...}]`，密钥和区域通过`Ocp-Apim-Subscription-Key`/`Ocp-Apim-Subscription-Region`
+/// 请求头发送；响应为`[{"translations": [{"text": ...}], "detectedLanguage": {...}}]`。
+async fn request_azure_translation(
+    client: &reqwest::Client,
+    config: &LocalTranslationConfig,
+    texts: &[&str],
+    source_lang: &str,
+) -> Result<(Vec<String>, Option<String>)> {
+    let api_key = config
+        .api_key()
+        .ok_or_else(|| anyhow::anyhow!("Azure协议需要订阅密钥，请通过 --api-key 或 TRANSLATION_CLI_API_KEY 提供"))?;
+    let url = azure_request_url(config.api_url(), config.target_lang(), source_lang)?;
+    let body: Vec<_> = texts.iter().map(|text| json!({ "Text": text })).collect();
+
+    let mut request = client
+        .post(url)
+        .header("Ocp-Apim-Subscription-Key", api_key)
+        .json(&body);
+    if let Some(region) = config.azure_region() {
+        request = request.header("Ocp-Apim-Subscription-Region", region);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(TranslationError::from)
+        .context("发送翻译请求失败")?;
+
+    if !response.status().is_success() {
+        return Err(TranslationError::TranslationApi {
+            status_code: response.status().as_u16(),
+            message: "Azure翻译API返回错误状态".to_string(),
+            api_url: config.api_url().split('?').next().unwrap_or_default().to_string(),
+        }
+        .into());
+    }
+
+    let json_val: serde_json::Value = response
+        .json()
+        .await
+        .map_err(TranslationError::from)
+        .context("Azure翻译API响应不是有效JSON")?;
+    parse_azure_response(&json_val, texts.len())
+}
+
+/// 在Azure端点上补充`api-version`、`to`以及非自动检测时的`from`查询参数
+fn azure_request_url(api_url: &str, target_lang: &str, source_lang: &str) -> Result<url::Url> {
+    let mut url = url::Url::parse(api_url).with_context(|| format!("Azure翻译API地址无效: {}", api_url))?;
+    let existing: Vec<String> = url.query_pairs().map(|(key, _)| key.into_owned()).collect();
+    {
+        let mut query = url.query_pairs_mut();
+        if !existing.iter().any(|key| key == "api-version") {
+            query.append_pair("api-version", api_config::AZURE_API_VERSION);
+        }
+        if !existing.iter().any(|key| key == "to") {
+            query.append_pair("to", azure_language_code(target_lang));
+        }
+        if source_lang != "auto" && !existing.iter().any(|key| key == "from") {
+            query.append_pair("from", azure_language_code(source_lang));
+        }
+    }
+    Ok(url)
+}
+
+/// 将中文语言代码映射为Azure要求的`zh-Hans`/`zh-Hant`，其它代码原样返回
+///
+/// Azure不接受`zh`、`zh-CN`、`zh-TW`等代码，会直接返回400。
+fn azure_language_code(lang: &str) -> &str {
+    match lang.to_ascii_lowercase().replace('_', "-").as_str() {
+        "zh" | "zh-cn" | "zh-sg" | "zh-hans" => "zh-Hans",
+        "zh-tw" | "zh-hk" | "zh-mo" | "zh-hant" => "zh-Hant",
+        _ => lang,
+    }
+}
+
+/// 解析Azure响应，译文数量必须与请求的文本数量一致
+fn parse_azure_response(json_val: &serde_json::Value, expected: usize) -> Result<(Vec<String>, Option<String>)> {
+    let items = json_val
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Azure翻译API响应不是数组"))?;
+    if items.len() != expected {
+        anyhow::bail!("Azure翻译API返回 {} 条译文，与请求的 {} 条不一致", items.len(), expected);
+    }
+
+    let translations = items
+        .iter()
+        .map(|item| {
+            item.pointer("/translations/0/text")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect();
+    let detected_source_lang = items
+        .first()
+        .and_then(|item| item.pointer("/detectedLanguage/language"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    Ok((translations, detected_source_lang))
+}
+
+/// 从JSON响应中读取API检测到的源语言，缺失、为空或仍为`auto`时返回None
+fn extract_detected_source_lang(json_val: &serde_json::Value) -> Option<String> {
+    json_val
+        .get("detected_source_language")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|lang| !lang.is_empty() && !lang.eq_ignore_ascii_case("auto"))
+        .map(str::to_string)
+}
+
+/// 判断翻译API响应是否为HTML页面（如网关错误页）
+fn is_html_page_response(content_type: &str, body: &str) -> bool {
+    if content_type.starts_with("text/html") || content_type.starts_with("application/xhtml") {
+        return true;
+    }
+
+    let head: String = body.trim_start().chars().take(16).collect::<String>().to_ascii_lowercase();
+    head.starts_with("<!doctype") || head.starts_with("<html")
+}
+
+/// 解析索引标记的翻译响应
+///
+/// 从一行`[n]`开始到下一个`[m]`行之前的所有内容都归属于索引n，
+/// 多行译文以换行连接，首尾空行被去除；首个索引之前的内容被忽略。
+pub fn parse_indexed_translations(translated_text: &str) -> Result<Vec<(usize, String)>> {
+    let index_regex = Regex::new(r"^\[(\d+)\]\s*(.*)$").context("编译正则表达式失败")?;
+    let mut translations = Vec::new();
+    let mut current: Option<(usize, Vec<&str>)> = None;
+
+    let mut flush = |entry: Option<(usize, Vec<&str>)>| {
+        if let Some((index, lines)) = entry {
+            let translated = lines.join("\n").trim().to_string();
+            if !translated.is_empty() {
+                translations.push((index, translated));
+            }
+        }
+    };
+
+    for line in translated_text.lines() {
+        let index_captures = index_regex
+            .captures(line.trim())
+            .and_then(|captures| Some((captures.get(1)?, captures.get(2)?)))
+            .and_then(|(index_str, text)| Some((index_str.as_str().parse::<usize>().ok()?, text)));
+
+        match index_captures {
+            Some((index, text)) => {
+                flush(current.take());
+                current = Some((index, vec![text.as_str()]));
+            }
+            None => {
+                if let Some((_, lines)) = current.as_mut() {
+                    lines.push(line.trim_end());
+                }
+            }
+        }
+    }
+    flush(current.take());
+
+    Ok(translations)
+}
+
+/// 渲染JSON请求模板
+///
+/// 模板中所有字符串值里的`{text}`、`{target}`、`{source}`占位符会被替换为实际值，
+/// 替换在JSON解析之后进行，因此译文内容中的引号、换行等会被正确转义。
+pub fn render_request_template(
+    template: &str,
+    text: &str,
+    target_lang: &str,
+    source_lang: &str,
+) -> Result<serde_json::Value> {
+    render_request_template_with_context(template, text, target_lang, source_lang, "")
+}
+
+/// 渲染JSON请求模板，额外支持`{context}`领域上下文占位符
+///
+/// 对OpenAI等LLM协议，可在system消息中写入`{context}`注入领域提示。
+pub fn render_request_template_with_context(
+    template: &str,
+    text: &str,
+    target_lang: &str,
+    source_lang: &str,
+    context: &str,
+) -> Result<serde_json::Value> {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    let placeholder = PLACEHOLDER
+        .get_or_init(|| Regex::new(r"\{(text|target|source|context)\}").expect("占位符正则无效"));
+
+    // 一次扫描完成全部替换，页面文本中字面的`{target}`等不会被再次替换
+    fn render(value: &mut serde_json::Value, placeholder: &Regex, replacements: &[(&str, &str)]) {
+        match value {
+            serde_json::Value::String(s) if placeholder.is_match(s) => {
+                *s = placeholder
+                    .replace_all(s, |captures: &regex::Captures| {
+                        replacements
+                            .iter()
+                            .find(|(name, _)| *name == &captures[1])
+                            .map_or("", |(_, replacement)| *replacement)
+                            .to_string()
+                    })
+                    .into_owned();
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    render(item, placeholder, replacements);
+                }
+            }
+            serde_json::Value::Object(map) => {
+                for item in map.values_mut() {
+                    render(item, placeholder, replacements);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut value: serde_json::Value =
+        serde_json::from_str(template).context("请求模板不是有效的JSON")?;
+    render(
+        &mut value,
+        placeholder,
+        &[
+            ("text", text),
+            ("target", target_lang),
+            ("source", source_lang),
+            ("context", context),
+        ],
+    );
+    Ok(value)
+}
+
+/// 按点分路径提取JSON值
+///
+/// 路径段为对象键或数组下标，如`data.translations.0.text`。
+pub fn extract_json_path<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            serde_json::Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => current.get(segment),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::NetworkErrorKind;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_translation_report() {
+        let report = TranslationReport::from_translations(&[
+            "你好".to_string(),
+            String::new(),
+            " ".to_string(),
+            "世界".to_string(),
+        ]);
+        assert_eq!(report, TranslationReport { total: 4, translated: 2 });
+        assert_eq!(report.success_rate(), 0.5);
+
+        let merged = report.merge(TranslationReport { total: 4, translated: 4 });
+        assert_eq!(merged.success_rate(), 0.75);
+        assert_eq!(TranslationReport::default().success_rate(), 1.0);
+    }
+
+    fn network_error_kind(error: &anyhow::Error) -> Option<NetworkErrorKind> {
+        match error.downcast_ref::<TranslationError>()? {
+            TranslationError::Network { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_refused_fails_fast() {
+        let config = LocalTranslationConfig::new()
+            .with_api_url("http://127.0.0.1:9/translate")
+            .with_max_retries(3);
+        let client = shared_http_client().unwrap();
+
+        let start = std::time::Instant::now();
+        let error = translate_indexed_batch_with_retry(&client, &config, "[0] Hello", &OnceLock::new())
+            .await
+            .unwrap_err();
+        assert_eq!(network_error_kind(&error), Some(NetworkErrorKind::Connect));
+        // 连接失败不重试，不应经历退避等待
+        assert!(start.elapsed() < Duration::from_millis(service_config::RETRY_DELAY_BASE_MS));
+    }
+
+    /// 本地测试服务器按到达顺序记录的请求体
+    type RecordedRequests = Arc<Mutex<Vec<String>>>;
+
+    /// 启动本地HTTP服务器：读完每个请求后记录请求体，由`handler`根据请求体生成完整的HTTP响应
+    ///
+    /// 返回翻译API地址和请求记录。
+    async fn serve_recording<F>(handler: F) -> (String, RecordedRequests)
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        serve_recording_with_delay(Duration::ZERO, handler).await
+    }
+
+    /// 同[`serve_recording`]，每个连接单独处理并在响应前等待`delay`，用于让并发请求在时间上重叠
+    async fn serve_recording_with_delay<F>(delay: Duration, handler: F) -> (String, RecordedRequests)
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let requests = RecordedRequests::default();
+        let recorded = requests.clone();
+        let handler = Arc::new(handler);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let (recorded, handler) = (recorded.clone(), handler.clone());
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buffer = [0u8; 4096];
+                    let body = loop {
+                        match socket.read(&mut buffer).await {
+                            Ok(read) if read > 0 => request.extend_from_slice(&buffer[..read]),
+                            _ => return,
+                        }
+                        if let Some(body) = complete_request_body(&request) {
+                            break body;
+                        }
+                    };
+                    recorded.lock().unwrap().push(body.clone());
+                    tokio::time::sleep(delay).await;
+                    let _ = socket.write_all(handler(&body).as_bytes()).await;
+                });
+            }
+        });
+        (format!("http://{}/translate", address), requests)
+    }
+
+    /// 请求头已结束且请求体达到`Content-Length`时返回请求体
+    fn complete_request_body(request: &[u8]) -> Option<String> {
+        let head_end = request.windows(4).position(|window| window == b"\r\n\r\n")?;
+        let head = String::from_utf8_lossy(&request[..head_end]);
+        let length = head
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        let body = &request[head_end + 4..];
+        (body.len() >= length).then(|| String::from_utf8_lossy(body).into_owned())
+    }
+
+    /// 以200和指定的Content-Type、响应体构造HTTP响应
+    fn ok_response(content_type: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            content_type,
+            body.len(),
+            body
+        )
+    }
+
+    /// 启动只返回固定响应的本地HTTP服务器，返回翻译API地址
+    async fn serve_fixed_response(response: String) -> String {
+        serve_recording(move |_| response.clone()).await.0
+    }
+
+    /// 让翻译API以200和指定的Content-Type、响应体应答，返回批次翻译结果
+    async fn translate_with_fixed_response(content_type: &str, body: &str) -> Result<Vec<(usize, String)>> {
+        let config = LocalTranslationConfig::new().with_api_url(&serve_fixed_response(ok_response(content_type, body)).await);
+        translate_indexed_batch(&shared_http_client()?, &config, "[0] Hello").await
+    }
+
+    #[tokio::test]
+    async fn test_response_content_type_validation() {
+        // 以200返回的HTML错误页，即使未声明为HTML也应报错
+        let error_page = "<!DOCTYPE html><html><body>502 Bad Gateway</body></html>";
+        assert!(translate_with_fixed_response("text/html", error_page).await.is_err());
+        assert!(translate_with_fixed_response("text/plain", error_page).await.is_err());
+
+        // 声明为JSON但内容无法解析
+        let broken_json = "{\"data\": \"[0] 你好\"";
+        assert!(translate_with_fixed_response("application/json", broken_json).await.is_err());
+
+        // 正常JSON与纯文本响应
+        let json = "{\"data\": \"[0] 你好\"}";
+        let expected = vec![(0, "你好".to_string())];
+        assert_eq!(
+            translate_with_fixed_response("application/json; charset=utf-8", json).await.unwrap(),
+            expected
+        );
+        assert_eq!(translate_with_fixed_response("text/plain", "[0] 你好").await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_priority_preview_keeps_backup_of_previous_output() {
+        let (api_url, _) = serve_recording(|body| {
+            let text = serde_json::from_str::<serde_json::Value>(body).unwrap()["text"].as_str().unwrap().to_string();
+            let translated = text
+                .lines()
+                .map(|line| format!("{} 译文", line.split_once(' ').map_or(line, |(marker, _)| marker)))
+                .collect::<Vec<_>>()
+                .join("\n");
+            ok_response("text/plain", &translated)
+        })
+        .await;
+
+        let dir = std::env::temp_dir().join(format!("translation-cli-preview-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("page_zh.html");
+        std::fs::write(&output, "上一次的译文").unwrap();
+
+        let preview = crate::utils::preview_output_path(&output);
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .enable_cache(false)
+            .priority_first(true)
+            .with_preview_output(Some(preview.clone()));
+        let html = "<html><head><title>Title</title></head><body><h1>Heading</h1><div>Other text</div></body></html>";
+        let outcome = translate_with_outcome(html, &config, false).await.unwrap();
+
+        // 预览写到单独的文件，正式输出在写出前保持上一次的内容
+        assert_eq!(preview, dir.join("page_zh.html.preview"));
+        let preview_html = std::fs::read_to_string(&preview).unwrap();
+        assert!(preview_html.contains("译文") && preview_html.contains("Other text"));
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "上一次的译文");
+
+        // 按--backup写出时，备份保存的是上一次的译文而不是预览
+        let sink = crate::output_sink::FileSink { backup: true, ..Default::default() };
+        crate::output_sink::OutputSink::write(&sink, &output, &outcome.html).await.unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("page_zh.html.bak")).unwrap(), "上一次的译文");
+        assert!(!std::fs::read_to_string(&output).unwrap().contains("Other text"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_plan_batches() {
+        let lengths = [100, 90, 5, 4, 3, 2];
+        assert_eq!(
+            plan_batches(&lengths, 2, false),
+            vec![vec![0, 1], vec![2, 3], vec![4, 5]]
+        );
+
+        let balanced = plan_batches(&lengths, 2, true);
+        assert_eq!(balanced.len(), 3);
+        // 每个下标恰好出现一次
+        let mut all: Vec<usize> = balanced.iter().flatten().copied().collect();
+        all.sort_unstable();
+        assert_eq!(all, vec![0, 1, 2, 3, 4, 5]);
+        // 两个长文本不会落在同一批次
+        assert!(!balanced.iter().any(|batch| batch.contains(&0) && batch.contains(&1)));
+        assert!(balanced.iter().all(|batch| batch.windows(2).all(|w| w[0] < w[1])));
+
+        assert!(plan_batches(&[], 5, true).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_max_batch_chars_limits_every_request() {
+
+        // 记录每个请求，按行回显索引标记作为译文
+        let request_text = |body: &str| {
+            serde_json::from_str::<serde_json::Value>(body).unwrap()["text"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        let (api_url, requests) = serve_recording(move |body| {
+            let translated = request_text(body)
+                .lines()
+                .map(|line| format!("{} 译文", line.split_once(' ').map_or(line, |(marker, _)| marker)))
+                .collect::<Vec<_>>()
+                .join("\n");
+            ok_response("text/plain", &translated)
+        })
+        .await;
+
+        let max_chars = 120;
+        let texts: Vec<String> = [3, 80, 5, 40, 40, 150, 10, 60, 2, 90, 30, 30, 30, 7]
+            .iter()
+            .enumerate()
+            .map(|(index, &length)| format!("T{}{}", index, "x".repeat(length)))
+            .collect();
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .enable_cache(false)
+            .with_max_batch_chars(Some(max_chars));
+        let translations = translate_batches(&shared_http_client().unwrap(), texts.clone(), &config, false, &|_, _| {})
+            .await
+            .unwrap().0;
+        assert_eq!(translations, vec!["译文".to_string(); texts.len()]);
+
+        // 除单条即超预算的文本独占一批外，每个请求都不超过字符预算
+        let requests: Vec<String> = requests.lock().unwrap().iter().map(|body| request_text(body)).collect();
+        assert!(requests.len() > 2);
+        for request in requests.iter() {
+            assert!(
+                request.chars().count() <= max_chars || request.lines().count() == 1,
+                "批次超出字符预算: {} 字符",
+                request.chars().count()
+            );
+        }
+        assert!(requests.iter().any(|request| request.starts_with("[5] T5")));
+    }
+
+    #[tokio::test]
+    async fn test_check_translation_api() {
+        let ok = "{\"data\": \"[0] 你好\"}";
+        let response = ok_response("application/json", ok);
+        let config = LocalTranslationConfig::new().with_api_url(&serve_fixed_response(response).await);
+        assert_eq!(check_translation_api(&config).await.unwrap(), "你好");
+
+        // 响应中缺少探测文本的译文
+        let empty = "{\"data\": \"\"}";
+        let response = ok_response("application/json", empty);
+        let config = LocalTranslationConfig::new().with_api_url(&serve_fixed_response(response).await);
+        assert!(check_translation_api(&config).await.is_err());
+
+        // 端点返回错误状态
+        let response = "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string();
+        let config = LocalTranslationConfig::new().with_api_url(&serve_fixed_response(response).await);
+        assert!(check_translation_api(&config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_is_classified() {
+        // 读完请求后迟迟不响应的服务器
+        let (api_url, _) = serve_recording_with_delay(Duration::from_secs(60), |_| String::new()).await;
+
+        let config = LocalTranslationConfig::new().with_api_url(&api_url);
+        let client = Client::builder()
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let error = translate_indexed_batch(&client, &config, "[0] Hello").await.unwrap_err();
+        assert_eq!(network_error_kind(&error), Some(NetworkErrorKind::Timeout));
+        let delay = error
+            .downcast_ref::<TranslationError>()
+            .and_then(|e| e.retry_delay(1, Duration::from_millis(100)));
+        assert_eq!(delay, Some(Duration::from_millis(200)));
+    }
+
+    #[tokio::test]
+    async fn test_shared_client_follows_config_timeout() {
+        let (api_url, _) = serve_recording_with_delay(Duration::from_secs(60), |_| String::new()).await;
+
+        // 默认设置的客户端先被创建，之后按配置取得的客户端仍使用配置中的超时
+        shared_http_client().unwrap();
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .with_request_timeout(Duration::from_millis(200));
+        let client = shared_http_client_for(&config).unwrap();
+
+        let started = std::time::Instant::now();
+        let error = translate_indexed_batch(&client, &config, "[0] Hello").await.unwrap_err();
+        assert_eq!(network_error_kind(&error), Some(NetworkErrorKind::Timeout));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_translate_stream_without_texts() {
+        let config = LocalTranslationConfig::new().enable_cache(false);
+        let events: Vec<_> = translate_stream("<html><body></body></html>", &config)
+            .collect()
+            .await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], TranslationEvent::Extracted { total: 0 });
+        assert!(matches!(events[1], TranslationEvent::Completed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_translate_stream_reports_failure() {
+        let config = LocalTranslationConfig::new()
+            .with_api_url("http://127.0.0.1:9/translate")
+            .enable_cache(false)
+            .fail_fast(true);
+        let events: Vec<_> = translate_stream("<p>Hello World</p>", &config).collect().await;
+        assert_eq!(events.first(), Some(&TranslationEvent::Extracted { total: 1 }));
+        assert!(matches!(events.last(), Some(TranslationEvent::Failed { .. })));
+    }
+
+    #[test]
+    fn test_parse_indexed_translations_multiline() {
+        let response = "[0] 第一行\n第二行\n\n[1] 单行译文\n[2]\n  换行后的译文\n\n";
+        let translations = parse_indexed_translations(response).unwrap();
+        assert_eq!(
+            translations,
+            vec![
+                (0, "第一行\n第二行".to_string()),
+                (1, "单行译文".to_string()),
+                (2, "换行后的译文".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_residual_index_markers_are_stripped() {
+        let body = "{\"data\": \"[0] [0] 参见注释 [1] §2§\\n[1] 世界 【1】\\n[2] [5]\"}";
+        let response = ok_response("application/json", body);
+        let config = LocalTranslationConfig::new().with_api_url(&serve_fixed_response(response).await);
+        let translations = translate_indexed_batch(
+            &shared_http_client().unwrap(),
+            &config,
+            "[0] See note [1]\n[1] World\n[2] Empty",
+        )
+        .await
+        .unwrap();
+
+        // 原文中的脚注[1]保留，其余残留标记被移除，只剩标记的条目视为未翻译
+        assert_eq!(translations, vec![(0, "参见注释 [1]".to_string()), (1, "世界".to_string())]);
+    }
+
+    #[test]
+    fn test_strip_residual_markers() {
+        assert_eq!(strip_residual_markers("Hello", "[3] 你好 [4]"), "你好");
+        assert_eq!(strip_residual_markers("Hello world", "你好 §1§ 世界"), "你好 世界");
+        assert_eq!(strip_residual_markers("Hello", "你好[ 2 ]。"), "你好。");
+        assert_eq!(strip_residual_markers("Step [2] done", "第 [2] 步完成"), "第 [2] 步完成");
+        assert_eq!(strip_residual_markers("Hello", "你好"), "你好");
+    }
+
+    #[test]
+    fn test_parse_indexed_translations_skips_preamble_and_empty() {
+        let response = "以下是翻译：\n[3]   \n[4] 有内容";
+        let translations = parse_indexed_translations(response).unwrap();
+        assert_eq!(translations, vec![(4, "有内容".to_string())]);
+    }
+
+    #[test]
+    fn test_enforce_translation_consistency_picks_majority() {
+        // 属性与文本节点中完全相同的文本提取时已去重，只有内部空白不同的写法会各自翻译
+        let dom = parse_html_document(
+            "<html><body><a title=\"Sign in\">Sign in</a><p>Sign\n    in</p><b>Sign  in</b><p>Home</p></body></html>",
+        )
+        .unwrap();
+        let originals = extract_translatable_texts_with_options(&dom, &Default::default());
+        assert_eq!(originals, vec!["Sign in", "Sign\n    in", "Sign  in", "Home"]);
+        let mut translations = vec![
+            "登录".to_string(),
+            "登入".to_string(),
+            "登录".to_string(),
+            "首页".to_string(),
+        ];
+
+        let conflicts = enforce_translation_consistency(&originals, &mut translations);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].original, "Sign in");
+        assert_eq!(conflicts[0].chosen, "登录");
+        assert_eq!(translations, vec!["登录", "登录", "登录", "首页"]);
+    }
+
+    #[test]
+    fn test_reuse_adopted_translations_across_phases() {
+        // 前一阶段（或分块）已采用“登录”，后续出现的空白变体沿用该译文
+        let mut adopted = HashMap::new();
+        let mut first = vec!["登录".to_string()];
+        assert!(reuse_adopted_translations(&mut adopted, &["Sign in".to_string()], &mut first).is_empty());
+
+        let originals = vec!["Sign\n  in".to_string(), "Home".to_string()];
+        let mut translations = vec!["登入".to_string(), "首页".to_string()];
+        let conflicts = reuse_adopted_translations(&mut adopted, &originals, &mut translations);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].original, "Sign in");
+        assert_eq!(conflicts[0].chosen, "登录");
+        assert_eq!(translations, vec!["登录", "首页"]);
+        assert_eq!(adopted.get("Home").map(String::as_str), Some("首页"));
+    }
+
+    #[test]
+    fn test_enforce_translation_consistency_ignores_failed() {
+        let originals = vec!["Hello world".to_string(), "Hello\n  world".to_string()];
+        let mut translations = vec!["你好世界".to_string(), String::new()];
+
+        let conflicts = enforce_translation_consistency(&originals, &mut translations);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(translations, vec!["你好世界".to_string(), String::new()]);
+    }
+
+    #[test]
+    fn test_render_request_template() {
+        let template = r#"{"content":"{text}","to":"{target}","from":"{source}","opts":["{target}",1]}"#;
+        let body = render_request_template(template, "[0] Say \"hi\"\n[1] Bye", "ja", "auto").unwrap();
+
+        assert_eq!(body["content"], "[0] Say \"hi\"\n[1] Bye");
+        assert_eq!(body["to"], "ja");
+        assert_eq!(body["from"], "auto");
+        assert_eq!(body["opts"][0], "ja");
+        assert_eq!(body["opts"][1], 1);
+
+        assert!(render_request_template("{not json", "x", "zh", "auto").is_err());
+    }
+
+    #[test]
+    fn test_render_request_template_with_context() {
+        let template = r#"{"messages": [
+            {"role": "system", "content": "Translate to {target}. Context: {context}"},
+            {"role": "user", "content": "{text}"}
+        ]}"#;
+        let body = render_request_template_with_context(
+            template,
+            "[0] Chart",
+            "zh",
+            "auto",
+            "这是一个医疗器械产品页面",
+        )
+        .unwrap();
+        assert_eq!(
+            body["messages"][0]["content"],
+            "Translate to zh. Context: 这是一个医疗器械产品页面"
+        );
+        assert_eq!(body["messages"][1]["content"], "[0] Chart");
+    }
+
+    #[test]
+    fn test_render_request_template_keeps_placeholders_in_text() {
+        let template = r#"{"q": "{text}", "to": "{target}", "hint": "{context}"}"#;
+        let body = render_request_template_with_context(
+            template,
+            "[0] Use {target} and {source} in {context}",
+            "zh",
+            "en",
+            "docs",
+        )
+        .unwrap();
+        assert_eq!(body["q"], "[0] Use {target} and {source} in {context}");
+        assert_eq!(body["to"], "zh");
+        assert_eq!(body["hint"], "docs");
+    }
+
+    #[test]
+    fn test_extract_json_path() {
+        let value = json!({"data": {"translations": [{"text": "你好"}]}, "code": 200});
+
+        assert_eq!(
+            extract_json_path(&value, "data.translations.0.text").and_then(|v| v.as_str()),
+            Some("你好")
+        );
+        assert_eq!(extract_json_path(&value, "code").and_then(|v| v.as_u64()), Some(200));
+        assert!(extract_json_path(&value, "data.translations.1.text").is_none());
+        assert!(extract_json_path(&value, "data.missing").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast_returns_error_on_batch_failure() {
+        // 本地未监听端口，连接会被立即拒绝
+        let texts = vec!["Hello world".to_string(), "Welcome home".to_string()];
+
+        let best_effort = LocalTranslationConfig::new().with_api_url("http://127.0.0.1:9/translate");
+        let translations = indexed_batch_translation_with_config(texts.clone(), &best_effort, false)
+            .await
+            .unwrap();
+        assert_eq!(translations, vec![String::new(), String::new()]);
+
+        let fail_fast = best_effort.fail_fast(true);
+        let result = indexed_batch_translation_with_config(texts, &fail_fast, false).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_detected_source_lang_is_reused() {
+
+        // 记录每个请求体，并总是回报检测到的源语言为en
+        let (api_url, requests) = serve_recording(|_| {
+            ok_response("application/json", "{\"data\": \"[0] 你好\", \"detected_source_language\": \"en\"}")
+        })
+        .await;
+
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .with_concurrent_batches(3);
+        let texts: Vec<String> = (0..15).map(|i| format!("Sentence {}", i)).collect();
+        translate_batches(&shared_http_client().unwrap(), texts, &config, false, &|_, _| {})
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].contains("\"source_lang\":\"auto\""));
+        assert!(requests[1..].iter().all(|request| request.contains("\"source_lang\":\"en\"")));
+    }
+
+    #[test]
+    fn test_extract_detected_source_lang() {
+        assert_eq!(
+            extract_detected_source_lang(&json!({"data": "x", "detected_source_language": "ja"})),
+            Some("ja".to_string())
+        );
+        assert_eq!(extract_detected_source_lang(&json!({"detected_source_language": "auto"})), None);
+        assert_eq!(extract_detected_source_lang(&json!({"data": "x"})), None);
+    }
+
+    #[tokio::test]
+    async fn test_single_retry_fallback_recovers_failed_batch() {
+
+        // 带索引的批次请求返回不可重试的400，单条请求正常返回译文
+        let (api_url, _) = serve_recording(|body| {
+            if body.contains("\"text\":\"[") {
+                "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            } else {
+                ok_response("text/plain", if body.contains("Broken") { "" } else { "译文" })
+            }
+        })
+        .await;
+
+        let texts = vec!["First".to_string(), "Broken".to_string(), "Third".to_string()];
+        let client = shared_http_client().unwrap();
+
+        let config = LocalTranslationConfig::new().with_api_url(&api_url).with_max_retries(0);
+        let (translations, succeeded) = translate_batches(&client, texts.clone(), &config, false, &|_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(translations, vec!["译文".to_string(), String::new(), "译文".to_string()]);
+        assert_eq!(succeeded, vec![true, false, true]);
+
+        let config = config.single_retry_fallback(false);
+        let translations = translate_batches(&client, texts, &config, false, &|_, _| {})
+            .await
+            .unwrap().0;
+        assert!(translations.iter().all(String::is_empty));
+    }
+
+    #[tokio::test]
+    async fn test_translate_with_outcome() {
+        let body = "{\"data\": \"[0] 你好\"}";
+        let response = ok_response("application/json", body);
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&serve_fixed_response(response).await)
+            .with_concurrent_batches(1)
+            .enable_cache(false);
+        let html = "<html><body><p>Hello</p><p>Goodbye</p></body></html>";
+
+        let outcome = translate_with_outcome(html, &config, false).await.unwrap();
+        assert_eq!(outcome.total, 2);
+        assert_eq!(outcome.succeeded, 1);
+        assert_eq!(outcome.failed_texts, vec!["Goodbye".to_string()]);
+        assert_eq!(outcome.success_rate(), 0.5);
+        assert_eq!(outcome.report(), TranslationReport { total: 2, translated: 1 });
+        assert!(outcome.html.contains("<p>你好</p><p>Goodbye</p>"));
+    }
+
+    #[test]
+    fn test_azure_request_url() {
+        let url = azure_request_url(api_config::AZURE_API_URL, "zh-Hans", "auto").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://api.cognitive.microsofttranslator.com/translate?api-version=3.0&to=zh-Hans"
+        );
+
+        // 已有的查询参数保持不变
+        let url = azure_request_url("https://example.com/translate?api-version=3.0&to=ja", "zh", "en").unwrap();
+        assert_eq!(url.as_str(), "https://example.com/translate?api-version=3.0&to=ja&from=en");
+
+        // 中文代码映射为Azure的简繁体代码
+        for (lang, expected) in [("zh", "zh-Hans"), ("zh-CN", "zh-Hans"), ("zh_TW", "zh-Hant"), ("ja", "ja")] {
+            let url = azure_request_url(api_config::AZURE_API_URL, lang, "zh-TW").unwrap();
+            assert!(url.as_str().ends_with(&format!("&to={}&from=zh-Hant", expected)), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_parse_azure_response() {
+        let response = json!([
+            {"detectedLanguage": {"language": "en", "score": 1.0}, "translations": [{"text": "你好", "to": "zh"}]},
+            {"translations": [{"text": "世界", "to": "zh"}]}
+        ]);
+        let (translations, detected) = parse_azure_response(&response, 2).unwrap();
+        assert_eq!(translations, vec!["你好".to_string(), "世界".to_string()]);
+        assert_eq!(detected.as_deref(), Some("en"));
+
+        assert!(parse_azure_response(&response, 3).is_err());
+        assert!(parse_azure_response(&json!({"error": "bad"}), 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_azure_indexed_batch() {
+        let body = r#"[{"translations":[{"text":"你好"}]},{"translations":[{"text":"世界"}]}]"#;
+        let response = ok_response("application/json", body);
+        let api_url = serve_fixed_response(response).await;
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .with_api_protocol(ApiProtocol::Azure);
+        let client = shared_http_client().unwrap();
+
+        // 缺少订阅密钥时给出明确错误
+        let error = translate_indexed_batch(&client, &config, "[3] Hello\n[7] World").await.unwrap_err();
+        assert!(error.to_string().contains("--api-key"));
+
+        let config = config.with_api_key(Some("secret".to_string()));
+        let translations = translate_indexed_batch(&client, &config, "[3] Hello\n[7] World").await.unwrap();
+        assert_eq!(translations, vec![(3, "你好".to_string()), (7, "世界".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_misses_after_switching_api() {
+        let fixed = |body: &str| {
+            ok_response("application/json", body)
+        };
+        let api_a = serve_fixed_response(fixed("{\"data\": \"[0] 甲译文\"}")).await;
+        let api_b = serve_fixed_response(fixed("{\"data\": \"[0] 乙译文\"}")).await;
+
+        let cache_dir = std::env::temp_dir().join(format!("translation-cli-scope-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let config = LocalTranslationConfig::new()
+            .enable_cache(true)
+            .with_cache_dir(Some(cache_dir.clone()));
+        let texts = vec!["Hello".to_string()];
+
+        let first = indexed_batch_translation_with_config(texts.clone(), &config.clone().with_api_url(&api_a), false)
+            .await
+            .unwrap();
+        assert_eq!(first, vec!["甲译文".to_string()]);
+
+        // 换API后不复用旧译文，换回原API时命中缓存
+        let switched = indexed_batch_translation_with_config(texts.clone(), &config.clone().with_api_url(&api_b), false)
+            .await
+            .unwrap();
+        assert_eq!(switched, vec!["乙译文".to_string()]);
+        let cached = TranslationCache::new(&cache_dir)
+            .with_scope(&translation_cache_scope(&config.clone().with_api_url(&api_a)));
+        assert_eq!(cached.get("Hello", "zh"), Some("甲译文".to_string()));
+
+        // 只换领域上下文同样不复用旧译文，换回原上下文时命中缓存
+        let (api_c, requests) = serve_recording(|body| {
+            let domain = if body.contains("医疗") { "医疗" } else { "法律" };
+            ok_response("text/plain", &format!("[0] {}译文", domain))
+        })
+        .await;
+        let medical = config.clone().with_api_url(&api_c).with_context(Some("医疗".to_string()));
+        let legal = config.clone().with_api_url(&api_c).with_context(Some("法律".to_string()));
+        for (context_config, expected) in [(&medical, "医疗译文"), (&legal, "法律译文"), (&medical, "医疗译文")] {
+            let translations = indexed_batch_translation_with_config(texts.clone(), context_config, false)
+                .await
+                .unwrap();
+            assert_eq!(translations, vec![expected.to_string()]);
+        }
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_translate_large_html_in_chunks() {
+        let body = "{\"data\": \"[0] 你好\"}";
+        let response = ok_response("application/json", body);
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&serve_fixed_response(response).await)
+            .with_concurrent_batches(1)
+            .enable_cache(false);
+        let html = "<html><head><title>Title</title></head><body>\
+<div><p>One</p></div><div><p>Two</p></div><div><p>Three</p></div></body></html>";
+
+        let outcome = translate_large_html(html, &config, 10, false).await.unwrap();
+        assert_eq!((outcome.total, outcome.succeeded), (4, 4));
+        assert!(outcome.html.contains("<title>你好</title>"));
+        assert!(outcome.html.contains("<body><div><p>你好</p></div><div><p>你好</p></div><div><p>你好</p></div></body>"));
+        assert!(!outcome.html.contains(CHUNK_PLACEHOLDER));
+    }
+
+    #[test]
+    fn test_find_overlong_translations() {
+        let texts = vec!["Save".to_string(), "Cancel".to_string(), "".to_string(), "OK".to_string()];
+        let translations = vec!["保存".to_string(), "取消当前所有的操作并返回".to_string(), "空".to_string(), String::new()];
+        assert_eq!(find_overlong_translations(&texts, &translations, 1.5), vec![1]);
+        assert_eq!(translation_length_ratio("Save", "保存"), Some(0.5));
+        assert_eq!(translation_length_ratio("", "保存"), None);
+    }
+
+    #[tokio::test]
+    async fn test_max_translation_ratio_requests_shorter_retranslation() {
+
+        // 首次返回冗长译文；上下文中带有长度要求的重译请求返回简短译文
+        let (api_url, _) = serve_recording(|body| {
+            ok_response("text/plain", if body.contains("更简短") { "保存" } else { "[0] 保存您刚才所做的全部更改" })
+        })
+        .await;
+
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .with_request_template(Some("{\"prompt\": \"{context}\\n{text}\"}".to_string()))
+            .with_max_translation_ratio(Some(1.5));
+        let client = shared_http_client().unwrap();
+        let texts = vec!["Save".to_string()];
+        let translations = translate_batches(&client, texts.clone(), &config, false, &|_, _| {})
+            .await
+            .unwrap().0;
+        assert_eq!(translations, vec!["保存".to_string()]);
+
+        // 模板没有{context}占位符时无法传递长度要求，跳过重译
+        let no_context = config
+            .clone()
+            .with_request_template(Some("{\"prompt\": \"{text}\"}".to_string()));
+        let translations = translate_batches(&client, texts.clone(), &no_context, false, &|_, _| {})
+            .await
+            .unwrap().0;
+        assert_eq!(translations, vec!["保存您刚才所做的全部更改".to_string()]);
+
+        // 非LLM后端只标记，不重译
+        let config = config.with_request_template(None);
+        let translations = translate_batches(&client, texts, &config, false, &|_, _| {})
+            .await
+            .unwrap().0;
+        assert_eq!(translations, vec!["保存您刚才所做的全部更改".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_pre_and_post_process_hooks() {
+
+        // 记录发往API的请求体，验证预处理后的文本才会被发送
+        let (api_url, requests) = serve_recording(|_| ok_response("text/plain", "[0] 联系 [EMAIL]")).await;
+
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .with_pre_process(|text| text.replace("alice@example.com", "[EMAIL]"))
+            .with_post_process(|text| text.replace("[EMAIL]", "alice@example.com"));
+        let translations =
+            indexed_batch_translation_with_config(vec!["Contact alice@example.com".to_string()], &config, false)
+                .await
+                .unwrap();
+
+        assert_eq!(translations, vec!["联系 alice@example.com".to_string()]);
+        let requests = requests.lock().unwrap();
+        assert!(requests.iter().all(|request| !request.contains("alice@example.com")));
+    }
+
+    #[tokio::test]
+    async fn test_post_process_hook_is_not_cached() {
+        let (api_url, requests) = serve_recording(|_| ok_response("text/plain", "[0] 术语译文")).await;
+
+        let cache_dir = std::env::temp_dir().join(format!("translation-cli-hook-cache-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .enable_cache(true)
+            .with_cache_dir(Some(cache_dir.clone()));
+        let texts = vec!["Term".to_string()];
+
+        let hooked = config.clone().with_post_process(|text| text.replace("术语", "TERM"));
+        let first = indexed_batch_translation_with_config(texts.clone(), &hooked, false).await.unwrap();
+        assert_eq!(first, vec!["TERM译文".to_string()]);
+
+        // 第二次不带hook，命中缓存但拿到的是API原始译文
+        let second = indexed_batch_translation_with_config(texts.clone(), &config, false).await.unwrap();
+        assert_eq!(second, vec!["术语译文".to_string()]);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        // 预处理hook改变了发送的文本，不读写缓存
+        let pre_hooked = config.clone().with_pre_process(|text| text.to_uppercase());
+        indexed_batch_translation_with_config(texts, &pre_hooked, false).await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_html_entities_are_decoded_before_translation() {
+
+        let (api_url, requests) =
+            serve_recording(|_| ok_response("text/plain", "[0] 汤姆 & 杰瑞\u{a0}秀\n[1] 研发 & 质检")).await;
+
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .enable_cache(false);
+        let translations = translate_batches(
+            &shared_http_client().unwrap(),
+            vec!["Tom &amp; Jerry&nbsp;Show".to_string(), "R&D".to_string()],
+            &config,
+            false,
+            &|_, _| {},
+        )
+        .await
+        .unwrap().0;
+
+        // API看到的是解码后的自然文本，回写时按原文写法重新编码
+        let requests = requests.lock().unwrap();
+        assert!(requests.iter().all(|request| !request.contains("&amp;") && !request.contains("&nbsp;")));
+        assert!(requests.iter().any(|request| request.contains("Tom & Jerry\u{a0}Show")));
+        assert_eq!(
+            translations,
+            vec!["汤姆 &amp; 杰瑞&nbsp;秀".to_string(), "研发 & 质检".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redact_pii_keeps_email_and_phone_verbatim() {
+        use crate::pii_redaction::PiiRedactor;
+
+        let (api_url, requests) =
+            serve_recording(|_| ok_response("text/plain", "[0] 请发邮件至 {{P0}} 或致电 {{ P1 }}")).await;
+
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .with_pii_redactor(Some(PiiRedactor::new()));
+        let translations = translate_batches(
+            &shared_http_client().unwrap(),
+            vec!["Email alice@example.com or call +1 555-123-4567".to_string()],
+            &config,
+            false,
+            &|_, _| {},
+        )
+        .await
+        .unwrap().0;
+
+        assert_eq!(
+            translations,
+            vec!["请发邮件至 alice@example.com 或致电 +1 555-123-4567".to_string()]
+        );
+        let requests = requests.lock().unwrap();
+        assert!(requests
+            .iter()
+            .all(|request| !request.contains("alice@example.com") && !request.contains("555-123-4567")));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_signal_stops_new_requests() {
+        use crate::shutdown::ShutdownSignal;
+
+        let (api_url, requests) = serve_recording(|_| ok_response("text/plain", "[0] 你好")).await;
+
+        let signal = ShutdownSignal::new();
+        signal.request();
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .with_shutdown_signal(Some(signal));
+        let translations = translate_batches(
+            &shared_http_client().unwrap(),
+            vec!["Hello".to_string(), "World".to_string()],
+            &config,
+            false,
+            &|_, _| {},
+        )
+        .await
+        .unwrap().0;
+
+        assert_eq!(translations, vec![String::new(), String::new()]);
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_translation_overrides_skip_api() {
+        use crate::dictionary::TranslationDictionary;
+
+        let mut overrides = TranslationDictionary::new();
+        overrides.extend_from_pairs(&["Save".to_string()], &["保存更改".to_string()]);
+        // API地址不可达：命中覆盖译文的文本不应发出任何请求
+        let config = LocalTranslationConfig::new()
+            .with_api_url("http://127.0.0.1:9/translate")
+            .enable_cache(false)
+            .with_translation_overrides(Some(overrides));
+        let translations = indexed_batch_translation_with_progress(
+            &shared_http_client().unwrap(),
+            vec![" Save ".to_string()],
+            &config,
+            false,
+            &|_, _| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(translations, vec!["保存更改".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_shared_translations_skip_repeated_texts_across_pages() {
+        use crate::translation_cache::SharedTranslations;
+        use std::sync::Arc;
+
+        let body = "{\"data\": \"[0] 首页\"}";
+        let response = ok_response("application/json", body);
+        let shared = Arc::new(SharedTranslations::new());
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&serve_fixed_response(response).await)
+            .enable_cache(false)
+            .with_shared_translations(Some(shared.clone()));
+        let client = shared_http_client().unwrap();
+
+        let first = indexed_batch_translation_with_client(&client, vec!["Home".to_string()], &config, false)
+            .await
+            .unwrap();
+        assert_eq!(first, vec!["首页".to_string()]);
+        assert_eq!(shared.len(), 1);
+
+        // 第二个页面复用内存字典，API不可用也能得到译文
+        let config = config.with_api_url("http://127.0.0.1:9/translate").with_max_retries(0);
+        let second = indexed_batch_translation_with_client(&client, vec!["Home".to_string()], &config, false)
+            .await
+            .unwrap();
+        assert_eq!(second, vec!["首页".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_for_same_text_hit_api_once() {
+
+        // 响应前稍作延迟，保证两次调用的请求在时间上重叠
+        let (api_url, requests) =
+            serve_recording_with_delay(Duration::from_millis(200), |_| ok_response("text/plain", "[0] 你好")).await;
+
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .enable_cache(false);
+        let client = shared_http_client().unwrap();
+        let (first, second) = tokio::join!(
+            indexed_batch_translation_with_client(&client, vec!["Hello".to_string(), "Hello".to_string()], &config, false),
+            indexed_batch_translation_with_client(&client, vec!["Hello".to_string()], &config, false),
+        );
+
+        assert_eq!(first.unwrap(), vec!["你好".to_string(), "你好".to_string()]);
+        assert_eq!(second.unwrap(), vec!["你好".to_string()]);
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1, "同一文本的并发请求应只打一次API");
+        assert!(!requests[0].contains("[1]"), "同批内的重复文本只发送一次");
+        assert!(InFlightTranslations::global().claim(&translation_cache_scope(&config), "zh", &["Hello".to_string()]).waiting.is_empty());
+    }
+
+    #[test]
+    fn test_void_element_style_follows_input_doctype() {
+        let xhtml_input = r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "x.dtd"><html></html>"#;
+        let serialized = "<p>A<br>B</p>".to_string();
+        let config = LocalTranslationConfig::new();
+        assert_eq!(apply_void_element_style(&config, xhtml_input, serialized.clone()), "<p>A<br/>B</p>");
+        assert_eq!(apply_void_element_style(&config, "<!DOCTYPE html>", serialized.clone()), "<p>A<br>B</p>");
+
+        let config = config.with_xhtml_output(Some(false));
+        assert_eq!(apply_void_element_style(&config, xhtml_input, serialized.clone()), "<p>A<br>B</p>");
+        let config = config.with_xhtml_output(Some(true));
+        assert_eq!(apply_void_element_style(&config, "<!DOCTYPE html>", serialized), "<p>A<br/>B</p>");
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast_after_threshold() {
+
+        // 始终返回503的API，记录实际收到的请求
+        let (api_url, requests) = serve_recording(|_| {
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+        })
+        .await;
+
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&api_url)
+            .with_max_retries(5)
+            .with_circuit_breaker(2, Duration::from_secs(60));
+        let client = shared_http_client().unwrap();
+        let detected = OnceLock::new();
+
+        let error = translate_indexed_batch_with_retry(&client, &config, "[0] Hello", &detected)
+            .await
+            .unwrap_err();
+        assert!(matches!(error.downcast_ref::<TranslationError>(), Some(TranslationError::CircuitOpen { .. })));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        // 熔断期间后续批次不再发请求
+        assert!(translate_indexed_batch_with_retry(&client, &config, "[0] Again", &detected).await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fragment_mode_keeps_fragment_output() {
+        let body = "{\"data\": \"[0] 文本\"}";
+        let response = ok_response("application/json", body);
+        let config = LocalTranslationConfig::new()
+            .with_api_url(&serve_fixed_response(response).await)
+            .enable_cache(false)
+            .fragment(true);
+
+        let outcome = translate_with_outcome("<p>text</p>", &config, false).await.unwrap();
+        assert_eq!(outcome.html, "<p>文本</p>");
+
+        let config = config.preserve_formatting(true);
+        let outcome = translate_with_outcome("<p>text</p>", &config, false).await.unwrap();
+        assert_eq!(outcome.html, "<p>文本</p>");
+    }
+}
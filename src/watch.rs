@@ -0,0 +1,141 @@
+//! 监听模式模块
+//!
+//! `--watch`下监听输入文件或目录的变化，供主流程在源文件保存后自动重新翻译。
+//! 编辑器一次保存可能产生多个文件系统事件（写入、改名替换、修改元数据），
+//! 收到变化后等待一段安静期再统一返回，短时间内的多次保存只触发一次翻译
+
+// 标准库导入
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// 第三方crate导入
+use anyhow::{Context, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+/// 默认防抖安静期：最后一次变化后等待该时长没有新变化才重新翻译
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 等待变化时检查停止条件的间隔
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 输入变化监听器
+pub struct ChangeWatcher {
+    /// 底层监听器，需在监听期间保持存活
+    _watcher: Option<RecommendedWatcher>,
+    events: UnboundedReceiver<PathBuf>,
+}
+
+impl ChangeWatcher {
+    /// 监听文件或目录
+    ///
+    /// 监听文件时实际监听其所在目录（编辑器常以「写临时文件再改名」的方式保存，
+    /// 直接监听文件会在首次保存后失效），返回的变化中包含同目录的其它文件，由调用方过滤。
+    pub fn watch(path: &Path) -> Result<Self> {
+        let (sender, events) = unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                for path in event.paths {
+                    let _ = sender.send(path);
+                }
+            }
+        })
+        .context("创建文件监听器失败")?;
+
+        let (target, mode) = if path.is_dir() {
+            (path, RecursiveMode::Recursive)
+        } else {
+            (path.parent().unwrap_or(Path::new(".")), RecursiveMode::NonRecursive)
+        };
+        watcher
+            .watch(target, mode)
+            .with_context(|| format!("监听失败: {}", target.display()))?;
+
+        Ok(Self {
+            _watcher: Some(watcher),
+            events,
+        })
+    }
+
+    /// 从事件通道创建，不监听真实文件系统
+    pub fn from_receiver(events: UnboundedReceiver<PathBuf>) -> Self {
+        Self { _watcher: None, events }
+    }
+
+    /// 等待下一批变化
+    ///
+    /// 收到首个变化后持续合并后续变化，直到`debounce`内没有新变化才返回；
+    /// 等待期间`stop`返回true或监听器关闭时返回`None`。
+    pub async fn next_changes(&mut self, debounce: Duration, stop: impl Fn() -> bool) -> Option<BTreeSet<PathBuf>> {
+        let first = loop {
+            if stop() {
+                return None;
+            }
+            match tokio::time::timeout(STOP_POLL_INTERVAL, self.events.recv()).await {
+                Ok(Some(path)) => break path,
+                Ok(None) => return None,
+                Err(_) => continue,
+            }
+        };
+
+        let mut changes = BTreeSet::from([first]);
+        while let Ok(Some(path)) = tokio::time::timeout(debounce, self.events.recv()).await {
+            changes.insert(path);
+        }
+        Some(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rapid_saves_trigger_once() {
+        let (sender, events) = unbounded_channel();
+        let mut watcher = ChangeWatcher::from_receiver(events);
+
+        // 安静期内的多次保存合并为一批
+        let page = PathBuf::from("site/page.html");
+        for _ in 0..3 {
+            sender.send(page.clone()).unwrap();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        sender.send(PathBuf::from("site/about.html")).unwrap();
+        let changes = watcher.next_changes(Duration::from_millis(100), || false).await.unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(changes.contains(&page));
+
+        // 安静期之后的保存属于下一批
+        sender.send(page.clone()).unwrap();
+        let changes = watcher.next_changes(Duration::from_millis(100), || false).await.unwrap();
+        assert_eq!(changes, BTreeSet::from([page]));
+
+        assert!(watcher.next_changes(Duration::from_millis(100), || true).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_watch_detects_file_change() {
+        let dir = std::env::temp_dir().join(format!("translation-cli-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("page.html");
+        std::fs::write(&file, "<p>Hello</p>").unwrap();
+
+        let mut watcher = ChangeWatcher::watch(&file).unwrap();
+        std::fs::write(&file, "<p>Hello again</p>").unwrap();
+        let changes = tokio::time::timeout(
+            Duration::from_secs(5),
+            watcher.next_changes(Duration::from_millis(100), || false),
+        )
+        .await
+        .expect("未收到文件变化")
+        .unwrap();
+        assert!(changes.iter().any(|path| path.file_name() == file.file_name()));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
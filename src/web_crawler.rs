@@ -7,11 +7,21 @@
 
 // 标准库导入
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 // 第三方crate导入
 use anyhow::{Context, Result};
+use regex::Regex;
 use tracing::{debug, info, warn};
 
+// 本地模块导入
+use crate::api_constants::crawler_config;
+use crate::error::{NetworkErrorKind, TranslationError};
+use crate::page_cache::{CachedPage, PageCache};
+use crate::rate_limiter::HostThrottle;
+use crate::translator::shared_http_client;
+
 /// Web爬虫配置结构体
 #[derive(Debug, Clone)]
 pub struct WebCrawlerConfig {
@@ -25,10 +35,31 @@ pub struct WebCrawlerConfig {
     pub include_js: bool,
     /// 是否包含图片资源
     pub include_images: bool,
+    /// 是否保留并内联CSS中的@font-face字体
+    pub embed_fonts: bool,
     /// 用户代理字符串
     pub user_agent: String,
     /// 连接超时时间（秒）
     pub timeout: u64,
+    /// 最大并发资源下载数
+    ///
+    /// Monolith在内联阶段逐个串行抓取子资源，未暴露并发控制，爬取本身不使用该值；
+    /// 它只限制本工具自行发起的资源请求（`--embed-fonts`内联字体）的并发数。
+    pub max_resource_concurrency: usize,
+    /// 爬取缓存，仅在只抓取HTML文档时用于条件请求
+    pub page_cache: Option<PageCache>,
+    /// 同主机请求之间的礼貌延迟
+    ///
+    /// 作用于本工具自行发起的请求（HTML文档、失败诊断、字体内联）；Monolith内部的
+    /// 子资源抓取不经过该节流器。
+    pub host_throttle: Option<Arc<HostThrottle>>,
+}
+
+impl WebCrawlerConfig {
+    /// 是否只抓取HTML文档本身（不内联任何子资源）
+    pub fn is_html_only(&self) -> bool {
+        !self.include_css && !self.include_js && !self.include_images && !self.embed_fonts
+    }
 }
 
 impl Default for WebCrawlerConfig {
@@ -39,8 +70,12 @@ impl Default for WebCrawlerConfig {
             include_css: true,
             include_js: false, // 默认不包含JS，避免潜在的安全问题
             include_images: true,
+            embed_fonts: true,
             user_agent: "translation-cli/0.1.0 (Monolith Web Crawler)".to_string(),
             timeout: 30,
+            max_resource_concurrency: crawler_config::DEFAULT_MAX_RESOURCE_CONCURRENCY,
+            page_cache: None,
+            host_throttle: None,
         }
     }
 }
@@ -61,6 +96,13 @@ pub struct WebCrawler {
 }
 
 impl WebCrawler {
+    /// 对同一主机的连续请求插入礼貌延迟
+    async fn throttle(&self) {
+        if let Some(throttle) = &self.config.host_throttle {
+            throttle.acquire(&self.config.url).await;
+        }
+    }
+
     /// 使用Monolith库进行实际的网页爬取
     async fn crawl_website(&self) -> Result<String> {
         let config = &self.config;
@@ -70,6 +112,7 @@ impl WebCrawler {
             no_css: !config.include_css,
             no_js: !config.include_js,
             no_images: !config.include_images,
+            no_fonts: !config.embed_fonts,
             user_agent: Some(config.user_agent.clone()),
             timeout: config.timeout,
             ignore_errors: false,
@@ -82,15 +125,34 @@ impl WebCrawler {
 
         let target_url = config.url.clone();
 
+        // 只抓取HTML时自行下载文档，以便检查内容类型并利用爬取缓存发送条件请求
+        let prefetched = if config.is_html_only() {
+            self.fetch_html_document(config.page_cache.as_ref()).await?
+        } else {
+            None
+        };
+        if prefetched.is_none() {
+            self.throttle().await;
+        }
+
         // 在blocking线程中执行monolith操作
         let result = tokio::task::spawn_blocking(move || {
-            use monolith::core::create_monolithic_document;
+            use monolith::core::{create_monolithic_document, create_monolithic_document_from_data};
             use monolith::cache::Cache;
             
             // 创建缓存，设置最小文件大小为0，不使用磁盘缓存文件
             let mut cache: Option<Cache> = Some(Cache::new(0, None));
             
-            create_monolithic_document(target_url, &mut options, &mut cache)
+            match prefetched {
+                Some(page) => create_monolithic_document_from_data(
+                    page.body,
+                    &options,
+                    &mut cache,
+                    page.charset,
+                    Some(page.final_url),
+                ),
+                None => create_monolithic_document(target_url, &mut options, &mut cache),
+            }
         })
         .await
         .with_context(|| "Monolith任务执行失败")?;
@@ -108,9 +170,134 @@ impl WebCrawler {
                 Ok(html_content)
             }
             Err(e) => {
-                anyhow::bail!("Monolith爬取失败: {}", e);
+                let network_error = self.diagnose_crawl_failure(&e.to_string()).await;
+                Err(network_error.into())
+            }
+        }
+    }
+
+    /// 在Monolith失败后使用reqwest探测目标地址，得到可分类的网络错误
+    ///
+    /// Monolith只返回笼统的错误描述（如"could not retrieve target document"），
+    /// 这里重新请求一次以获取HTTP状态码和错误类别（超时/DNS/TLS/连接失败）。
+    async fn diagnose_crawl_failure(&self, monolith_error: &str) -> TranslationError {
+        let mut builder = reqwest::Client::builder().user_agent(&self.config.user_agent);
+        if self.config.timeout > 0 {
+            builder = builder.timeout(Duration::from_secs(self.config.timeout));
+        }
+        let client = match builder.build() {
+            Ok(client) => client,
+            Err(e) => return e.into(),
+        };
+
+        self.throttle().await;
+        match client.get(&self.config.url).send().await {
+            Ok(response) if !response.status().is_success() => TranslationError::Network {
+                message: format!("Monolith爬取失败: {} (HTTP {})", monolith_error, response.status()),
+                status_code: Some(response.status().as_u16()),
+                kind: NetworkErrorKind::Http,
+            },
+            Ok(_) => TranslationError::Network {
+                message: format!("Monolith爬取失败: {}", monolith_error),
+                status_code: None,
+                kind: NetworkErrorKind::Other,
+            },
+            Err(e) => TranslationError::Network {
+                message: format!("Monolith爬取失败: {}: {}", monolith_error, format_error_chain(&e)),
+                status_code: e.status().map(|s| s.as_u16()),
+                kind: NetworkErrorKind::from_reqwest(&e),
+            },
+        }
+    }
+
+    /// 下载HTML文档，启用爬取缓存时使用其中的`ETag`/`Last-Modified`发送条件请求
+    ///
+    /// 服务器返回304时复用缓存内容，返回新内容时更新缓存。响应声明的`Content-Type`
+    /// 不是`text/html`/`application/xhtml+xml`时返回错误，避免把PDF、图片等当作HTML翻译；
+    /// 请求失败等其它情况返回`None`，由Monolith按常规流程抓取并诊断错误。
+    async fn fetch_html_document(&self, page_cache: Option<&PageCache>) -> Result<Option<CachedPage>> {
+        use reqwest::header::{
+            CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT,
+        };
+
+        let url = &self.config.url;
+        let cached = page_cache.and_then(|cache| cache.get(url));
+        let Ok(client) = shared_http_client() else {
+            return Ok(None);
+        };
+        let mut request = client
+            .get(url)
+            .header(USER_AGENT, &self.config.user_agent)
+            .timeout(Duration::from_secs(self.config.timeout));
+        if let Some(page) = &cached {
+            if let Some(etag) = &page.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &page.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        self.throttle().await;
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                debug!("请求HTML文档失败，交由Monolith抓取: {}", e);
+                return Ok(None);
             }
+        };
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if cached.is_some() {
+                info!("♻️ 页面未修改 (304)，使用爬取缓存: {}", url);
+            }
+            return Ok(cached);
+        }
+        if !response.status().is_success() {
+            return Ok(None);
         }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let Some(content_type) = header(CONTENT_TYPE) else {
+            return Ok(None);
+        };
+        let (media_type, charset, _) = monolith::core::parse_content_type(&content_type);
+        if !media_type.eq_ignore_ascii_case("text/html")
+            && !media_type.eq_ignore_ascii_case("application/xhtml+xml")
+        {
+            return Err(TranslationError::InputValidation {
+                input: url.clone(),
+                reason: format!("目标不是 HTML 页面（实际类型：{}）", media_type),
+            }
+            .into());
+        }
+
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let final_url = response.url().to_string();
+        let Ok(body) = response.bytes().await else {
+            return Ok(None);
+        };
+        let page = CachedPage {
+            body: body.to_vec(),
+            charset: Some(charset),
+            final_url,
+            etag,
+            last_modified,
+        };
+
+        if let Some(page_cache) = page_cache.filter(|_| page.has_validators()) {
+            if let Err(e) = page_cache.put(url, &page) {
+                warn!("⚠️ 写入爬取缓存失败: {:#}", e);
+            }
+        }
+        Ok(Some(page))
     }
 
     /// 带重试机制的网页爬取
@@ -130,7 +317,16 @@ impl WebCrawler {
                 }
                 Err(e) => {
                     warn!("❌ 爬取失败 (尝试 {}/{}): {}", attempt, MAX_RETRIES, e);
+                    let retryable = e
+                        .downcast_ref::<TranslationError>()
+                        .map(|err| err.is_retryable())
+                        .unwrap_or(true);
                     last_error = Some(e);
+
+                    if !retryable {
+                        warn!("⛔ 错误不可重试，停止爬取");
+                        break;
+                    }
                     
                     if attempt < MAX_RETRIES {
                         let delay = std::time::Duration::from_secs(attempt as u64 * 2);
@@ -150,8 +346,10 @@ impl WebCrawler {
 
     /// 使用默认配置创建Web爬虫
     pub fn with_url(url: &str) -> Self {
-        let mut config = WebCrawlerConfig::default();
-        config.url = url.to_string();
+        let config = WebCrawlerConfig {
+            url: url.to_string(),
+            ..Default::default()
+        };
         Self::new(config)
     }
 
@@ -169,6 +367,12 @@ impl WebCrawler {
         self
     }
 
+    /// 设置是否保留并内联字体
+    pub fn embed_fonts(mut self, embed: bool) -> Self {
+        self.config.embed_fonts = embed;
+        self
+    }
+
     /// 设置用户代理
     pub fn user_agent(mut self, user_agent: &str) -> Self {
         self.config.user_agent = user_agent.to_string();
@@ -181,6 +385,29 @@ impl WebCrawler {
         self
     }
 
+    /// 设置最大并发资源下载数（至少为1）
+    pub fn max_resource_concurrency(mut self, limit: usize) -> Self {
+        self.config.max_resource_concurrency = limit.max(1);
+        self
+    }
+
+    /// 设置单次爬取中同一主机连续请求之间的礼貌延迟，零表示不延迟
+    pub fn crawl_delay(mut self, delay: Duration) -> Self {
+        self.config.host_throttle = (!delay.is_zero()).then(|| Arc::new(HostThrottle::new(delay)));
+        self
+    }
+
+    /// 设置爬取缓存（只抓取HTML时生效）
+    pub fn page_cache(mut self, cache: Option<PageCache>) -> Self {
+        self.config.page_cache = cache;
+        self
+    }
+
+    /// 获取爬虫配置
+    pub fn config(&self) -> &WebCrawlerConfig {
+        &self.config
+    }
+
     /// 执行网页爬取
     /// 
     /// 返回爬取的HTML内容字符串和输出文件的路径
@@ -275,6 +502,109 @@ impl WebCrawler {
     }
 }
 
+/// 拼接错误及其底层原因，便于定位具体网络问题
+fn format_error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+    message
+}
+
+/// 检查页面的`<meta name="robots">`是否声明了`noindex`（或等价的`none`）
+///
+/// 只检查面向所有爬虫的`robots`，不处理`googlebot`等特定爬虫的声明。
+pub fn robots_noindex(html: &str) -> bool {
+    static META: OnceLock<Regex> = OnceLock::new();
+    static ATTR: OnceLock<Regex> = OnceLock::new();
+    let meta_regex = META.get_or_init(|| Regex::new(r"(?is)<meta\b[^>]*>").expect("meta正则无效"));
+    let attr_regex = ATTR.get_or_init(|| {
+        Regex::new(r#"(?is)\b(name|content)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).expect("属性正则无效")
+    });
+
+    let noindex = meta_regex.find_iter(html).any(|meta| {
+        let mut name = None;
+        let mut content = None;
+        for captures in attr_regex.captures_iter(meta.as_str()) {
+            let value = captures
+                .get(2)
+                .or_else(|| captures.get(3))
+                .or_else(|| captures.get(4))
+                .map(|m| m.as_str().trim().to_ascii_lowercase());
+            match captures[1].to_ascii_lowercase().as_str() {
+                "name" => name = value,
+                _ => content = value,
+            }
+        }
+        name.as_deref() == Some("robots")
+            && content.is_some_and(|content| {
+                content
+                    .split(',')
+                    .map(str::trim)
+                    .any(|directive| directive == "noindex" || directive == "none")
+            })
+    });
+    noindex
+}
+
+/// 某一类型内联资源的统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InlineResourceStats {
+    /// 资源个数
+    pub count: usize,
+    /// 解码后的总字节数（base64按3/4估算）
+    pub bytes: usize,
+}
+
+/// 页面中以data URI内联的资源清单，按MIME类型分组
+///
+/// Monolith的缓存不对外暴露抓取过的URL，无法列出每个外部资源的来源；
+/// 内联后的资源都以data URI形式留在页面中，统计它们即可判断CSS、图片、字体是否内联完整。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InlineResourceInventory {
+    /// MIME类型 → 统计，未声明类型的data URI记为`text/plain`
+    pub by_type: std::collections::BTreeMap<String, InlineResourceStats>,
+}
+
+impl InlineResourceInventory {
+    /// 内联资源总数
+    pub fn total_count(&self) -> usize {
+        self.by_type.values().map(|stats| stats.count).sum()
+    }
+
+    /// 内联资源总字节数
+    pub fn total_bytes(&self) -> usize {
+        self.by_type.values().map(|stats| stats.bytes).sum()
+    }
+}
+
+/// 统计HTML中（属性、内联样式、`<style>`中的`url()`）内联的data URI
+pub fn inline_resource_inventory(html: &str) -> InlineResourceInventory {
+    let data_uri_regex = Regex::new(r#"(?i)data:([a-z0-9.+-]+/[a-z0-9.+-]+)?((?:;[^,;"'\s)]*)*),([^"'\s)]*)"#)
+        .expect("data URI正则无效");
+
+    let mut inventory = InlineResourceInventory::default();
+    for captures in data_uri_regex.captures_iter(html) {
+        let media_type = captures
+            .get(1)
+            .map_or_else(|| "text/plain".to_string(), |m| m.as_str().to_ascii_lowercase());
+        let payload_len = captures[3].len();
+        let bytes = if captures[2].to_ascii_lowercase().contains(";base64") {
+            payload_len / 4 * 3
+        } else {
+            payload_len
+        };
+
+        let stats = inventory.by_type.entry(media_type).or_default();
+        stats.count += 1;
+        stats.bytes += bytes;
+    }
+    inventory
+}
+
 /// 便捷函数：快速爬取网页到指定路径
 pub async fn crawl_url_to_file<P: AsRef<Path>>(
     url: &str,
@@ -292,6 +622,7 @@ pub async fn crawl_url_to_string(url: &str) -> Result<String> {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -302,6 +633,7 @@ mod tests {
         assert_eq!(config.include_css, true);
         assert_eq!(config.include_js, false);
         assert_eq!(config.include_images, true);
+        assert!(config.embed_fonts);
         assert_eq!(config.timeout, 30);
         assert_eq!(config.user_agent, "translation-cli/0.1.0 (Monolith Web Crawler)");
     }
@@ -436,4 +768,110 @@ mod tests {
         let prepared_path = crawler.prepare_output_path().unwrap();
         assert_eq!(prepared_path, output_path);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_html_only_crawl_uses_conditional_request() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let not_modified_count = Arc::new(AtomicUsize::new(0));
+        let server_count = not_modified_count.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = vec![0u8; 4096];
+                let read = socket.read(&mut buffer).await.unwrap();
+                let request = String::from_utf8_lossy(&buffer[..read]).to_lowercase();
+                let response = if request.contains("if-none-match: \"v1\"") {
+                    server_count.fetch_add(1, Ordering::SeqCst);
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_string()
+                } else {
+                    let body = "<html><body><p>Cached page</p></body></html>";
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                };
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let cache_dir = std::env::temp_dir().join("translation-cli-crawl-cache-test");
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let crawler = WebCrawler::with_url(&format!("http://{}/page", address))
+            .include_resources(false, false, false)
+            .embed_fonts(false)
+            .page_cache(Some(PageCache::new(&cache_dir)));
+        assert!(crawler.config().is_html_only());
+
+        let first = crawler.crawl_website().await.unwrap();
+        let second = crawler.crawl_website().await.unwrap();
+        assert!(first.contains("Cached page"));
+        assert!(second.contains("Cached page"));
+        assert_eq!(not_modified_count.load(Ordering::SeqCst), 1);
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_html_only_crawl_rejects_non_html() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buffer = vec![0u8; 4096];
+                let _ = socket.read(&mut buffer).await;
+                let body = "%PDF-1.7 binary";
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/pdf\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let crawler = WebCrawler::with_url(&format!("http://{}/report.pdf", address))
+            .include_resources(false, false, false)
+            .embed_fonts(false);
+        let error = crawler.crawl_website_with_retry().await.unwrap_err();
+        assert!(error.to_string().contains("目标不是 HTML 页面（实际类型：application/pdf）"));
+        // 内容类型错误不可重试
+        assert!(!error.downcast_ref::<TranslationError>().unwrap().is_retryable());
+    }
+
+    #[test]
+    fn test_robots_noindex() {
+        assert!(robots_noindex(r#"<head><meta name="robots" content="noindex,nofollow"></head>"#));
+        assert!(robots_noindex(r#"<META CONTENT="NoFollow, NoIndex" NAME="Robots">"#));
+        assert!(robots_noindex("<meta name=robots content=none>"));
+
+        assert!(!robots_noindex(r#"<meta name="robots" content="index,follow">"#));
+        assert!(!robots_noindex(r#"<meta name="googlebot" content="noindex">"#));
+        assert!(!robots_noindex(r#"<meta name="description" content="noindex pages">"#));
+        assert!(!robots_noindex("<html><body>noindex</body></html>"));
+    }
+
+    #[test]
+    fn test_inline_resource_inventory() {
+        let html = r#"<html><head><style>@font-face { src: url(data:font/woff2;base64,AAAABBBB); }
+body { background: url("data:image/png;base64,iVBORw0KGgo=") }</style></head>
+<body><img src="data:image/png;base64,AAAA"><img src='data:image/svg+xml,%3Csvg%3E'>
+<link rel="stylesheet" href="data:text/css;charset=utf-8;base64,Ym9keXt9"><p>data: not a uri</p></body></html>"#;
+
+        let inventory = inline_resource_inventory(html);
+        assert_eq!(inventory.by_type["image/png"], InlineResourceStats { count: 2, bytes: 9 + 3 });
+        assert_eq!(inventory.by_type["font/woff2"], InlineResourceStats { count: 1, bytes: 6 });
+        assert_eq!(inventory.by_type["image/svg+xml"], InlineResourceStats { count: 1, bytes: 9 });
+        assert_eq!(inventory.by_type["text/css"], InlineResourceStats { count: 1, bytes: 6 });
+        assert_eq!(inventory.total_count(), 5);
+        assert_eq!(inventory.total_bytes(), 12 + 6 + 9 + 6);
+        assert_eq!(inline_resource_inventory("<p>plain</p>"), InlineResourceInventory::default());
+    }
+}